@@ -0,0 +1,165 @@
+//! An interactive debugger for the kernel.
+//!
+//! Usage: `debug_kernel <generation_inputs.json> [start_label]`
+//!
+//! The inputs file should contain a JSON-serialized `GenerationInputs`. Type `help` at the prompt
+//! for the list of commands.
+
+use std::io::{self, BufRead, Write};
+use std::{env, fs};
+
+use anyhow::{bail, Context};
+use plonky2_evm::all_stark::Table;
+use plonky2_evm::cpu::kernel::debugger::{parse_segment, KernelDebugger, StopReason};
+use plonky2_evm::generation::GenerationInputs;
+
+const HELP: &str = "\
+Commands:
+  break <label>        Add a breakpoint at a global label
+  delete <label>       Remove the breakpoint at a global label
+  breakpoints          List breakpoints
+  step [n]             Execute n instructions (default 1)
+  continue [n]         Run until a breakpoint, halt, or n instructions
+  where                Print the current location
+  stack                Print the stack, top first
+  mem <segment> [ctx]  Print a memory segment, e.g. `mem SEGMENT_KERNEL_GENERAL 0`
+  ctx [ctx]            Print the metadata of a context (default: current context)
+  global               Print the global metadata
+  journal              Print the journal entries
+  profile [file]       Start profiling, or write the profile as collapsed stacks
+  help                 Print this message
+  quit                 Exit the debugger";
+
+fn main() -> anyhow::Result<()> {
+    let mut args = env::args();
+    args.next();
+    let Some(inputs_path) = args.next() else {
+        bail!("Usage: debug_kernel <generation_inputs.json> [start_label]");
+    };
+    let start_label = args.next().unwrap_or_else(|| "main".to_string());
+
+    let inputs_json = fs::read_to_string(&inputs_path)
+        .with_context(|| format!("Unable to read {inputs_path}"))?;
+    let inputs: GenerationInputs =
+        serde_json::from_str(&inputs_json).context("Unable to parse generation inputs")?;
+    let mut debugger = KernelDebugger::new_at_label(&start_label, vec![], inputs)?;
+    println!("Stopped at {}", debugger.location());
+
+    let stdin = io::stdin();
+    loop {
+        print!("(kdb) ");
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&command, command_args)) = words.split_first() else {
+            continue;
+        };
+        if matches!(command, "quit" | "q") {
+            return Ok(());
+        }
+        if let Err(err) = run_command(&mut debugger, command, command_args) {
+            println!("Error: {err:#}");
+        }
+    }
+}
+
+fn run_command(debugger: &mut KernelDebugger, command: &str, args: &[&str]) -> anyhow::Result<()> {
+    let count_arg = || {
+        args.first()
+            .map(|n| n.parse::<usize>().context("Invalid instruction count"))
+            .transpose()
+    };
+
+    match command {
+        "break" | "b" => {
+            let label = args.first().context("Missing label")?;
+            let offset = debugger.add_breakpoint(label)?;
+            println!("Breakpoint at {label} ({offset})");
+        }
+        "delete" | "d" => {
+            let label = args.first().context("Missing label")?;
+            if !debugger.remove_breakpoint(label)? {
+                println!("No breakpoint at {label}");
+            }
+        }
+        "breakpoints" => {
+            for location in debugger.breakpoints() {
+                println!("{location}");
+            }
+        }
+        "step" | "s" => {
+            let n = count_arg()?.unwrap_or(1);
+            for _ in 0..n {
+                if !debugger.step()? {
+                    println!("Halted");
+                    break;
+                }
+            }
+            println!("At {}", debugger.location());
+        }
+        "continue" | "c" => match debugger.continue_execution(count_arg()?)? {
+            StopReason::Breakpoint(location) => println!("Breakpoint at {location}"),
+            StopReason::Halted => println!("Halted"),
+            StopReason::StepLimit => println!("At {}", debugger.location()),
+        },
+        "where" | "w" => println!(
+            "{} (pc={}, context={})",
+            debugger.location(),
+            debugger.program_counter(),
+            debugger.context()
+        ),
+        "stack" => {
+            for (i, value) in debugger.stack().iter().rev().enumerate() {
+                println!("{i:>4}: {value:#x}");
+            }
+        }
+        "mem" => {
+            let name = args.first().context("Missing segment")?;
+            let segment =
+                parse_segment(name).with_context(|| format!("No such segment: {name}"))?;
+            let context = match args.get(1) {
+                Some(ctx) => ctx.parse().context("Invalid context")?,
+                None => debugger.context(),
+            };
+            for (i, value) in debugger.memory_segment(context, segment).iter().enumerate() {
+                println!("{i:>6}: {value:#x}");
+            }
+        }
+        "ctx" => {
+            let context = match args.first() {
+                Some(ctx) => ctx.parse().context("Invalid context")?,
+                None => debugger.context(),
+            };
+            for (name, value) in debugger.context_metadata(context) {
+                println!("{name}: {value:#x}");
+            }
+        }
+        "global" => {
+            for (name, value) in debugger.global_metadata() {
+                println!("{name}: {value:#x}");
+            }
+        }
+        "journal" => {
+            for (i, entry) in debugger.journal()?.iter().enumerate() {
+                let fields: Vec<String> = entry.fields.iter().map(|f| format!("{f:#x}")).collect();
+                println!("{i:>4}: {} [{}]", entry.entry_type, fields.join(", "));
+            }
+        }
+        "profile" => match args.first() {
+            None => debugger.enable_profiling(),
+            Some(path) => {
+                let profile = debugger
+                    .take_profile()
+                    .context("Profiling is not enabled")?;
+                let mut file = fs::File::create(path)?;
+                profile.write_collapsed_stacks(Table::Cpu, &mut file)?;
+            }
+        },
+        "help" | "h" => println!("{HELP}"),
+        _ => bail!("Unknown command {command:?}, type `help` for the list of commands"),
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! Runs the `GeneralStateTests` of https://github.com/ethereum/tests.
+//!
+//! Usage: `state_tests [--prove] [--fork <fork>] [--filter <name>] <path>`
+//!
+//! The path can be a single test file, or a directory which is searched recursively for test
+//! files. By default, transactions are only executed; with `--prove`, a proof is also generated
+//! and verified for each of them. `--fork` only runs the cases of the given fork, and `--filter`
+//! only the tests whose name contains the given string.
+
+use std::path::PathBuf;
+use std::{env, io, process};
+
+use anyhow::{bail, Context};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::plonk::config::KeccakGoldilocksConfig;
+use plonky2_evm::all_stark::AllStark;
+use plonky2_evm::config::StarkConfig;
+use plonky2_evm::state_tests::{load_state_tests, state_test_files, Outcome, StateTestReport};
+
+type F = GoldilocksField;
+const D: usize = 2;
+type C = KeccakGoldilocksConfig;
+
+const USAGE: &str = "Usage: state_tests [--prove] [--fork <fork>] [--filter <name>] <path>";
+
+fn main() -> anyhow::Result<()> {
+    let mut prove = false;
+    let mut fork = None;
+    let mut filter = None;
+    let mut path = None;
+
+    let mut args = env::args();
+    args.next();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--prove" => prove = true,
+            "--fork" => fork = Some(args.next().context(USAGE)?),
+            "--filter" => filter = Some(args.next().context(USAGE)?),
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => bail!(USAGE),
+        }
+    }
+    let Some(path) = path else {
+        bail!(USAGE);
+    };
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+    let prover = prove.then_some((&all_stark, &config));
+
+    let mut report = StateTestReport::default();
+    for file in state_test_files(&path).with_context(|| format!("Unable to list {path:?}"))? {
+        let cases = match load_state_tests(&file) {
+            Ok(cases) => cases,
+            Err(err) => {
+                println!("FAIL {}: {err:#}", file.display());
+                report.record_invalid_file(&file, &err);
+                continue;
+            }
+        };
+        for case in cases {
+            if fork.as_ref().is_some_and(|fork| *fork != case.fork)
+                || filter
+                    .as_ref()
+                    .is_some_and(|name| !case.name.contains(name))
+            {
+                continue;
+            }
+            let outcome = case.run::<F, C, D>(prover);
+            let status = match &outcome {
+                Outcome::Passed => "PASS",
+                Outcome::Failed(_) => "FAIL",
+                Outcome::Skipped(_) => "SKIP",
+            };
+            println!("{status} {} [{}] ({})", case.name, case.indexes, case.fork);
+            report.record(&case, outcome);
+        }
+    }
+
+    println!();
+    report.write_summary(&mut io::stdout())?;
+    if report.num_failed() > 0 {
+        process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! Verifies a standalone proof file, as written by `ProofFile::to_json` or `ProofFile::to_bytes`,
+//! and prints its public values as JSON.
+//!
+//! Usage: `verify_proof [--poseidon] <proof file>`
+//!
+//! Proofs are expected to use `StarkConfig::standard_fast_config()`, and `KeccakGoldilocksConfig`,
+//! or `PoseidonGoldilocksConfig` with `--poseidon`.
+
+use std::env;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::plonk::config::{GenericConfig, KeccakGoldilocksConfig, PoseidonGoldilocksConfig};
+use plonky2_evm::all_stark::AllStark;
+use plonky2_evm::config::StarkConfig;
+use plonky2_evm::proof_file::ProofFile;
+
+type F = GoldilocksField;
+const D: usize = 2;
+
+const USAGE: &str = "Usage: verify_proof [--poseidon] <proof file>";
+
+fn main() -> anyhow::Result<()> {
+    let mut poseidon = false;
+    let mut path = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--poseidon" => poseidon = true,
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(PathBuf::from(arg)),
+            _ => bail!(USAGE),
+        }
+    }
+    let Some(path) = path else {
+        bail!(USAGE);
+    };
+
+    if poseidon {
+        verify::<PoseidonGoldilocksConfig>(path)
+    } else {
+        verify::<KeccakGoldilocksConfig>(path)
+    }
+}
+
+fn verify<C: GenericConfig<D, F = F>>(path: PathBuf) -> anyhow::Result<()> {
+    let proof_file = ProofFile::<F, C, D>::load(&path)?;
+    let all_stark = AllStark::<F, D>::default();
+    let public_values = proof_file
+        .verify(&all_stark, &StarkConfig::standard_fast_config())
+        .with_context(|| format!("Invalid proof in {:?}", path))?;
+
+    eprintln!("Valid proof");
+    println!("{}", serde_json::to_string_pretty(&public_values)?);
+    Ok(())
+}
@@ -57,7 +57,7 @@ use crate::stark::Stark;
 use crate::witness::memory::MemoryAddress;
 
 /// Strict upper bound for the individual bytes range-check.
-const BYTE_RANGE_MAX: usize = 1usize << 8;
+pub(crate) const BYTE_RANGE_MAX: usize = 1usize << 8;
 
 pub(crate) fn ctl_looked_data<F: Field>() -> Vec<Column<F>> {
     // Reconstruct the u32 limbs composing the final `U256` word
@@ -1,6 +1,10 @@
 use plonky2::fri::reduction_strategies::FriReductionStrategy;
 use plonky2::fri::{FriConfig, FriParams};
+use serde::{Deserialize, Serialize};
 
+/// The parameters of the STARK proofs. The prover-side settings `threads_per_table` and
+/// `memory_budget` don't affect proofs, and aren't serialized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StarkConfig {
     pub security_bits: usize,
 
@@ -9,6 +13,19 @@ pub struct StarkConfig {
     pub num_challenges: usize,
 
     pub fri_config: FriConfig,
+
+    /// The maximum number of threads used to generate, commit to and prove each table's trace.
+    /// Since tables are processed concurrently, this keeps a single large table from taking over
+    /// the global thread pool. `None` lets each table use the whole global pool.
+    #[serde(skip)]
+    pub threads_per_table: Option<usize>,
+
+    /// A memory budget, in bytes, for proving the tables. If set, the prover only keeps the Merkle
+    /// caps of the trace commitments, recomputes each commitment when proving its table, and only
+    /// proves concurrently as many tables as fit in the budget. This trades extra FFTs for a lower
+    /// peak memory usage. The budget doesn't include the traces themselves.
+    #[serde(skip)]
+    pub memory_budget: Option<usize>,
 }
 
 impl StarkConfig {
@@ -25,6 +42,8 @@ impl StarkConfig {
                 reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
                 num_query_rounds: 84,
             },
+            threads_per_table: None,
+            memory_budget: None,
         }
     }
 
@@ -9,6 +9,7 @@ pub(crate) union CpuGeneralColumnsView<T: Copy> {
     exception: CpuExceptionView<T>,
     logic: CpuLogicView<T>,
     jumps: CpuJumpsView<T>,
+    push0: CpuPush0View<T>,
     shift: CpuShiftView<T>,
     stack: CpuStackView<T>,
 }
@@ -44,6 +45,16 @@ impl<T: Copy> CpuGeneralColumnsView<T> {
         unsafe { &mut self.jumps }
     }
 
+    // SAFETY: Each view is a valid interpretation of the underlying array.
+    pub(crate) fn push0(&self) -> &CpuPush0View<T> {
+        unsafe { &self.push0 }
+    }
+
+    // SAFETY: Each view is a valid interpretation of the underlying array.
+    pub(crate) fn push0_mut(&mut self) -> &mut CpuPush0View<T> {
+        unsafe { &mut self.push0 }
+    }
+
     // SAFETY: Each view is a valid interpretation of the underlying array.
     pub(crate) fn shift(&self) -> &CpuShiftView<T> {
         unsafe { &self.shift }
@@ -114,6 +125,13 @@ pub(crate) struct CpuJumpsView<T: Copy> {
     pub(crate) cond_sum_pinv: T,
 }
 
+#[derive(Copy, Clone)]
+pub(crate) struct CpuPush0View<T: Copy> {
+    // `hardfork - Shanghai`, which must be a bit for `PUSH0` to be valid in user mode. It doesn't
+    // overlap with the columns of `CpuStackView` used by the push.
+    pub(crate) shanghai_offset: T,
+}
+
 #[derive(Copy, Clone)]
 pub(crate) struct CpuShiftView<T: Copy> {
     // For a shift amount of displacement: [T], this is the inverse of
@@ -42,6 +42,7 @@ pub(crate) fn combined_kernel() -> Kernel {
         include_str!("asm/core/access_lists.asm"),
         include_str!("asm/core/log.asm"),
         include_str!("asm/core/selfdestruct_list.asm"),
+        include_str!("asm/core/created_contracts.asm"),
         include_str!("asm/core/touched_addresses.asm"),
         include_str!("asm/core/precompiles/main.asm"),
         include_str!("asm/core/precompiles/ecrec.asm"),
@@ -143,8 +143,10 @@ after_constructor:
     // stack: new_ctx, leftover_gas, success, address, kexit_info
     POP
 
-    // EIP-3541: Reject new contract code starting with the 0xEF byte
-    PUSH 0 %mload_current(@SEGMENT_RETURNDATA) %eq_const(0xEF) %jumpi(create_first_byte_ef)
+    // EIP-3541: Reject new contract code starting with the 0xEF byte, since London.
+    PUSH 0 %mload_current(@SEGMENT_RETURNDATA) %eq_const(0xEF)
+    %is_hardfork_at_least(@HARDFORK_LONDON) MUL // Cheaper than AND
+    %jumpi(create_first_byte_ef)
 
     // Charge gas for the code size.
     // stack: leftover_gas, success, address, kexit_info
@@ -254,14 +256,21 @@ global set_codehash:
     // stack: retdest
     JUMP
 
-// Check and charge gas cost for initcode size. See EIP-3860.
+// Check and charge gas cost for initcode size, since Shanghai. See EIP-3860.
 // Pre stack: code_size, kexit_info
 // Post stack: kexit_info
 %macro check_initcode_size
+    %is_hardfork_at_least(@HARDFORK_SHANGHAI) ISZERO %jumpi(%%before_shanghai)
     DUP1 %gt_const(@MAX_INITCODE_SIZE) %jumpi(fault_exception)
     // stack: code_size, kexit_info
     %num_bytes_to_num_words %mul_const(@INITCODE_WORD_COST)
     %charge_gas
+    %jump(%%after)
+%%before_shanghai:
+    // stack: code_size, kexit_info
+    POP
+%%after:
+    // stack: kexit_info
 %endmacro
 
 
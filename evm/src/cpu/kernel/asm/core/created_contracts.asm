@@ -0,0 +1,50 @@
+/// List of contracts created in the current transaction, which SELFDESTRUCT may delete (EIP-6780).
+/// Implemented as an array, with the length stored in the global metadata.
+/// Note: This array allows duplicates.
+///
+/// Entries are not removed when the creation is reverted. This is fine since a SELFDESTRUCT at
+/// such an address can only be executed if a contract is created there again, as a pre-existing
+/// contract would have made the creation fail with a collision.
+
+%macro insert_created_contracts
+    // stack: addr
+    %mload_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN)
+    %stack (len, addr) -> (len, addr, len)
+    %mstore_kernel(@SEGMENT_CREATED_CONTRACTS) // Store new address at the end of the array.
+    // stack: len
+    %increment
+    %mstore_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN) // Store new length.
+%endmacro
+
+/// Returns 1 if the address is in the list, 0 otherwise.
+global contains_created_contracts:
+    // stack: addr, retdest
+    %mload_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN)
+    // stack: len, addr, retdest
+    PUSH 0
+contains_created_contracts_loop:
+    // stack: i, len, addr, retdest
+    DUP2 DUP2 EQ %jumpi(contains_created_contracts_not_found)
+    // stack: i, len, addr, retdest
+    DUP1 %mload_kernel(@SEGMENT_CREATED_CONTRACTS)
+    // stack: loaded_addr, i, len, addr, retdest
+    DUP4
+    // stack: addr, loaded_addr, i, len, addr, retdest
+    EQ %jumpi(contains_created_contracts_found)
+    // stack: i, len, addr, retdest
+    %increment
+    %jump(contains_created_contracts_loop)
+contains_created_contracts_found:
+    %stack (i, len, addr, retdest) -> (retdest, 1)
+    JUMP
+contains_created_contracts_not_found:
+    %stack (i, len, addr, retdest) -> (retdest, 0)
+    JUMP
+
+%macro contains_created_contracts
+    // stack: addr
+    %stack (addr) -> (addr, %%after)
+    %jump(contains_created_contracts)
+%%after:
+    // stack: is_created
+%endmacro
@@ -32,12 +32,20 @@ global exc_invalid_opcode:
     // stack: trap_info
     // check if the opcode that triggered this trap is _actually_ invalid
     %opcode_from_exp_trap_info
+    DUP1
     PUSH @INVALID_OPCODES_USER
-    // stack: invalid_opcodes_user, opcode
+    // stack: invalid_opcodes_user, opcode, opcode
     SWAP1
-    // stack: opcode, invalid_opcodes_user
+    // stack: opcode, invalid_opcodes_user, opcode
     SHR
     %mod_const(2)
+    // stack: opcode_is_invalid, opcode
+    // PUSH0 is only valid from Shanghai onwards.
+    SWAP1
+    %eq_const(0x5f)
+    %is_hardfork_at_least(@HARDFORK_SHANGHAI) ISZERO
+    MUL // Cheaper than AND
+    OR
     // stack: opcode_is_invalid
     // if the opcode is indeed invalid, then perform an exceptional exit
     %jumpi(fault_exception)
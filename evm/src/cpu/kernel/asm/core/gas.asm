@@ -34,6 +34,22 @@ global refund_gas_hook:
     %mstore_global_metadata(@GLOBAL_METADATA_REFUND_COUNTER)
 %endmacro
 
+// The refund for clearing a storage slot, which was reduced by EIP-3529 in London.
+%macro refund_sclear
+    PUSH @REFUND_SCLEAR PUSH @REFUND_SCLEAR_PRE_LONDON
+    // stack: refund_pre_london, refund
+    %is_hardfork_at_least(@HARDFORK_LONDON)
+    %select_bool
+%endmacro
+
+// The refund counter is capped at used_gas / max_refund_quotient, which was changed by EIP-3529 in London.
+%macro max_refund_quotient
+    PUSH @MAX_REFUND_QUOTIENT PUSH @MAX_REFUND_QUOTIENT_PRE_LONDON
+    // stack: quotient_pre_london, quotient
+    %is_hardfork_at_least(@HARDFORK_LONDON)
+    %select_bool
+%endmacro
+
 // TODO: `%charge_gas` and `charge_gas_hook` are hooks used for debugging. They should be removed at some point and `charge_gas_original` renamed to `charge_gas`.
 %macro charge_gas
     PUSH %%after %jump(charge_gas_hook)
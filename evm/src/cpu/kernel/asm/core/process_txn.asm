@@ -6,6 +6,9 @@
 // Pre stack: retdest
 // Post stack: success, leftover_gas
 global process_normalized_txn:
+    // stack: retdest
+    // No contract has been created in this transaction yet.
+    PUSH 0 %mstore_global_metadata(@GLOBAL_METADATA_CREATED_CONTRACTS_LEN)
     // stack: retdest
     %compute_fees
     // stack: retdest
@@ -98,8 +101,9 @@ global warm_precompiles:
     PUSH @SNARKV %insert_accessed_addresses_no_return
     PUSH @BLAKE2_F %insert_accessed_addresses_no_return
 
-// EIP-3651
+// EIP-3651, since Shanghai.
 global warm_coinbase:
+    %is_hardfork_at_least(@HARDFORK_SHANGHAI) ISZERO %jumpi(process_based_on_type)
     %mload_global_metadata(@GLOBAL_METADATA_BLOCK_BENEFICIARY)
     %insert_accessed_addresses_no_return
 
@@ -187,8 +191,10 @@ global process_contract_creation_txn_after_constructor:
 
     ISZERO %jumpi(contract_creation_fault_3)
 
-    // EIP-3541: Reject new contract code starting with the 0xEF byte
-    PUSH 0 %mload_current(@SEGMENT_RETURNDATA) %eq_const(0xEF) %jumpi(contract_creation_fault_3_zero_leftover)
+    // EIP-3541: Reject new contract code starting with the 0xEF byte, since London.
+    PUSH 0 %mload_current(@SEGMENT_RETURNDATA) %eq_const(0xEF)
+    %is_hardfork_at_least(@HARDFORK_LONDON) MUL // Cheaper than AND
+    %jumpi(contract_creation_fault_3_zero_leftover)
 
     // stack: leftover_gas, new_ctx, address, retdest, success
     %returndatasize // Size of the code.
@@ -335,7 +341,7 @@ process_message_txn_fail:
     // stack: used_gas, leftover_gas
     %mload_global_metadata(@GLOBAL_METADATA_REFUND_COUNTER)
     // stack: refund, used_gas, leftover_gas
-    DUP2 %div_const(@MAX_REFUND_QUOTIENT) // max_refund = used_gas/5
+    DUP2 %max_refund_quotient SWAP1 DIV // max_refund = used_gas/quotient
     // stack: max_refund, refund, used_gas, leftover_gas
     %min
     %stack (refund, used_gas, leftover_gas) -> (leftover_gas, refund, refund, used_gas)
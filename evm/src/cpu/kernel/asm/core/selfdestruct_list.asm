@@ -41,6 +41,39 @@ remove_selfdestruct_list_found:
     %mstore_kernel(@SEGMENT_SELFDESTRUCT_LIST) // Store the last address at the position of the removed address.
     JUMP
 
+/// Returns 1 if the address is in the list, 0 otherwise.
+global contains_selfdestruct_list:
+    // stack: addr, retdest
+    %mload_global_metadata(@GLOBAL_METADATA_SELFDESTRUCT_LIST_LEN)
+    // stack: len, addr, retdest
+    PUSH 0
+contains_selfdestruct_list_loop:
+    // stack: i, len, addr, retdest
+    DUP2 DUP2 EQ %jumpi(contains_selfdestruct_list_not_found)
+    // stack: i, len, addr, retdest
+    DUP1 %mload_kernel(@SEGMENT_SELFDESTRUCT_LIST)
+    // stack: loaded_addr, i, len, addr, retdest
+    DUP4
+    // stack: addr, loaded_addr, i, len, addr, retdest
+    EQ %jumpi(contains_selfdestruct_list_found)
+    // stack: i, len, addr, retdest
+    %increment
+    %jump(contains_selfdestruct_list_loop)
+contains_selfdestruct_list_found:
+    %stack (i, len, addr, retdest) -> (retdest, 1)
+    JUMP
+contains_selfdestruct_list_not_found:
+    %stack (i, len, addr, retdest) -> (retdest, 0)
+    JUMP
+
+%macro contains_selfdestruct_list
+    // stack: addr
+    %stack (addr) -> (addr, %%after)
+    %jump(contains_selfdestruct_list)
+%%after:
+    // stack: is_selfdestructed
+%endmacro
+
 global delete_all_selfdestructed_addresses:
     // stack: retdest
     %mload_global_metadata(@GLOBAL_METADATA_SELFDESTRUCT_LIST_LEN)
@@ -77,6 +77,21 @@ global sys_selfdestruct:
     %charge_gas
     %stack (kexit_info, balance, address, recipient) -> (balance, address, recipient, kexit_info)
 
+    // Since EIP-6780 (Cancun), the account is only deleted if it was created in the current
+    // transaction.
+    // stack: balance, address, recipient, kexit_info
+    %is_hardfork_at_least(@HARDFORK_CANCUN) ISZERO %jumpi(sys_selfdestruct_delete)
+    DUP2 %contains_created_contracts
+    ISZERO %jumpi(sys_selfdestruct_balance_only)
+
+sys_selfdestruct_delete:
+    // Before London, each account destroyed in the transaction gives a refund (removed by EIP-3529).
+    // stack: balance, address, recipient, kexit_info
+    %is_hardfork_at_least(@HARDFORK_LONDON) %jumpi(sys_selfdestruct_insert)
+    DUP2 %contains_selfdestruct_list %jumpi(sys_selfdestruct_insert)
+    PUSH @REFUND_SELFDESTRUCT_PRE_LONDON %refund_gas
+
+sys_selfdestruct_insert:
     // Insert address into the selfdestruct set.
     // stack: balance, address, recipient, kexit_info
     DUP2 %insert_selfdestruct_list
@@ -105,12 +120,30 @@ sys_selfdestruct_journal_add:
     // stack: address, recipient, balance, kexit_info
     %journal_add_account_destroyed
 
+sys_selfdestruct_done:
     // stack: kexit_info
     %leftover_gas
     // stack: leftover_gas
     PUSH 1 // success
     %jump(terminate_common)
 
+sys_selfdestruct_balance_only:
+    // The account was not created in this transaction, so we only send its balance to the
+    // recipient. If the recipient is the address itself, the balance is left unchanged.
+    // stack: balance, address, recipient, kexit_info
+    DUP3 DUP3 EQ %jumpi(sys_selfdestruct_same_recipient)
+    %stack (balance, address, recipient) -> (address, recipient, balance, address, recipient, balance)
+    %transfer_eth
+    // stack: status, address, recipient, balance, kexit_info
+    %jumpi(panic) // This should never happen, since we transfer the whole balance.
+    %journal_add_balance_transfer
+    %jump(sys_selfdestruct_done)
+
+sys_selfdestruct_same_recipient:
+    // stack: balance, address, recipient, kexit_info
+    %pop3
+    %jump(sys_selfdestruct_done)
+
 global sys_revert:
     // stack: kexit_info, offset, size
     %stack (kexit_info, offset, size) -> (offset, size, kexit_info, offset, size)
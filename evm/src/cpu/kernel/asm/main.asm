@@ -5,6 +5,9 @@ global main:
     // Initialize the block bloom filter
     %initialize_block_bloom
 
+    // Blocks before London don't have a base fee.
+    %is_hardfork_at_least(@HARDFORK_LONDON) %basefee ISZERO OR %assert_nonzero
+
     // Second, load all MPT data from the prover.
     PUSH hash_initial_tries
     %jump(load_all_mpts)
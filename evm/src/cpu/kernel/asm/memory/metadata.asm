@@ -226,8 +226,17 @@ global sys_chainid:
     %mload_global_metadata(@GLOBAL_METADATA_BLOCK_BASE_FEE)
 %endmacro
 
+// Returns 1 if the rules of the given hardfork apply to the current block, i.e. if the block's
+// hardfork is this one or a later one, and 0 otherwise.
+%macro is_hardfork_at_least(hardfork)
+    %mload_global_metadata(@GLOBAL_METADATA_HARDFORK)
+    %ge_const($hardfork)
+%endmacro
+
 global sys_basefee:
     // stack: kexit_info
+    // The BASEFEE opcode was introduced in London, see EIP-3198.
+    %is_hardfork_at_least(@HARDFORK_LONDON) ISZERO %jumpi(fault_exception)
     %charge_gas_const(@GAS_BASE)
     // stack: kexit_info
     %basefee
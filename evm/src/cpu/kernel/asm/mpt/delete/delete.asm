@@ -15,6 +15,10 @@ global mpt_delete:
     DUP1 %eq_const(@MPT_NODE_EXTENSION) %jumpi(mpt_delete_extension)
     DUP1 %eq_const(@MPT_NODE_LEAF)      %jumpi(mpt_delete_leaf)
          %eq_const(@MPT_NODE_EMPTY)     %jumpi(panic) // This should never happen.
+
+    // There's still the MPT_NODE_HASH case, but if we hit a hash node,
+    // it means the prover failed to provide necessary Merkle data, so panic.
+global mpt_delete_hash_node:
     PANIC
 
 mpt_delete_leaf:
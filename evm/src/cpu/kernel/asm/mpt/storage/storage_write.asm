@@ -53,11 +53,11 @@ sstore_refund:
     %jump(sstore_dirty_reset)
 
 sstore_dirty_clear1:
-    PUSH @REFUND_SCLEAR PUSH 0 SUB %refund_gas
+    %refund_sclear PUSH 0 SUB %refund_gas
     %jump(sstore_dirty_reset)
 
 sstore_dirty_clear2:
-    PUSH @REFUND_SCLEAR %refund_gas
+    %refund_sclear %refund_gas
 
 sstore_dirty_reset:
     %stack (current_value, value, original_value, slot, kexit_info) -> (original_value, value, current_value, value, original_value, slot, kexit_info)
@@ -77,7 +77,7 @@ sstore_refund_original:
     ISZERO %jumpi(sstore_sclear)
     %jump(sstore_no_refund)
 sstore_sclear:
-    PUSH @REFUND_SCLEAR %refund_gas
+    %refund_sclear %refund_gas
     %jump(sstore_no_refund)
 
 sstore_no_refund:
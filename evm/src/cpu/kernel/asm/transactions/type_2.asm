@@ -9,6 +9,8 @@
 
 global process_type_2_txn:
     // stack: retdest
+    // Type 2 transactions were introduced in London.
+    %is_hardfork_at_least(@HARDFORK_LONDON) %assert_nonzero
     PUSH 1 // initial pos, skipping over the 0x02 byte
     // stack: pos, retdest
     %decode_rlp_list_len
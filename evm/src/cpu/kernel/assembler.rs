@@ -85,6 +85,19 @@ impl Kernel {
         }
     }
 
+    /// Get the global label at or immediately below the given offset, if any.
+    pub(crate) fn enclosing_label(&self, offset: usize) -> Option<&str> {
+        let idx = match self
+            .ordered_labels
+            .binary_search_by_key(&offset, |label| self.global_labels[label])
+        {
+            Ok(idx) => idx,
+            Err(0) => return None,
+            Err(idx) => idx - 1,
+        };
+        Some(&self.ordered_labels[idx])
+    }
+
     pub(crate) fn offset_label(&self, offset: usize) -> Option<String> {
         self.global_labels
             .iter()
@@ -85,10 +85,14 @@ pub(crate) enum GlobalMetadata {
     LogsPayloadLen = 43,
     TxnNumberBefore = 44,
     TxnNumberAfter = 45,
+    /// Length of the list of contracts created in the current transaction.
+    CreatedContractsLen = 46,
+    /// The hardfork whose rules apply to the current block. See `Hardfork`.
+    Hardfork = 47,
 }
 
 impl GlobalMetadata {
-    pub(crate) const COUNT: usize = 46;
+    pub(crate) const COUNT: usize = 48;
 
     pub(crate) fn all() -> [Self; Self::COUNT] {
         [
@@ -138,6 +142,8 @@ impl GlobalMetadata {
             Self::BlockCurrentHash,
             Self::TxnNumberBefore,
             Self::TxnNumberAfter,
+            Self::CreatedContractsLen,
+            Self::Hardfork,
         ]
     }
 
@@ -190,6 +196,8 @@ impl GlobalMetadata {
             Self::LogsPayloadLen => "GLOBAL_METADATA_LOGS_PAYLOAD_LEN",
             Self::TxnNumberBefore => "GLOBAL_METADATA_TXN_NUMBER_BEFORE",
             Self::TxnNumberAfter => "GLOBAL_METADATA_TXN_NUMBER_AFTER",
+            Self::CreatedContractsLen => "GLOBAL_METADATA_CREATED_CONTRACTS_LEN",
+            Self::Hardfork => "GLOBAL_METADATA_HARDFORK",
         }
     }
 }
@@ -8,6 +8,7 @@ use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::kernel::constants::journal_entry::JournalEntry;
 use crate::cpu::kernel::constants::trie_type::PartialTrieType;
 use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
+use crate::hardfork::Hardfork;
 use crate::memory::segments::Segment;
 
 pub(crate) mod context_metadata;
@@ -75,6 +76,9 @@ pub fn evm_constants() -> HashMap<String, U256> {
     for entry in JournalEntry::all() {
         c.insert(entry.var_name().into(), (entry as u32).into());
     }
+    for hardfork in Hardfork::all() {
+        c.insert(hardfork.var_name().into(), hardfork.into());
+    }
     c.insert(
         "INVALID_OPCODES_USER".into(),
         exc_bitfields::INVALID_OPCODES_USER,
@@ -230,7 +234,14 @@ const GAS_CONSTANTS: [(&str, u16); 36] = [
     ("GAS_BLOCKHASH", 20),
 ];
 
-const REFUND_CONSTANTS: [(&str, u16); 2] = [("REFUND_SCLEAR", 4_800), ("MAX_REFUND_QUOTIENT", 5)];
+const REFUND_CONSTANTS: [(&str, u16); 5] = [
+    ("REFUND_SCLEAR", 4_800),
+    ("MAX_REFUND_QUOTIENT", 5),
+    // Refunds before EIP-3529, i.e. before London.
+    ("REFUND_SCLEAR_PRE_LONDON", 15_000),
+    ("MAX_REFUND_QUOTIENT_PRE_LONDON", 2),
+    ("REFUND_SELFDESTRUCT_PRE_LONDON", 24_000),
+];
 
 const PRECOMPILES: [(&str, u16); 9] = [
     ("ECREC", 1),
@@ -0,0 +1,328 @@
+//! A step debugger for kernel code, built on top of the kernel interpreter.
+//!
+//! The debugger supports breakpoints on global labels, single-stepping, and inspection of the
+//! stack, memory segments, context and global metadata, and the journal.
+
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, ensure};
+use ethereum_types::U256;
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::constants::journal_entry::JournalEntry;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::generation::profiler::KernelProfile;
+use crate::generation::GenerationInputs;
+use crate::memory::segments::Segment;
+use crate::witness::memory::MemoryAddress;
+
+/// The reason why `KernelDebugger::continue_execution` returned.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum StopReason {
+    /// Execution reached a breakpoint, at the given location.
+    Breakpoint(String),
+    /// The kernel halted.
+    Halted,
+    /// The maximum number of steps was executed without reaching a breakpoint.
+    StepLimit,
+}
+
+/// A journal entry, as stored in the `Journal` and `JournalData` segments.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct JournalEntryView {
+    /// The name of the entry type, e.g. `JOURNAL_ENTRY_STORAGE_CHANGE`.
+    pub entry_type: &'static str,
+    /// The entry's fields, in the order in which they are stored.
+    pub fields: Vec<U256>,
+}
+
+pub struct KernelDebugger {
+    interpreter: Interpreter<'static>,
+    breakpoints: BTreeSet<usize>,
+    halted: bool,
+}
+
+impl KernelDebugger {
+    /// Creates a debugger which will execute the whole kernel, starting at `main`, on the given
+    /// inputs.
+    pub fn new(inputs: GenerationInputs) -> anyhow::Result<Self> {
+        Self::new_at_label("main", vec![], inputs)
+    }
+
+    /// Creates a debugger which will start executing at the given global label, with the given
+    /// initial stack (the last element being the top of the stack).
+    pub fn new_at_label(
+        label: &str,
+        initial_stack: Vec<U256>,
+        inputs: GenerationInputs,
+    ) -> anyhow::Result<Self> {
+        let offset = label_offset(label)?;
+        let mut interpreter =
+            Interpreter::new_with_generation_inputs(offset, initial_stack, inputs)?;
+        interpreter.halt_offsets.push(KERNEL.global_labels["halt"]);
+
+        Ok(Self {
+            interpreter,
+            breakpoints: BTreeSet::new(),
+            halted: false,
+        })
+    }
+
+    /// Adds a breakpoint at the given global label, and returns its offset.
+    pub fn add_breakpoint(&mut self, label: &str) -> anyhow::Result<usize> {
+        let offset = label_offset(label)?;
+        self.breakpoints.insert(offset);
+        Ok(offset)
+    }
+
+    /// Removes the breakpoint at the given global label. Returns whether there was one.
+    pub fn remove_breakpoint(&mut self, label: &str) -> anyhow::Result<bool> {
+        let offset = label_offset(label)?;
+        Ok(self.breakpoints.remove(&offset))
+    }
+
+    /// The locations of all breakpoints, in code order.
+    pub fn breakpoints(&self) -> Vec<String> {
+        self.breakpoints
+            .iter()
+            .map(|&offset| KERNEL.offset_name(offset))
+            .collect()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Executes a single instruction. Returns whether the kernel is still running afterwards.
+    pub fn step(&mut self) -> anyhow::Result<bool> {
+        ensure!(!self.halted, "The kernel has already halted");
+        let running = self.interpreter.step()?;
+        self.halted = !running;
+        Ok(running)
+    }
+
+    /// Executes instructions until a breakpoint is reached, the kernel halts, or `max_steps`
+    /// instructions have been executed.
+    pub fn continue_execution(&mut self, max_steps: Option<usize>) -> anyhow::Result<StopReason> {
+        let mut steps = 0;
+        loop {
+            if max_steps.is_some_and(|max| steps >= max) {
+                return Ok(StopReason::StepLimit);
+            }
+            if !self.step()? {
+                return Ok(StopReason::Halted);
+            }
+            steps += 1;
+            if self.breakpoints.contains(&self.program_counter()) {
+                return Ok(StopReason::Breakpoint(self.location()));
+            }
+        }
+    }
+
+    /// Starts profiling the cycles spent in each kernel routine.
+    pub fn enable_profiling(&mut self) {
+        self.interpreter.enable_profiling();
+    }
+
+    /// Returns the profile collected since `enable_profiling` was called, if it was, and stops
+    /// profiling.
+    pub fn take_profile(&mut self) -> Option<KernelProfile> {
+        self.interpreter.take_profile()
+    }
+
+    pub fn program_counter(&self) -> usize {
+        self.interpreter.program_counter()
+    }
+
+    /// A description of the current location, relative to the nearest global label.
+    pub fn location(&self) -> String {
+        self.interpreter.offset_name()
+    }
+
+    pub fn context(&self) -> usize {
+        self.interpreter.context
+    }
+
+    /// The current stack, the last element being the top of the stack.
+    pub fn stack(&self) -> Vec<U256> {
+        self.interpreter.stack()
+    }
+
+    /// The content of the given segment in the given context.
+    pub fn memory_segment(&self, context: usize, segment: Segment) -> Vec<U256> {
+        self.interpreter
+            .generation_state
+            .memory
+            .contexts
+            .get(context)
+            .map(|ctx| ctx.segments[segment as usize].content.clone())
+            .unwrap_or_default()
+    }
+
+    /// The metadata fields of the given context, along with their names.
+    pub fn context_metadata(&self, context: usize) -> Vec<(&'static str, U256)> {
+        ContextMetadata::all()
+            .iter()
+            .map(|field| {
+                let address =
+                    MemoryAddress::new(context, Segment::ContextMetadata, *field as usize);
+                (
+                    field.var_name(),
+                    self.interpreter.generation_state.memory.get(address),
+                )
+            })
+            .collect()
+    }
+
+    /// The global metadata fields, along with their names.
+    pub fn global_metadata(&self) -> Vec<(&'static str, U256)> {
+        GlobalMetadata::all()
+            .iter()
+            .map(|&field| {
+                (
+                    field.var_name(),
+                    self.interpreter.get_global_metadata_field(field),
+                )
+            })
+            .collect()
+    }
+
+    /// The entries currently in the journal, oldest first.
+    pub fn journal(&self) -> anyhow::Result<Vec<JournalEntryView>> {
+        let journal_len = self
+            .interpreter
+            .get_global_metadata_field(GlobalMetadata::JournalLen)
+            .as_usize();
+        let journal = self.memory_segment(0, Segment::Journal);
+        let journal_data = self.memory_segment(0, Segment::JournalData);
+        let read_data = |i: usize| journal_data.get(i).copied().unwrap_or_default();
+
+        (0..journal_len)
+            .map(|i| {
+                let ptr = journal.get(i).copied().unwrap_or_default().as_usize();
+                let entry_type = read_data(ptr).as_usize();
+                let entry = *JournalEntry::all()
+                    .get(entry_type)
+                    .ok_or_else(|| anyhow!("Invalid journal entry type {}", entry_type))?;
+                let fields = (1..=journal_entry_num_fields(entry))
+                    .map(|j| read_data(ptr + j))
+                    .collect();
+                Ok(JournalEntryView {
+                    entry_type: entry.var_name(),
+                    fields,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses a segment name, either as its kernel variable name (e.g. `SEGMENT_JOURNAL`) or as its
+/// Rust variant name (e.g. `Journal`).
+pub fn parse_segment(name: &str) -> Option<Segment> {
+    Segment::all()
+        .into_iter()
+        .find(|segment| segment.var_name() == name || format!("{segment:?}") == name)
+}
+
+fn label_offset(label: &str) -> anyhow::Result<usize> {
+    KERNEL
+        .global_labels
+        .get(label)
+        .copied()
+        .ok_or_else(|| anyhow!("No such global label: {}", label))
+}
+
+/// The number of fields of each journal entry type, see the structs in `asm/journal`.
+fn journal_entry_num_fields(entry: JournalEntry) -> usize {
+    match entry {
+        JournalEntry::AccountLoaded => 1,
+        JournalEntry::AccountDestroyed => 3,
+        JournalEntry::AccountTouched => 1,
+        JournalEntry::BalanceTransfer => 3,
+        JournalEntry::NonceChange => 2,
+        JournalEntry::StorageChange => 3,
+        JournalEntry::StorageLoaded => 2,
+        JournalEntry::CodeChange => 2,
+        JournalEntry::Refund => 1,
+        JournalEntry::AccountCreated => 1,
+        JournalEntry::Log => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethereum_types::U256;
+
+    use crate::cpu::kernel::aggregator::KERNEL;
+    use crate::cpu::kernel::debugger::{parse_segment, KernelDebugger, StopReason};
+    use crate::generation::GenerationInputs;
+    use crate::memory::segments::Segment;
+
+    #[test]
+    fn test_breakpoint() -> anyhow::Result<()> {
+        let retdest = KERNEL.global_labels["mload_packing"].into();
+        let initial_stack = vec![
+            retdest,
+            2.into(),
+            0x1234.into(),
+            0.into(),
+            (Segment::MainMemory as usize).into(),
+            0.into(),
+        ];
+        let mut debugger =
+            KernelDebugger::new_at_label("mstore_unpacking", initial_stack, Default::default())?;
+        debugger.add_breakpoint("mload_packing")?;
+        assert_eq!(debugger.breakpoints(), vec!["mload_packing".to_string()]);
+
+        assert_eq!(
+            debugger.continue_execution(None)?,
+            StopReason::Breakpoint("mload_packing".to_string())
+        );
+        assert_eq!(debugger.stack(), vec![2.into()]);
+        assert_eq!(
+            debugger.memory_segment(0, Segment::MainMemory),
+            vec![U256::from(0x12), U256::from(0x34)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_until_halt() -> anyhow::Result<()> {
+        let retdest = 0xDEADBEEFu32.into();
+        let initial_stack = vec![
+            retdest,
+            1.into(),
+            0.into(),
+            (Segment::MainMemory as usize).into(),
+            0.into(),
+        ];
+        let mut debugger =
+            KernelDebugger::new_at_label("mload_packing", initial_stack, Default::default())?;
+
+        assert!(debugger.step()?);
+        assert_eq!(debugger.continue_execution(Some(1))?, StopReason::StepLimit);
+        assert_eq!(debugger.continue_execution(None)?, StopReason::Halted);
+        assert!(debugger.is_halted());
+        assert!(debugger.step().is_err());
+        assert_eq!(debugger.stack(), vec![0.into()]);
+        assert!(debugger.journal()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_label() {
+        assert!(
+            KernelDebugger::new_at_label("no_such_label", vec![], GenerationInputs::default())
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_segment() {
+        assert_eq!(parse_segment("SEGMENT_JOURNAL"), Some(Segment::Journal));
+        assert_eq!(parse_segment("JournalData"), Some(Segment::JournalData));
+        assert_eq!(parse_segment("foo"), None);
+    }
+}
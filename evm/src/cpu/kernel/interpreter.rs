@@ -2,6 +2,7 @@
 
 use core::cmp::Ordering;
 use std::collections::HashMap;
+#[cfg(test)]
 use std::ops::Range;
 
 use anyhow::{anyhow, bail, ensure};
@@ -9,16 +10,22 @@ use ethereum_types::{U256, U512};
 use keccak_hash::keccak;
 use plonky2::field::goldilocks_field::GoldilocksField;
 
+use crate::all_stark::{Table, NUM_TABLES};
 use crate::cpu::kernel::aggregator::KERNEL;
 use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
 use crate::extension_tower::BN_BASE;
+use crate::generation::profiler::{KernelProfile, KernelProfiler};
 use crate::generation::prover_input::ProverInputFn;
 use crate::generation::state::GenerationState;
-use crate::generation::GenerationInputs;
+use crate::generation::{apply_metadata_and_tries_memops, GenerationInputs};
+#[cfg(test)]
+use crate::hardfork::Hardfork;
 use crate::memory::segments::Segment;
-use crate::witness::memory::{MemoryAddress, MemoryContextState, MemorySegmentState, MemoryState};
+#[cfg(test)]
+use crate::witness::memory::MemoryContextState;
+use crate::witness::memory::{MemoryAddress, MemorySegmentState, MemoryState};
 use crate::witness::util::stack_peek;
 
 type F = GoldilocksField;
@@ -46,8 +53,10 @@ pub struct Interpreter<'a> {
     pub(crate) debug_offsets: Vec<usize>,
     running: bool,
     opcode_count: [usize; 0x100],
+    profiler: Option<KernelProfiler>,
 }
 
+#[cfg(test)]
 pub fn run_interpreter(
     initial_offset: usize,
     initial_stack: Vec<U256>,
@@ -60,6 +69,7 @@ pub fn run_interpreter(
     )
 }
 
+#[cfg(test)]
 #[derive(Clone)]
 pub struct InterpreterMemoryInitialization {
     pub label: String,
@@ -68,6 +78,7 @@ pub struct InterpreterMemoryInitialization {
     pub memory: Vec<(usize, Vec<U256>)>,
 }
 
+#[cfg(test)]
 pub fn run_interpreter_with_memory(
     memory_init: InterpreterMemoryInitialization,
 ) -> anyhow::Result<Interpreter<'static>> {
@@ -87,6 +98,7 @@ pub fn run_interpreter_with_memory(
     Ok(interpreter)
 }
 
+#[cfg(test)]
 pub fn run<'a>(
     code: &'a [u8],
     initial_offset: usize,
@@ -99,6 +111,7 @@ pub fn run<'a>(
 }
 
 impl<'a> Interpreter<'a> {
+    #[cfg(test)]
     pub(crate) fn new_with_kernel(initial_offset: usize, initial_stack: Vec<U256>) -> Self {
         Self::new(
             &KERNEL.code,
@@ -108,22 +121,64 @@ impl<'a> Interpreter<'a> {
         )
     }
 
+    /// Creates a kernel interpreter whose memory is initialized from `inputs`, in the same way as
+    /// at the start of trace generation.
+    pub(crate) fn new_with_generation_inputs(
+        initial_offset: usize,
+        initial_stack: Vec<U256>,
+        inputs: GenerationInputs,
+    ) -> anyhow::Result<Self> {
+        let mut generation_state = GenerationState::new(inputs.clone(), &KERNEL.code)
+            .map_err(|err| anyhow!("Failed to parse all the initial prover inputs: {:?}", err))?;
+        apply_metadata_and_tries_memops::<F, 2>(&mut generation_state, &inputs);
+
+        Ok(Self::new_with_state(
+            &KERNEL.code,
+            initial_offset,
+            initial_stack,
+            &KERNEL.prover_inputs,
+            generation_state,
+        ))
+    }
+
+    #[cfg(test)]
     pub(crate) fn new(
         code: &'a [u8],
         initial_offset: usize,
         initial_stack: Vec<U256>,
         prover_inputs: &'a HashMap<usize, ProverInputFn>,
+    ) -> Self {
+        let generation_state = GenerationState::new(GenerationInputs::default(), code).unwrap();
+        let mut interpreter = Self::new_with_state(
+            code,
+            initial_offset,
+            initial_stack,
+            prover_inputs,
+            generation_state,
+        );
+        // Apply the rules of the default hardfork, as the block metadata isn't loaded.
+        interpreter.set_global_metadata_field(GlobalMetadata::Hardfork, Hardfork::default().into());
+        interpreter
+    }
+
+    fn new_with_state(
+        code: &'a [u8],
+        initial_offset: usize,
+        initial_stack: Vec<U256>,
+        prover_inputs: &'a HashMap<usize, ProverInputFn>,
+        generation_state: GenerationState<F>,
     ) -> Self {
         let mut result = Self {
             kernel_mode: true,
             jumpdests: find_jumpdests(code),
-            generation_state: GenerationState::new(GenerationInputs::default(), code).unwrap(),
+            generation_state,
             prover_inputs_map: prover_inputs,
             context: 0,
             halt_offsets: vec![DEFAULT_HALT_OFFSET],
             debug_offsets: vec![],
             running: false,
             opcode_count: [0; 0x100],
+            profiler: None,
         };
         result.generation_state.registers.program_counter = initial_offset;
         let initial_stack_len = initial_stack.len();
@@ -137,21 +192,45 @@ impl<'a> Interpreter<'a> {
         result
     }
 
+    #[cfg(test)]
     pub(crate) fn run(&mut self) -> anyhow::Result<()> {
         self.running = true;
         while self.running {
             self.run_opcode()?;
         }
-        println!("Opcode count:");
+        log::debug!("Opcode count:");
         for i in 0..0x100 {
             if self.opcode_count[i] > 0 {
-                println!("{}: {}", get_mnemonic(i as u8), self.opcode_count[i])
+                log::debug!("{}: {}", get_mnemonic(i as u8), self.opcode_count[i])
             }
         }
-        println!("Total: {}", self.opcode_count.into_iter().sum::<usize>());
+        log::debug!("Total: {}", self.opcode_count.into_iter().sum::<usize>());
         Ok(())
     }
 
+    /// Executes a single instruction. Returns whether the interpreter is still running afterwards,
+    /// i.e. `false` if the instruction halted execution.
+    pub(crate) fn step(&mut self) -> anyhow::Result<bool> {
+        self.running = true;
+        self.run_opcode()?;
+        Ok(self.running)
+    }
+
+    /// Starts attributing the cycles of executed instructions to kernel routines. The interpreter
+    /// doesn't generate traces, so only CPU cycles are counted.
+    pub(crate) fn enable_profiling(&mut self) {
+        self.profiler = Some(KernelProfiler::default());
+    }
+
+    /// Returns the profile of the execution since `enable_profiling` was called, if it was.
+    pub(crate) fn take_profile(&mut self) -> Option<KernelProfile> {
+        self.profiler.take().map(KernelProfiler::finish)
+    }
+
+    pub(crate) fn program_counter(&self) -> usize {
+        self.generation_state.registers.program_counter
+    }
+
     fn code(&self) -> &MemorySegmentState {
         &self.generation_state.memory.contexts[self.context].segments[Segment::Code as usize]
     }
@@ -169,11 +248,13 @@ impl<'a> Interpreter<'a> {
             .get(field as usize)
     }
 
+    #[cfg(test)]
     pub(crate) fn set_txn_field(&mut self, field: NormalizedTxnField, value: U256) {
         self.generation_state.memory.contexts[0].segments[Segment::TxnFields as usize]
             .set(field as usize, value);
     }
 
+    #[cfg(test)]
     pub(crate) fn get_txn_data(&self) -> &[U256] {
         &self.generation_state.memory.contexts[0].segments[Segment::TxnData as usize].content
     }
@@ -183,15 +264,18 @@ impl<'a> Interpreter<'a> {
             .get(field as usize)
     }
 
+    #[cfg(test)]
     pub(crate) fn set_global_metadata_field(&mut self, field: GlobalMetadata, value: U256) {
         self.generation_state.memory.contexts[0].segments[Segment::GlobalMetadata as usize]
             .set(field as usize, value)
     }
 
+    #[cfg(test)]
     pub(crate) fn get_trie_data(&self) -> &[U256] {
         &self.generation_state.memory.contexts[0].segments[Segment::TrieData as usize].content
     }
 
+    #[cfg(test)]
     pub(crate) fn get_trie_data_mut(&mut self) -> &mut Vec<U256> {
         &mut self.generation_state.memory.contexts[0].segments[Segment::TrieData as usize].content
     }
@@ -202,6 +286,7 @@ impl<'a> Interpreter<'a> {
             .clone()
     }
 
+    #[cfg(test)]
     pub(crate) fn get_memory_segment_bytes(&self, segment: Segment) -> Vec<u8> {
         self.generation_state.memory.contexts[0].segments[segment as usize]
             .content
@@ -210,6 +295,7 @@ impl<'a> Interpreter<'a> {
             .collect()
     }
 
+    #[cfg(test)]
     pub(crate) fn get_current_general_memory(&self) -> Vec<U256> {
         self.generation_state.memory.contexts[self.context].segments
             [Segment::KernelGeneral as usize]
@@ -221,29 +307,35 @@ impl<'a> Interpreter<'a> {
         self.get_memory_segment(Segment::KernelGeneral)
     }
 
+    #[cfg(test)]
     pub(crate) fn get_rlp_memory(&self) -> Vec<u8> {
         self.get_memory_segment_bytes(Segment::RlpRaw)
     }
 
+    #[cfg(test)]
     pub(crate) fn set_current_general_memory(&mut self, memory: Vec<U256>) {
         self.generation_state.memory.contexts[self.context].segments
             [Segment::KernelGeneral as usize]
             .content = memory;
     }
 
+    #[cfg(test)]
     pub(crate) fn set_memory_segment(&mut self, segment: Segment, memory: Vec<U256>) {
         self.generation_state.memory.contexts[0].segments[segment as usize].content = memory;
     }
 
+    #[cfg(test)]
     pub(crate) fn set_memory_segment_bytes(&mut self, segment: Segment, memory: Vec<u8>) {
         self.generation_state.memory.contexts[0].segments[segment as usize].content =
             memory.into_iter().map(U256::from).collect();
     }
 
+    #[cfg(test)]
     pub(crate) fn set_rlp_memory(&mut self, rlp: Vec<u8>) {
         self.set_memory_segment_bytes(Segment::RlpRaw, rlp)
     }
 
+    #[cfg(test)]
     pub(crate) fn set_code(&mut self, context: usize, code: Vec<u8>) {
         assert_ne!(context, 0, "Can't modify kernel code.");
         while self.generation_state.memory.contexts.len() <= context {
@@ -256,6 +348,7 @@ impl<'a> Interpreter<'a> {
             code.into_iter().map(U256::from).collect();
     }
 
+    #[cfg(test)]
     pub(crate) fn get_jumpdest_bits(&self, context: usize) -> Vec<bool> {
         self.generation_state.memory.contexts[context].segments[Segment::JumpdestBits as usize]
             .content
@@ -284,6 +377,7 @@ impl<'a> Interpreter<'a> {
             .content
     }
 
+    #[cfg(test)]
     pub fn extract_kernel_memory(self, segment: Segment, range: Range<usize>) -> Vec<U256> {
         let mut output: Vec<U256> = vec![];
         for i in range {
@@ -331,6 +425,15 @@ impl<'a> Interpreter<'a> {
             .get(self.generation_state.registers.program_counter)
             .byte(0);
         self.opcode_count[opcode as usize] += 1;
+        if let Some(profiler) = self.profiler.as_mut() {
+            let mut rows = [0; NUM_TABLES];
+            rows[Table::Cpu as usize] = 1;
+            profiler.record_instruction(
+                self.generation_state.registers.program_counter,
+                self.kernel_mode,
+                rows,
+            );
+        }
         self.incr(1);
 
         match opcode {
@@ -447,7 +550,7 @@ impl<'a> Interpreter<'a> {
         Ok(())
     }
 
-    fn offset_name(&self) -> String {
+    pub(crate) fn offset_name(&self) -> String {
         KERNEL.offset_name(self.generation_state.registers.program_counter)
     }
 
@@ -1203,7 +1306,7 @@ fn find_jumpdests(code: &[u8]) -> Vec<usize> {
     res
 }
 
-fn get_mnemonic(opcode: u8) -> &'static str {
+pub(crate) fn get_mnemonic(opcode: u8) -> &'static str {
     match opcode {
         0x00 => "STOP",
         0x01 => "ADD",
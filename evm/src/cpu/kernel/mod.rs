@@ -3,6 +3,7 @@ pub mod assembler;
 mod ast;
 pub(crate) mod constants;
 mod cost_estimator;
+pub mod debugger;
 pub(crate) mod keccak_util;
 pub mod opcodes;
 mod optimizer;
@@ -10,8 +11,7 @@ mod parser;
 pub mod stack;
 mod utils;
 
-#[cfg(test)]
-mod interpreter;
+pub(crate) mod interpreter;
 #[cfg(test)]
 mod tests;
 
@@ -5,9 +5,12 @@ use crate::cpu::kernel::aggregator::KERNEL;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::kernel::constants::txn_fields::NormalizedTxnField;
 use crate::cpu::kernel::interpreter::Interpreter;
+use crate::hardfork::Hardfork;
 
 const GAS_TX: u32 = 21_000;
 const GAS_TXCREATE: u32 = 32_000;
+const GAS_TXDATAZERO: u32 = 4;
+const INITCODE_WORD_COST: u32 = 2;
 
 #[test]
 fn test_intrinsic_gas() -> Result<()> {
@@ -28,3 +31,27 @@ fn test_intrinsic_gas() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_intrinsic_gas_initcode() -> Result<()> {
+    let intrinsic_gas = KERNEL.global_labels["intrinsic_gas"];
+    // Two words of zero bytes.
+    let data_len = 64;
+
+    // The initcode of contract creations is only metered since Shanghai, see EIP-3860.
+    for (hardfork, gas_initcode) in [
+        (Hardfork::London, 0),
+        (Hardfork::Shanghai, 2 * INITCODE_WORD_COST),
+    ] {
+        let initial_stack = vec![0xdeadbeefu32.into()];
+        let mut interpreter = Interpreter::new_with_kernel(intrinsic_gas, initial_stack);
+        interpreter.set_global_metadata_field(GlobalMetadata::ContractCreation, U256::one());
+        interpreter.set_global_metadata_field(GlobalMetadata::Hardfork, hardfork.into());
+        interpreter.set_txn_field(NormalizedTxnField::DataLen, data_len.into());
+        interpreter.run()?;
+        let expected_gas = GAS_TX + GAS_TXCREATE + data_len * GAS_TXDATAZERO + gas_initcode;
+        assert_eq!(interpreter.stack(), vec![expected_gas.into()]);
+    }
+
+    Ok(())
+}
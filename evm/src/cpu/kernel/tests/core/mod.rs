@@ -2,3 +2,4 @@ mod access_lists;
 mod create_addresses;
 mod intrinsic_gas;
 mod jumpdest_analysis;
+mod selfdestruct;
@@ -0,0 +1,282 @@
+use anyhow::Result;
+use eth_trie_utils::nibbles::Nibbles;
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{Address, U256};
+use keccak_hash::keccak;
+use rand::{thread_rng, Rng};
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::constants::journal_entry::JournalEntry;
+use crate::cpu::kernel::interpreter::Interpreter;
+use crate::generation::mpt::AccountRlp;
+use crate::generation::{GenerationInputs, TrieInputs};
+use crate::hardfork::Hardfork;
+use crate::memory::segments::Segment;
+use crate::proof::BlockMetadata;
+use crate::witness::memory::MemoryAddress;
+use crate::Node;
+
+const ADDRESS_BALANCE: u64 = 1000;
+const RECIPIENT_BALANCE: u64 = 200;
+const REFUND_SELFDESTRUCT_PRE_LONDON: u64 = 24_000;
+
+fn u256_from_address(address: Address) -> U256 {
+    U256::from_big_endian(address.as_bytes())
+}
+
+/// Prepares an interpreter whose state trie contains the self-destructing account `address` and
+/// the account `recipient`, with `address` as the current context's address. The rules of Cancun
+/// apply, unless the hardfork is changed afterwards.
+fn prepare_interpreter(address: Address, recipient: Address) -> Result<Interpreter<'static>> {
+    let mut state_trie = HashedPartialTrie::from(Node::Empty);
+    for (addr, balance, code) in [
+        (address, ADDRESS_BALANCE, vec![0xff]),
+        (recipient, RECIPIENT_BALANCE, vec![]),
+    ] {
+        let account = AccountRlp {
+            nonce: 1.into(),
+            balance: balance.into(),
+            code_hash: keccak(code),
+            ..AccountRlp::default()
+        };
+        state_trie.insert(
+            Nibbles::from_bytes_be(keccak(addr).as_bytes()).unwrap(),
+            rlp::encode(&account).to_vec(),
+        );
+    }
+    let inputs = GenerationInputs {
+        tries: TrieInputs {
+            state_trie,
+            ..TrieInputs::default()
+        },
+        block_metadata: BlockMetadata {
+            hardfork: Hardfork::Cancun,
+            ..BlockMetadata::default()
+        },
+        ..GenerationInputs::default()
+    };
+
+    let load_all_mpts = KERNEL.global_labels["load_all_mpts"];
+    let mut interpreter =
+        Interpreter::new_with_generation_inputs(load_all_mpts, vec![0xDEADBEEFu32.into()], inputs)?;
+    interpreter.run()?;
+    assert_eq!(interpreter.stack(), vec![]);
+
+    let mut set_context_metadata = |field: ContextMetadata, value: U256| {
+        interpreter.generation_state.memory.set(
+            MemoryAddress::new(0, Segment::ContextMetadata, field as usize),
+            value,
+        )
+    };
+    set_context_metadata(ContextMetadata::Address, u256_from_address(address));
+    set_context_metadata(ContextMetadata::GasLimit, 100_000.into());
+    set_context_metadata(ContextMetadata::ParentPc, 0xDEADBEEFu32.into());
+    interpreter.set_global_metadata_field(GlobalMetadata::CallStackDepth, 1.into());
+
+    Ok(interpreter)
+}
+
+fn selfdestruct(interpreter: &mut Interpreter, recipient: Address) -> Result<()> {
+    interpreter.generation_state.registers.program_counter =
+        KERNEL.global_labels["sys_selfdestruct"];
+    interpreter.push(u256_from_address(recipient));
+    interpreter.push(0.into()); // kexit_info
+    interpreter.run()?;
+
+    let success = interpreter.pop();
+    let _leftover_gas = interpreter.pop();
+    assert_eq!(success, 1.into());
+    assert_eq!(interpreter.stack(), vec![]);
+    Ok(())
+}
+
+fn revert_all(interpreter: &mut Interpreter) -> Result<()> {
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["revert_batch"];
+    interpreter.push(0xDEADBEEFu32.into());
+    interpreter.push(0.into()); // target_size
+    interpreter.run()?;
+    assert_eq!(interpreter.stack(), vec![]);
+    Ok(())
+}
+
+fn balance(interpreter: &mut Interpreter, address: Address) -> Result<U256> {
+    interpreter.generation_state.registers.program_counter = KERNEL.global_labels["balance"];
+    interpreter.push(0xDEADBEEFu32.into());
+    interpreter.push(u256_from_address(address));
+    interpreter.run()?;
+    Ok(interpreter.pop())
+}
+
+/// The types of the journal entries, oldest first.
+fn journal_entry_types(interpreter: &Interpreter) -> Vec<usize> {
+    let journal_len = interpreter
+        .get_global_metadata_field(GlobalMetadata::JournalLen)
+        .as_usize();
+    let journal = interpreter.get_memory_segment(Segment::Journal);
+    let journal_data = interpreter.get_memory_segment(Segment::JournalData);
+    journal[..journal_len]
+        .iter()
+        .map(|ptr| journal_data[ptr.as_usize()].as_usize())
+        .collect()
+}
+
+#[test]
+fn test_selfdestruct_not_created() -> Result<()> {
+    let mut rng = thread_rng();
+    let address: Address = rng.gen();
+    let recipient: Address = rng.gen();
+    let mut interpreter = prepare_interpreter(address, recipient)?;
+
+    selfdestruct(&mut interpreter, recipient)?;
+    assert_eq!(balance(&mut interpreter, address)?, 0.into());
+    assert_eq!(
+        balance(&mut interpreter, recipient)?,
+        (ADDRESS_BALANCE + RECIPIENT_BALANCE).into()
+    );
+    // The account is not scheduled for deletion, and the balance transfer is journaled.
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::SelfDestructListLen),
+        0.into()
+    );
+    let journal = journal_entry_types(&interpreter);
+    assert_eq!(
+        journal.last(),
+        Some(&(JournalEntry::BalanceTransfer as usize))
+    );
+    assert!(!journal.contains(&(JournalEntry::AccountDestroyed as usize)));
+
+    revert_all(&mut interpreter)?;
+    assert_eq!(balance(&mut interpreter, address)?, ADDRESS_BALANCE.into());
+    assert_eq!(
+        balance(&mut interpreter, recipient)?,
+        RECIPIENT_BALANCE.into()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_selfdestruct_not_created_to_self() -> Result<()> {
+    let mut rng = thread_rng();
+    let address: Address = rng.gen();
+    let recipient: Address = rng.gen();
+    let mut interpreter = prepare_interpreter(address, recipient)?;
+
+    // The balance is not burnt, since the account was not created in this transaction.
+    selfdestruct(&mut interpreter, address)?;
+    assert_eq!(balance(&mut interpreter, address)?, ADDRESS_BALANCE.into());
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::SelfDestructListLen),
+        0.into()
+    );
+    let journal = journal_entry_types(&interpreter);
+    assert!(!journal.contains(&(JournalEntry::BalanceTransfer as usize)));
+    assert!(!journal.contains(&(JournalEntry::AccountDestroyed as usize)));
+    Ok(())
+}
+
+#[test]
+fn test_selfdestruct_created() -> Result<()> {
+    let mut rng = thread_rng();
+    let address: Address = rng.gen();
+    let recipient: Address = rng.gen();
+    let mut interpreter = prepare_interpreter(address, recipient)?;
+    interpreter.set_memory_segment(
+        Segment::CreatedContracts,
+        vec![u256_from_address(recipient), u256_from_address(address)],
+    );
+    interpreter.set_global_metadata_field(GlobalMetadata::CreatedContractsLen, 2.into());
+
+    selfdestruct(&mut interpreter, recipient)?;
+    assert_eq!(balance(&mut interpreter, address)?, 0.into());
+    assert_eq!(
+        balance(&mut interpreter, recipient)?,
+        (ADDRESS_BALANCE + RECIPIENT_BALANCE).into()
+    );
+    // The account is scheduled for deletion at the end of the transaction.
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::SelfDestructListLen),
+        1.into()
+    );
+    assert_eq!(
+        interpreter.get_memory_segment(Segment::SelfDestructList),
+        vec![u256_from_address(address)]
+    );
+    assert_eq!(
+        journal_entry_types(&interpreter).last(),
+        Some(&(JournalEntry::AccountDestroyed as usize))
+    );
+
+    // Reverting removes the account from the self-destruct list, and restores the balances.
+    revert_all(&mut interpreter)?;
+    assert_eq!(
+        interpreter.get_global_metadata_field(GlobalMetadata::SelfDestructListLen),
+        0.into()
+    );
+    assert_eq!(balance(&mut interpreter, address)?, ADDRESS_BALANCE.into());
+    assert_eq!(
+        balance(&mut interpreter, recipient)?,
+        RECIPIENT_BALANCE.into()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_selfdestruct_before_cancun() -> Result<()> {
+    let mut rng = thread_rng();
+    for (hardfork, refund) in [
+        (Hardfork::Berlin, REFUND_SELFDESTRUCT_PRE_LONDON),
+        (Hardfork::Shanghai, 0),
+    ] {
+        let address: Address = rng.gen();
+        let recipient: Address = rng.gen();
+        let mut interpreter = prepare_interpreter(address, recipient)?;
+        interpreter.set_global_metadata_field(GlobalMetadata::Hardfork, hardfork.into());
+
+        // The account is scheduled for deletion, even though it was not created in this
+        // transaction, and a refund is given before London.
+        selfdestruct(&mut interpreter, recipient)?;
+        assert_eq!(balance(&mut interpreter, address)?, 0.into());
+        assert_eq!(
+            interpreter.get_global_metadata_field(GlobalMetadata::SelfDestructListLen),
+            1.into()
+        );
+        assert_eq!(
+            interpreter.get_global_metadata_field(GlobalMetadata::RefundCounter),
+            refund.into()
+        );
+
+        // Self-destructing the same account again doesn't give another refund.
+        selfdestruct(&mut interpreter, recipient)?;
+        assert_eq!(
+            interpreter.get_global_metadata_field(GlobalMetadata::SelfDestructListLen),
+            2.into()
+        );
+        assert_eq!(
+            interpreter.get_global_metadata_field(GlobalMetadata::RefundCounter),
+            refund.into()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_contains_created_contracts() -> Result<()> {
+    let mut rng = thread_rng();
+    let created: Address = rng.gen();
+    let not_created: Address = rng.gen();
+
+    for (address, expected) in [(created, 1), (not_created, 0)] {
+        let initial_stack = vec![0xDEADBEEFu32.into(), u256_from_address(address)];
+        let mut interpreter = Interpreter::new_with_kernel(
+            KERNEL.global_labels["contains_created_contracts"],
+            initial_stack,
+        );
+        interpreter.set_memory_segment(Segment::CreatedContracts, vec![u256_from_address(created)]);
+        interpreter.set_global_metadata_field(GlobalMetadata::CreatedContractsLen, 1.into());
+        interpreter.run()?;
+        assert_eq!(interpreter.stack(), vec![expected.into()]);
+    }
+    Ok(())
+}
@@ -1,10 +1,22 @@
 use plonky2::field::extension::Extendable;
 use plonky2::field::packed::PackedField;
+use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::ext_target::ExtensionTarget;
 
 use crate::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use crate::cpu::columns::CpuColumnsView;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::membus::NUM_GP_CHANNELS;
+use crate::hardfork::Hardfork;
+use crate::memory::segments::Segment;
+
+/// The channel reading the hardfork, which determines whether `PUSH0` (EIP-3855) is valid.
+const HARDFORK_CHANNEL: usize = 1;
+
+// `PUSH0` is valid in user mode iff `hardfork - Shanghai` is a bit, i.e. iff the hardfork is
+// Shanghai or the only later one.
+const _: () = assert!(Hardfork::COUNT == Hardfork::Shanghai as usize + 2);
 
 pub fn eval_packed<P: PackedField>(
     lv: &CpuColumnsView<P>,
@@ -15,6 +27,34 @@ pub fn eval_packed<P: PackedField>(
     for limb in nv.mem_channels[0].value {
         yield_constr.constraint(filter * limb);
     }
+
+    // Read the hardfork from the global metadata.
+    let channel = lv.mem_channels[HARDFORK_CHANNEL];
+    yield_constr.constraint(filter * (channel.used - P::ONES));
+    yield_constr.constraint(filter * (channel.is_read - P::ONES));
+    yield_constr.constraint(filter * channel.addr_context);
+    yield_constr.constraint(
+        filter
+            * (channel.addr_segment
+                - P::Scalar::from_canonical_usize(Segment::GlobalMetadata as usize)),
+    );
+    yield_constr.constraint(
+        filter
+            * (channel.addr_virtual
+                - P::Scalar::from_canonical_usize(GlobalMetadata::Hardfork as usize)),
+    );
+    // The last channel is used by the push, and the others are unused.
+    for channel in &lv.mem_channels[HARDFORK_CHANNEL + 1..NUM_GP_CHANNELS - 1] {
+        yield_constr.constraint(filter * channel.used);
+    }
+
+    // In user mode, `PUSH0` is only valid from Shanghai onwards.
+    let shanghai_offset = lv.general.push0().shanghai_offset;
+    yield_constr.constraint(filter * shanghai_offset * (shanghai_offset - P::ONES));
+    let shanghai = P::Scalar::from_canonical_usize(Hardfork::Shanghai as usize);
+    yield_constr.constraint(
+        filter * (P::ONES - lv.is_kernel_mode) * (channel.value[0] - shanghai - shanghai_offset),
+    );
 }
 
 pub fn eval_ext_circuit<F: RichField + Extendable<D>, const D: usize>(
@@ -28,4 +68,61 @@ pub fn eval_ext_circuit<F: RichField + Extendable<D>, const D: usize>(
         let constr = builder.mul_extension(filter, limb);
         yield_constr.constraint(builder, constr);
     }
+
+    // Read the hardfork from the global metadata.
+    let channel = lv.mem_channels[HARDFORK_CHANNEL];
+    {
+        let constr = builder.mul_sub_extension(filter, channel.used, filter);
+        yield_constr.constraint(builder, constr);
+    }
+    {
+        let constr = builder.mul_sub_extension(filter, channel.is_read, filter);
+        yield_constr.constraint(builder, constr);
+    }
+    {
+        let constr = builder.mul_extension(filter, channel.addr_context);
+        yield_constr.constraint(builder, constr);
+    }
+    {
+        let constr = builder.arithmetic_extension(
+            F::ONE,
+            -F::from_canonical_usize(Segment::GlobalMetadata as usize),
+            filter,
+            channel.addr_segment,
+            filter,
+        );
+        yield_constr.constraint(builder, constr);
+    }
+    {
+        let constr = builder.arithmetic_extension(
+            F::ONE,
+            -F::from_canonical_usize(GlobalMetadata::Hardfork as usize),
+            filter,
+            channel.addr_virtual,
+            filter,
+        );
+        yield_constr.constraint(builder, constr);
+    }
+    // The last channel is used by the push, and the others are unused.
+    for channel in &lv.mem_channels[HARDFORK_CHANNEL + 1..NUM_GP_CHANNELS - 1] {
+        let constr = builder.mul_extension(filter, channel.used);
+        yield_constr.constraint(builder, constr);
+    }
+
+    // In user mode, `PUSH0` is only valid from Shanghai onwards.
+    let shanghai_offset = lv.general.push0().shanghai_offset;
+    {
+        let constr = builder.mul_sub_extension(shanghai_offset, shanghai_offset, shanghai_offset);
+        let constr = builder.mul_extension(filter, constr);
+        yield_constr.constraint(builder, constr);
+    }
+    {
+        let user_filter = builder.mul_sub_extension(filter, lv.is_kernel_mode, filter);
+        let diff = builder.sub_extension(channel.value[0], shanghai_offset);
+        let diff = builder
+            .add_const_extension(diff, -F::from_canonical_usize(Hardfork::Shanghai as usize));
+        // `user_filter` is `-filter * (1 - is_kernel_mode)`, which doesn't change the constraint.
+        let constr = builder.mul_extension(user_filter, diff);
+        yield_constr.constraint(builder, constr);
+    }
 }
@@ -106,7 +106,8 @@ pub(crate) const STACK_BEHAVIORS: OpsColumnsView<Option<StackBehavior>> = OpsCol
         num_pops: 0,
         pushes: true,
         new_top_stack_channel: None,
-        disable_other_channels: true,
+        // The other channels are constrained in `push0.rs`.
+        disable_other_channels: false,
     }),
     push: None, // TODO
     dup: None,
@@ -479,17 +479,12 @@ where
             }
         }
 
+        // Check that every proof starts from the challenger state reached after drawing the CTL
+        // challenges, as tables are proven concurrently.
         let state = challenger.compact(&mut builder);
-        for (&before, &s) in zip_eq(state.as_ref(), pis[0].challenger_state_before.as_ref()) {
-            builder.connect(before, s);
-        }
-        // Check that the challenger state is consistent between proofs.
-        for i in 1..NUM_TABLES {
-            for (&before, &after) in zip_eq(
-                pis[i].challenger_state_before.as_ref(),
-                pis[i - 1].challenger_state_after.as_ref(),
-            ) {
-                builder.connect(before, after);
+        for pi in &pis {
+            for (&before, &s) in zip_eq(pi.challenger_state_before.as_ref(), state.as_ref()) {
+                builder.connect(before, s);
             }
         }
 
@@ -901,6 +896,30 @@ where
         }
     }
 
+    /// The range of degrees, i.e. log2 of trace lengths, supported by these circuits for each
+    /// table. Blocks can be checked against these ahead of proving with
+    /// `generation::trace_sizes::estimate_trace_sizes`.
+    pub fn degree_bits_ranges(&self) -> anyhow::Result<[Range<usize>; NUM_TABLES]> {
+        let ranges = self
+            .by_table
+            .iter()
+            .zip(Table::all())
+            .map(|(table_circuits, table)| {
+                let sizes = &table_circuits.by_stark_size;
+                match (sizes.keys().next(), sizes.keys().next_back()) {
+                    (Some(&start), Some(&end)) => Ok(start..end + 1),
+                    _ => Err(anyhow::Error::msg(format!(
+                        "No preprocessed circuits for {:?} table.",
+                        table
+                    ))),
+                }
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(ranges
+            .try_into()
+            .expect("There should be exactly one range per table"))
+    }
+
     /// Create a proof for each STARK, then combine them, eventually culminating in a root proof.
     pub fn prove_root(
         &self,
@@ -0,0 +1,580 @@
+//! Loading of `GenerationInputs` from a block and the pre-state it accesses, saved as JSON.
+//!
+//! The file contains a single object, whose values are hex strings unless stated otherwise:
+//!
+//! ```text
+//! {
+//!   "block": {
+//!     "miner", "timestamp", "number", "difficulty", "mixHash", "gasLimit", "gasUsed",
+//!     "baseFeePerGas" (optional before London), "logsBloom",
+//!     "stateRoot", "transactionsRoot", "receiptsRoot", "hash",
+//!     "transactions": [signed transactions, RLP-encoded as by `eth_getRawTransactionByHash`]
+//!   },
+//!   "chainId",
+//!   "hardfork": a variant of `Hardfork`, e.g. "Shanghai",
+//!   "genesisStateRoot" (optional, defaults to the root of an empty trie),
+//!   "blockHashes" (optional): the hashes of up to 256 previous blocks, oldest first,
+//!   "parentStateRoot": the state root before the block, required with "witness",
+//!   "witness": an execution witness, as returned by `debug_executionWitness`, i.e.
+//!     { "state": [RLP-encoded trie nodes], "codes": [contract codes] },
+//!   "prestate": the accessed accounts, as output by the `prestateTracer`, i.e.
+//!     { address: { "balance", "nonce", "code", "storage": { slot: value } } }
+//! }
+//! ```
+//!
+//! Exactly one of `witness` and `prestate` must be given. With a witness, the state and storage
+//! tries are rebuilt from the given nodes, and the subtries whose nodes aren't given are hashed
+//! out. A prestate only lists accounts, so the tries built from it contain these accounts alone,
+//! and their roots only match the block's if the prestate covers the whole state.
+//!
+//! If the kernel later needs a node which was hashed out, trace generation fails with an error
+//! naming the hash of that node.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, ensure, Context};
+use eth_trie_utils::nibbles::Nibbles;
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{Address, BigEndianHash, H256, U256, U512};
+use keccak_hash::keccak;
+use rlp::Rlp;
+use serde::Deserialize;
+
+use crate::generation::mpt::AccountRlp;
+use crate::generation::{GenerationInputs, TrieInputs};
+use crate::hardfork::Hardfork;
+use crate::proof::{BlockHashes, BlockMetadata, TrieRoots};
+use crate::Node;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockWitnessJson {
+    block: BlockJson,
+    chain_id: String,
+    hardfork: Hardfork,
+    genesis_state_root: Option<String>,
+    #[serde(default)]
+    block_hashes: Vec<String>,
+    parent_state_root: Option<String>,
+    witness: Option<ExecutionWitnessJson>,
+    prestate: Option<BTreeMap<String, AccountJson>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockJson {
+    miner: String,
+    timestamp: String,
+    number: String,
+    difficulty: String,
+    mix_hash: String,
+    gas_limit: String,
+    gas_used: String,
+    base_fee_per_gas: Option<String>,
+    logs_bloom: String,
+    state_root: String,
+    transactions_root: String,
+    receipts_root: String,
+    hash: String,
+    transactions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ExecutionWitnessJson {
+    state: Vec<String>,
+    #[serde(default)]
+    codes: Vec<String>,
+}
+
+/// An account, as output by the `prestateTracer` and in the pre-state of Ethereum state tests.
+#[derive(Deserialize)]
+pub(crate) struct AccountJson {
+    #[serde(default)]
+    balance: String,
+    #[serde(default)]
+    code: String,
+    #[serde(default)]
+    nonce: String,
+    #[serde(default)]
+    storage: BTreeMap<String, String>,
+}
+
+/// Reads a block and its pre-state from a JSON file, in the format described in the module
+/// documentation.
+pub fn load_block_witness(path: &Path) -> anyhow::Result<GenerationInputs> {
+    let json =
+        fs::read_to_string(path).with_context(|| format!("Unable to read {}", path.display()))?;
+    parse_block_witness(&json).with_context(|| format!("Unable to parse {}", path.display()))
+}
+
+/// Parses a block and its pre-state, in the format described in the module documentation.
+pub fn parse_block_witness(json: &str) -> anyhow::Result<GenerationInputs> {
+    let witness: BlockWitnessJson = serde_json::from_str(json).context("Invalid block witness")?;
+    let block = &witness.block;
+
+    let pre_state = match (&witness.witness, &witness.prestate) {
+        (Some(execution_witness), None) => {
+            let parent_state_root = witness
+                .parent_state_root
+                .as_deref()
+                .context("Missing parentStateRoot, which is required with a witness")?;
+            PreState::from_execution_witness(parse_h256(parent_state_root)?, execution_witness)?
+        }
+        (None, Some(prestate)) => PreState::from_accounts(prestate)?,
+        _ => bail!("Exactly one of witness and prestate must be given"),
+    };
+
+    ensure!(
+        witness.block_hashes.len() <= 256,
+        "At most 256 previous block hashes can be given"
+    );
+    let mut prev_hashes = vec![H256::zero(); 256 - witness.block_hashes.len()];
+    for hash in &witness.block_hashes {
+        prev_hashes.push(parse_h256(hash)?);
+    }
+
+    let logs_bloom = parse_bytes(&block.logs_bloom)?;
+    ensure!(logs_bloom.len() == 256, "Invalid logsBloom length");
+    let block_bloom =
+        core::array::from_fn(|i| U256::from_big_endian(&logs_bloom[i * 32..(i + 1) * 32]));
+    let gas_used = parse_u256(&block.gas_used)?;
+
+    let block_metadata = BlockMetadata {
+        block_beneficiary: parse_address(&block.miner)?,
+        block_timestamp: parse_u256(&block.timestamp)?,
+        block_number: parse_u256(&block.number)?,
+        block_difficulty: parse_u256(&block.difficulty)?,
+        block_random: parse_h256(&block.mix_hash)?,
+        block_gaslimit: parse_u256(&block.gas_limit)?,
+        block_chain_id: parse_u256(&witness.chain_id)?,
+        block_base_fee: block
+            .base_fee_per_gas
+            .as_deref()
+            .map_or(Ok(U256::zero()), parse_u256)?,
+        block_gas_used: gas_used,
+        block_bloom,
+        hardfork: witness.hardfork,
+    };
+
+    let genesis_state_trie_root = match &witness.genesis_state_root {
+        Some(root) => parse_h256(root)?,
+        None => HashedPartialTrie::from(Node::Empty).hash(),
+    };
+
+    Ok(GenerationInputs {
+        txn_number_before: U256::zero(),
+        gas_used_before: U256::zero(),
+        block_bloom_before: [U256::zero(); 8],
+        gas_used_after: gas_used,
+        block_bloom_after: block_bloom,
+        signed_txns: block
+            .transactions
+            .iter()
+            .map(|txn| parse_bytes(txn))
+            .collect::<anyhow::Result<_>>()?,
+        tries: pre_state.tries,
+        trie_roots_after: TrieRoots {
+            state_root: parse_h256(&block.state_root)?,
+            transactions_root: parse_h256(&block.transactions_root)?,
+            receipts_root: parse_h256(&block.receipts_root)?,
+        },
+        genesis_state_trie_root,
+        contract_code: pre_state.contract_code,
+        block_metadata,
+        block_hashes: BlockHashes {
+            prev_hashes,
+            cur_hash: parse_h256(&block.hash)?,
+        },
+        addresses: pre_state.addresses,
+    })
+}
+
+/// The tries and contract code of the state before a block.
+pub(crate) struct PreState {
+    pub(crate) tries: TrieInputs,
+    pub(crate) contract_code: HashMap<H256, Vec<u8>>,
+    pub(crate) addresses: Vec<Address>,
+}
+
+impl PreState {
+    /// Builds tries containing exactly the given accounts.
+    pub(crate) fn from_accounts(accounts: &BTreeMap<String, AccountJson>) -> anyhow::Result<Self> {
+        let mut state_trie = HashedPartialTrie::from(Node::Empty);
+        let mut storage_tries = vec![];
+        let mut contract_code = HashMap::new();
+        contract_code.insert(keccak(vec![]), vec![]);
+        let mut addresses = vec![];
+
+        for (address, account) in accounts {
+            let address = parse_address(address)?;
+
+            let mut storage_trie = HashedPartialTrie::from(Node::Empty);
+            for (slot, value) in &account.storage {
+                let value = parse_u256(value)?;
+                // Zero values are absent from the storage trie.
+                if !value.is_zero() {
+                    let slot = H256::from_uint(&parse_u256(slot)?);
+                    storage_trie.insert(
+                        Nibbles::from_h256_be(keccak(slot.0)),
+                        rlp::encode(&value).to_vec(),
+                    );
+                }
+            }
+
+            let code = parse_bytes(&account.code)?;
+            let code_hash = keccak(&code);
+            let account_rlp = AccountRlp {
+                nonce: parse_u256(&account.nonce)?,
+                balance: parse_u256(&account.balance)?,
+                storage_root: storage_trie.hash(),
+                code_hash,
+            };
+
+            let state_key = keccak(address.0);
+            state_trie.insert(
+                Nibbles::from_h256_be(state_key),
+                rlp::encode(&account_rlp).to_vec(),
+            );
+            storage_tries.push((state_key, storage_trie));
+            contract_code.insert(code_hash, code);
+            addresses.push(address);
+        }
+
+        Ok(Self {
+            tries: TrieInputs {
+                state_trie,
+                transactions_trie: Node::Empty.into(),
+                receipts_trie: Node::Empty.into(),
+                storage_tries,
+            },
+            contract_code,
+            addresses,
+        })
+    }
+
+    /// Rebuilds the state trie with the given root, and the storage tries of its accounts, from
+    /// the nodes of an execution witness.
+    fn from_execution_witness(
+        state_root: H256,
+        witness: &ExecutionWitnessJson,
+    ) -> anyhow::Result<Self> {
+        let mut nodes = HashMap::new();
+        for node in &witness.state {
+            let node = parse_bytes(node)?;
+            nodes.insert(keccak(&node), node);
+        }
+        let mut contract_code = HashMap::new();
+        contract_code.insert(keccak(vec![]), vec![]);
+        for code in &witness.codes {
+            let code = parse_bytes(code)?;
+            contract_code.insert(keccak(&code), code);
+        }
+
+        let empty_root = HashedPartialTrie::from(Node::Empty).hash();
+        ensure!(
+            state_root == empty_root || nodes.contains_key(&state_root),
+            "The witness doesn't contain the state root node {:?}",
+            state_root
+        );
+        let state_trie = partial_trie_from_nodes(state_root, &nodes)
+            .context("Unable to rebuild the state trie")?;
+
+        let mut accounts = vec![];
+        collect_leaves(&state_trie, empty_nibbles(), &mut accounts);
+        let mut storage_tries = vec![];
+        for (key, account) in accounts {
+            let state_key = H256::from_uint(
+                &key.try_into_u256()
+                    .map_err(|_| anyhow!("Invalid state key {:?}", key))?,
+            );
+            let account: AccountRlp = rlp::decode(&account)
+                .map_err(|err| anyhow!("Invalid account {:?}: {:?}", state_key, err))?;
+            let storage_trie = partial_trie_from_nodes(account.storage_root, &nodes)
+                .with_context(|| format!("Unable to rebuild the storage trie of {state_key:?}"))?;
+            storage_tries.push((state_key, storage_trie));
+        }
+
+        Ok(Self {
+            tries: TrieInputs {
+                state_trie,
+                transactions_trie: Node::Empty.into(),
+                receipts_trie: Node::Empty.into(),
+                storage_tries,
+            },
+            contract_code,
+            addresses: vec![],
+        })
+    }
+}
+
+/// Rebuilds the trie with the given root from the RLP encodings of some of its nodes, keyed by
+/// hash. Subtries whose root node isn't given are hashed out.
+pub(crate) fn partial_trie_from_nodes(
+    root: H256,
+    nodes: &HashMap<H256, Vec<u8>>,
+) -> anyhow::Result<HashedPartialTrie> {
+    if root == HashedPartialTrie::from(Node::Empty).hash() {
+        return Ok(Node::Empty.into());
+    }
+    let trie = HashedPartialTrie::new(node_from_hash(root, nodes)?);
+    ensure!(trie.hash() == root, "Rebuilt trie doesn't match {:?}", root);
+    Ok(trie)
+}
+
+fn node_from_hash(hash: H256, nodes: &HashMap<H256, Vec<u8>>) -> anyhow::Result<Node> {
+    match nodes.get(&hash) {
+        Some(node) => decode_node(&Rlp::new(node), nodes)
+            .with_context(|| format!("Invalid trie node {hash:?}")),
+        None => Ok(Node::Hash(hash)),
+    }
+}
+
+fn decode_node(rlp: &Rlp, nodes: &HashMap<H256, Vec<u8>>) -> anyhow::Result<Node> {
+    let rlp_err = |err| anyhow!("{:?}", err);
+    match rlp.item_count().map_err(rlp_err)? {
+        17 => {
+            let mut children = core::array::from_fn(|_| Node::Empty.into());
+            for (i, child) in children.iter_mut().enumerate() {
+                *child = decode_child(&rlp.at(i).map_err(rlp_err)?, nodes)?.into();
+            }
+            let value = rlp.at(16).and_then(|v| v.data()).map_err(rlp_err)?;
+            Ok(Node::Branch {
+                children,
+                value: value.to_vec(),
+            })
+        }
+        2 => {
+            let path = rlp.at(0).and_then(|p| p.data()).map_err(rlp_err)?;
+            let (nibbles, is_leaf) = decode_hex_prefix(path)?;
+            let second = rlp.at(1).map_err(rlp_err)?;
+            if is_leaf {
+                Ok(Node::Leaf {
+                    nibbles,
+                    value: second.data().map_err(rlp_err)?.to_vec(),
+                })
+            } else {
+                Ok(Node::Extension {
+                    nibbles,
+                    child: decode_child(&second, nodes)?.into(),
+                })
+            }
+        }
+        n => bail!("Trie node with {} items", n),
+    }
+}
+
+/// Decodes a reference to a child node, which is either its hash or, for nodes shorter than 32
+/// bytes, the node itself.
+fn decode_child(rlp: &Rlp, nodes: &HashMap<H256, Vec<u8>>) -> anyhow::Result<Node> {
+    if rlp.is_list() {
+        return decode_node(rlp, nodes);
+    }
+    let data = rlp.data().map_err(|err| anyhow!("{:?}", err))?;
+    match data.len() {
+        0 => Ok(Node::Empty),
+        32 => node_from_hash(H256::from_slice(data), nodes),
+        len => bail!("Child reference of {} bytes", len),
+    }
+}
+
+/// Decodes the hex-prefix encoding of a path, and returns its nibbles and whether it belongs to a
+/// leaf.
+fn decode_hex_prefix(path: &[u8]) -> anyhow::Result<(Nibbles, bool)> {
+    let Some((&first, rest)) = path.split_first() else {
+        bail!("Empty hex-prefix path");
+    };
+    let flag = first >> 4;
+    ensure!(flag < 4, "Invalid hex-prefix flag {}", flag);
+    let is_leaf = flag >= 2;
+    let is_odd = flag % 2 == 1;
+
+    let mut nibbles = vec![];
+    if is_odd {
+        nibbles.push(first & 0xf);
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0xf);
+    }
+    let packed = nibbles
+        .iter()
+        .fold(U512::zero(), |acc, &nibble| (acc << 4) | U512::from(nibble));
+    Ok((
+        Nibbles {
+            count: nibbles.len(),
+            packed,
+        },
+        is_leaf,
+    ))
+}
+
+/// Appends the full key and value of each value in the trie.
+fn collect_leaves(trie: &HashedPartialTrie, prefix: Nibbles, leaves: &mut Vec<(Nibbles, Vec<u8>)>) {
+    match &**trie {
+        Node::Empty | Node::Hash(_) => {}
+        Node::Branch { children, value } => {
+            if !value.is_empty() {
+                leaves.push((prefix, value.clone()));
+            }
+            for (i, child) in children.iter().enumerate() {
+                collect_leaves(child, prefix.merge_nibble(i as u8), leaves);
+            }
+        }
+        Node::Extension { nibbles, child } => {
+            collect_leaves(child, prefix.merge_nibbles(nibbles), leaves)
+        }
+        Node::Leaf { nibbles, value } => {
+            leaves.push((prefix.merge_nibbles(nibbles), value.clone()))
+        }
+    }
+}
+
+fn empty_nibbles() -> Nibbles {
+    Nibbles {
+        count: 0,
+        packed: U512::zero(),
+    }
+}
+
+pub(crate) fn parse_u256(s: &str) -> anyhow::Result<U256> {
+    let hex = s.strip_prefix("0x").unwrap_or(s);
+    if hex.is_empty() {
+        return Ok(U256::zero());
+    }
+    U256::from_str_radix(hex, 16).map_err(|err| anyhow!("Invalid integer {s}: {:?}", err))
+}
+
+pub(crate) fn parse_h256(s: &str) -> anyhow::Result<H256> {
+    Ok(H256::from_uint(&parse_u256(s)?))
+}
+
+pub(crate) fn parse_bytes(s: &str) -> anyhow::Result<Vec<u8>> {
+    let hex = s.strip_prefix("0x").unwrap_or(s);
+    ensure!(
+        hex.is_ascii() && hex.len() % 2 == 0,
+        "Invalid hex string {s}"
+    );
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).with_context(|| format!("Invalid hex {s}")))
+        .collect()
+}
+
+pub(crate) fn parse_address(s: &str) -> anyhow::Result<Address> {
+    let bytes = parse_bytes(s)?;
+    ensure!(bytes.len() == 20, "Invalid address {s}");
+    Ok(Address::from_slice(&bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use eth_trie_utils::nibbles::Nibbles;
+    use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+    use ethereum_types::H256;
+    use hex_literal::hex;
+    use keccak_hash::keccak;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use rlp::RlpStream;
+
+    use crate::all_stark::AllStark;
+    use crate::config::StarkConfig;
+    use crate::generation::block_witness::{
+        collect_leaves, empty_nibbles, parse_block_witness, partial_trie_from_nodes,
+    };
+    use crate::generation::generate_traces;
+    use crate::hardfork::Hardfork;
+    use crate::Node;
+
+    type F = GoldilocksField;
+    const D: usize = 2;
+
+    #[test]
+    fn test_partial_trie_from_nodes() -> anyhow::Result<()> {
+        let key_1 = H256::from([0x1b; 32]);
+        let key_2 = H256::from([0x2a; 32]);
+        let value = vec![0xab; 40];
+
+        // Leaves whose paths are all but the first nibble of their key, so of odd length.
+        let leaf = |key: H256| {
+            let mut path = key.0.to_vec();
+            path[0] = 0x30 | (path[0] & 0xf);
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&path).append(&value);
+            stream.out().to_vec()
+        };
+        let leaf_1 = leaf(key_1);
+        let leaf_2 = leaf(key_2);
+        let mut branch = RlpStream::new_list(17);
+        for i in 0..16 {
+            match i {
+                1 => branch.append(&keccak(&leaf_1)),
+                2 => branch.append(&keccak(&leaf_2)),
+                _ => branch.append_empty_data(),
+            };
+        }
+        branch.append_empty_data();
+        let branch = branch.out().to_vec();
+
+        let mut expected = HashedPartialTrie::from(Node::Empty);
+        expected.insert(Nibbles::from_h256_be(key_1), value.clone());
+        expected.insert(Nibbles::from_h256_be(key_2), value.clone());
+        let root = keccak(&branch);
+        assert_eq!(expected.hash(), root);
+
+        let mut nodes: HashMap<H256, Vec<u8>> = [leaf_1, leaf_2, branch]
+            .into_iter()
+            .map(|node| (keccak(&node), node))
+            .collect();
+        let rebuilt = partial_trie_from_nodes(root, &nodes)?;
+        let mut leaves = vec![];
+        collect_leaves(&rebuilt, empty_nibbles(), &mut leaves);
+        assert_eq!(leaves.len(), 2);
+
+        // Without its preimage, the second leaf is hashed out.
+        nodes.remove(&keccak(leaf(key_2)));
+        let rebuilt = partial_trie_from_nodes(root, &nodes)?;
+        let mut leaves = vec![];
+        collect_leaves(&rebuilt, empty_nibbles(), &mut leaves);
+        assert_eq!(leaves, vec![(Nibbles::from_h256_be(key_1), value)]);
+
+        // Without the root node, the whole trie is hashed out.
+        nodes.remove(&root);
+        let rebuilt = partial_trie_from_nodes(root, &nodes)?;
+        assert_eq!(*rebuilt, Node::Hash(root));
+        Ok(())
+    }
+    /// The fixture is a block with a single transfer, whose witness lacks the recipient's leaf.
+    #[test]
+    fn test_parse_block_witness() -> anyhow::Result<()> {
+        let inputs = parse_block_witness(include_str!("block_witness_test_data.json"))?;
+        assert_eq!(inputs.block_metadata.block_number, 1.into());
+        assert_eq!(inputs.block_metadata.block_base_fee, 0xa.into());
+        assert_eq!(inputs.block_metadata.hardfork, Hardfork::Shanghai);
+        assert_eq!(inputs.gas_used_after, 21032.into());
+        assert_eq!(inputs.signed_txns.len(), 1);
+        assert_eq!(
+            inputs.tries.state_trie.hash(),
+            H256(hex!(
+                "c417ad2050ea8506e0a5c71ae591cdca6caf5cd1e3888f13cf84750f9e38bc09"
+            ))
+        );
+
+        let recipient_leaf = H256(hex!(
+            "7e7c12e182c91269a537895698251b3986ab040a686cc77547fdea6d7b7a8fbe"
+        ));
+        let err = generate_traces::<F, D>(
+            &AllStark::default(),
+            inputs,
+            &StarkConfig::standard_fast_config(),
+            &mut TimingTree::default(),
+        )
+        .unwrap_err();
+        assert!(format!("{err:#}").contains(&format!("Missing trie node {recipient_leaf:?}")));
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use anyhow::anyhow;
+use anyhow::{anyhow, ensure};
 use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
 use ethereum_types::{Address, BigEndianHash, H256, U256};
 use plonky2::field::extension::Extendable;
@@ -21,18 +21,26 @@ use crate::cpu::columns::CpuColumnsView;
 use crate::cpu::kernel::aggregator::KERNEL;
 use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::generation::outputs::{get_outputs, GenerationOutputs};
+use crate::generation::profiler::{KernelProfile, KernelProfiler, BOOTSTRAP_FRAME};
 use crate::generation::state::GenerationState;
+use crate::generation::tracer::ExecutionTracer;
+use crate::hardfork::Hardfork;
 use crate::memory::segments::Segment;
 use crate::proof::{BlockHashes, BlockMetadata, ExtraBlockData, PublicValues, TrieRoots};
 use crate::util::h2u;
 use crate::witness::memory::{MemoryAddress, MemoryChannel};
 use crate::witness::transition::transition;
 
+pub mod block_witness;
 pub mod mpt;
 pub mod outputs;
+pub mod profiler;
 pub(crate) mod prover_input;
 pub(crate) mod rlp;
+pub mod split;
 pub(crate) mod state;
+pub mod trace_sizes;
+pub mod tracer;
 mod trie_extractor;
 
 use crate::witness::util::mem_write_log;
@@ -69,6 +77,18 @@ pub struct GenerationInputs {
     pub addresses: Vec<Address>,
 }
 
+impl GenerationInputs {
+    /// Checks that the kernel supports the rules these inputs ask for.
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            self.block_metadata.hardfork != Hardfork::Cancun,
+            "Hardfork {:?} isn't supported yet",
+            self.block_metadata.hardfork
+        );
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
 pub struct TrieInputs {
     /// A partial version of the state trie prior to these transactions. It should include all nodes
@@ -88,7 +108,7 @@ pub struct TrieInputs {
     pub storage_tries: Vec<(H256, HashedPartialTrie)>,
 }
 
-fn apply_metadata_and_tries_memops<F: RichField + Extendable<D>, const D: usize>(
+pub(crate) fn apply_metadata_and_tries_memops<F: RichField + Extendable<D>, const D: usize>(
     state: &mut GenerationState<F>,
     inputs: &GenerationInputs,
 ) {
@@ -110,6 +130,7 @@ fn apply_metadata_and_tries_memops<F: RichField + Extendable<D>, const D: usize>
         (GlobalMetadata::BlockGasLimit, metadata.block_gaslimit),
         (GlobalMetadata::BlockChainId, metadata.block_chain_id),
         (GlobalMetadata::BlockBaseFee, metadata.block_base_fee),
+        (GlobalMetadata::Hardfork, metadata.hardfork.into()),
         (
             GlobalMetadata::BlockCurrentHash,
             h2u(inputs.block_hashes.cur_hash),
@@ -223,24 +244,66 @@ pub fn generate_traces<F: RichField + Extendable<D>, const D: usize>(
     PublicValues,
     GenerationOutputs,
 )> {
-    let mut state = GenerationState::<F>::new(inputs.clone(), &KERNEL.code)
-        .map_err(|err| anyhow!("Failed to parse all the initial prover inputs: {:?}", err))?;
-
-    apply_metadata_and_tries_memops(&mut state, &inputs);
-
-    generate_bootstrap_kernel::<F>(&mut state);
+    generate_traces_with_profiler(all_stark, inputs, config, timing, None, None)
+}
 
-    timed!(timing, "simulate CPU", simulate_cpu(&mut state)?);
+/// Same as `generate_traces`, but also returns a profile attributing the rows generated in each
+/// table to the kernel routines which generated them.
+pub fn generate_traces_with_profile<F: RichField + Extendable<D>, const D: usize>(
+    all_stark: &AllStark<F, D>,
+    inputs: GenerationInputs,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> anyhow::Result<(
+    [Vec<PolynomialValues<F>>; NUM_TABLES],
+    PublicValues,
+    GenerationOutputs,
+    KernelProfile,
+)> {
+    let mut profiler = KernelProfiler::default();
+    let (tables, public_values, outputs) = generate_traces_with_profiler(
+        all_stark,
+        inputs,
+        config,
+        timing,
+        Some(&mut profiler),
+        None,
+    )?;
+    Ok((tables, public_values, outputs, profiler.finish()))
+}
 
-    assert!(
-        state.mpt_prover_inputs.is_empty(),
-        "All MPT data should have been consumed"
-    );
+/// Same as `generate_traces`, but also returns an EIP-3155 trace of the user code executed by the
+/// transactions.
+pub fn generate_traces_with_tracer<F: RichField + Extendable<D>, const D: usize>(
+    all_stark: &AllStark<F, D>,
+    inputs: GenerationInputs,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> anyhow::Result<(
+    [Vec<PolynomialValues<F>>; NUM_TABLES],
+    PublicValues,
+    GenerationOutputs,
+    ExecutionTracer,
+)> {
+    let mut tracer = ExecutionTracer::default();
+    let (tables, public_values, outputs) =
+        generate_traces_with_profiler(all_stark, inputs, config, timing, None, Some(&mut tracer))?;
+    Ok((tables, public_values, outputs, tracer))
+}
 
-    log::info!(
-        "Trace lengths (before padding): {:?}",
-        state.traces.get_lengths()
-    );
+fn generate_traces_with_profiler<F: RichField + Extendable<D>, const D: usize>(
+    all_stark: &AllStark<F, D>,
+    inputs: GenerationInputs,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+    profiler: Option<&mut KernelProfiler>,
+    tracer: Option<&mut ExecutionTracer>,
+) -> anyhow::Result<(
+    [Vec<PolynomialValues<F>>; NUM_TABLES],
+    PublicValues,
+    GenerationOutputs,
+)> {
+    let mut state = run_kernel::<F, D>(&inputs, timing, profiler, tracer)?;
 
     let outputs = get_outputs(&mut state)
         .map_err(|err| anyhow!("Failed to generate post-state info: {:?}", err))?;
@@ -286,15 +349,58 @@ pub fn generate_traces<F: RichField + Extendable<D>, const D: usize>(
     Ok((tables, public_values, outputs))
 }
 
+/// Initializes a `GenerationState` with the given inputs, and runs the kernel until it halts.
+pub(crate) fn run_kernel<F: RichField + Extendable<D>, const D: usize>(
+    inputs: &GenerationInputs,
+    timing: &mut TimingTree,
+    mut profiler: Option<&mut KernelProfiler>,
+    tracer: Option<&mut ExecutionTracer>,
+) -> anyhow::Result<GenerationState<F>> {
+    inputs.validate()?;
+    let mut state = GenerationState::<F>::new(inputs.clone(), &KERNEL.code)
+        .map_err(|err| anyhow!("Failed to parse all the initial prover inputs: {:?}", err))?;
+    let initial_checkpoint = state.traces.checkpoint();
+
+    apply_metadata_and_tries_memops(&mut state, inputs);
+
+    generate_bootstrap_kernel::<F>(&mut state);
+
+    if let Some(profiler) = profiler.as_deref_mut() {
+        profiler.record_in_frame(BOOTSTRAP_FRAME, state.traces.rows_since(initial_checkpoint));
+    }
+
+    timed!(
+        timing,
+        "simulate CPU",
+        simulate_cpu(&mut state, profiler, tracer)?
+    );
+
+    assert!(
+        state.mpt_prover_inputs.is_empty(),
+        "All MPT data should have been consumed"
+    );
+
+    log::info!(
+        "Trace lengths (before padding): {:?}",
+        state.traces.get_lengths()
+    );
+
+    Ok(state)
+}
+
 fn simulate_cpu<F: RichField + Extendable<D>, const D: usize>(
     state: &mut GenerationState<F>,
+    mut profiler: Option<&mut KernelProfiler>,
+    mut tracer: Option<&mut ExecutionTracer>,
 ) -> anyhow::Result<()> {
     let halt_pc = KERNEL.global_labels["halt"];
 
     loop {
         // If we've reached the kernel's halt routine, and our trace length is a power of 2, stop.
         let pc = state.registers.program_counter;
-        let halt = state.registers.is_kernel && pc == halt_pc;
+        let is_kernel = state.registers.is_kernel;
+        let checkpoint = state.traces.checkpoint();
+        let halt = is_kernel && pc == halt_pc;
         if halt {
             log::info!("CPU halted after {} cycles", state.traces.clock());
 
@@ -319,9 +425,60 @@ fn simulate_cpu<F: RichField + Extendable<D>, const D: usize>(
             }
             log::info!("CPU trace padded to {} cycles", state.traces.clock());
 
+            if let Some(profiler) = profiler {
+                profiler.record_instruction(pc, is_kernel, state.traces.rows_since(checkpoint));
+            }
+
             return Ok(());
         }
 
+        if let Some(tracer) = tracer.as_deref_mut() {
+            tracer
+                .record_instruction(state)
+                .map_err(|err| anyhow!("Failed to trace instruction: {:?}", err))?;
+        }
+
         transition(state)?;
+
+        if !is_kernel {
+            if let Some(tracer) = tracer.as_deref_mut() {
+                tracer.record_exception(state);
+            }
+        }
+
+        if let Some(profiler) = profiler.as_deref_mut() {
+            profiler.record_instruction(pc, is_kernel, state.traces.rows_since(checkpoint));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+
+    use crate::all_stark::AllStark;
+    use crate::config::StarkConfig;
+    use crate::generation::{generate_traces, GenerationInputs};
+    use crate::hardfork::Hardfork;
+    use crate::proof::BlockMetadata;
+
+    type F = GoldilocksField;
+    const D: usize = 2;
+
+    #[test]
+    fn test_unsupported_hardfork() {
+        let inputs = GenerationInputs {
+            block_metadata: BlockMetadata {
+                hardfork: Hardfork::Cancun,
+                ..BlockMetadata::default()
+            },
+            ..GenerationInputs::default()
+        };
+        let all_stark = AllStark::<F, D>::default();
+        let config = StarkConfig::standard_fast_config();
+        let err =
+            generate_traces(&all_stark, inputs, &config, &mut TimingTree::default()).unwrap_err();
+        assert_eq!(err.to_string(), "Hardfork Cancun isn't supported yet");
     }
 }
@@ -57,10 +57,15 @@ pub(crate) fn all_mpt_prover_inputs_reversed(
 }
 
 pub(crate) fn parse_receipts(rlp: &[u8]) -> Result<Vec<U256>, ProgramError> {
+    // Receipts of typed transactions are prefixed with the transaction type, which is stored as is.
+    let (txn_type, rlp) = match rlp.split_first() {
+        Some((&txn_type @ (1 | 2), rest)) => (Some(txn_type), rest),
+        _ => (None, rlp),
+    };
     let payload_info = PayloadInfo::from(rlp).map_err(|_| ProgramError::InvalidRlp)?;
     let decoded_receipt: LegacyReceiptRlp =
         rlp::decode(rlp).map_err(|_| ProgramError::InvalidRlp)?;
-    let mut parsed_receipt = Vec::new();
+    let mut parsed_receipt: Vec<U256> = txn_type.into_iter().map(U256::from).collect();
 
     parsed_receipt.push(payload_info.value_len.into()); // payload_len of the entire receipt
     parsed_receipt.push((decoded_receipt.status as u8).into());
@@ -0,0 +1,220 @@
+//! Attribution of execution costs to kernel routines.
+//!
+//! Each executed instruction is attributed to the global label enclosing its program counter.
+//! Since the kernel has no explicit call instruction, calls and returns are inferred from control
+//! flow: moving to a label which is already on the current call stack is treated as a return to
+//! it, and moving to any other label is treated as a call.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use itertools::Itertools;
+
+use crate::all_stark::{Table, NUM_TABLES};
+use crate::cpu::kernel::aggregator::KERNEL;
+
+/// The frame name used for instructions executed in user mode.
+pub(crate) const USER_CODE_FRAME: &str = "<user_code>";
+/// The frame name used for the rows generated by the bootstrap kernel.
+pub(crate) const BOOTSTRAP_FRAME: &str = "<bootstrap>";
+
+/// The number of rows generated in each table, indexed by `Table`. For the CPU table, this is the
+/// number of cycles.
+pub type TableRows = [usize; NUM_TABLES];
+
+/// The costs attributed to a single kernel routine.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RoutineCosts {
+    /// The rows generated while this routine was on the call stack.
+    pub inclusive: TableRows,
+    /// The rows generated while this routine was at the top of the call stack.
+    pub exclusive: TableRows,
+}
+
+/// A profile of a kernel execution, as produced by `generate_traces_with_profile` or by the
+/// interpreter.
+#[derive(Clone, Debug, Default)]
+pub struct KernelProfile {
+    /// The costs of each routine, keyed by global label.
+    pub routines: HashMap<String, RoutineCosts>,
+    /// The exclusive costs of each distinct call stack, outermost frame first.
+    stacks: HashMap<Vec<String>, TableRows>,
+}
+
+impl KernelProfile {
+    /// The total number of rows generated in each table.
+    pub fn total(&self) -> TableRows {
+        let mut total = [0; NUM_TABLES];
+        for rows in self.stacks.values() {
+            add_rows(&mut total, rows);
+        }
+        total
+    }
+
+    /// Routines sorted by decreasing exclusive rows in the given table.
+    pub fn top_routines(&self, table: Table) -> Vec<(&str, &RoutineCosts)> {
+        self.routines
+            .iter()
+            .map(|(label, costs)| (label.as_str(), costs))
+            .sorted_by(|(label_a, costs_a), (label_b, costs_b)| {
+                let t = table as usize;
+                costs_b.exclusive[t]
+                    .cmp(&costs_a.exclusive[t])
+                    .then(label_a.cmp(label_b))
+            })
+            .collect()
+    }
+
+    /// Writes the rows generated in the given table in the collapsed-stack format consumed by
+    /// flamegraph tools, i.e. one `frame;frame;...;frame count` line per call stack.
+    pub fn write_collapsed_stacks<W: Write>(&self, table: Table, out: &mut W) -> io::Result<()> {
+        for (stack, rows) in self.stacks.iter().sorted() {
+            let count = rows[table as usize];
+            if count > 0 {
+                writeln!(out, "{} {}", stack.join(";"), count)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builds a `KernelProfile` from a sequence of executed instructions.
+#[derive(Debug, Default)]
+pub(crate) struct KernelProfiler {
+    call_stack: Vec<String>,
+    profile: KernelProfile,
+}
+
+impl KernelProfiler {
+    /// Attributes the rows generated by an instruction to the routine containing `pc`.
+    pub(crate) fn record_instruction(&mut self, pc: usize, is_kernel: bool, rows: TableRows) {
+        let frame = if is_kernel {
+            KERNEL.enclosing_label(pc).unwrap_or("<unknown>")
+        } else {
+            USER_CODE_FRAME
+        };
+        self.enter(frame);
+        self.record(rows);
+    }
+
+    /// Attributes rows to the given frame, on top of the current call stack, without changing it.
+    pub(crate) fn record_in_frame(&mut self, frame: &str, rows: TableRows) {
+        self.call_stack.push(frame.to_string());
+        self.record(rows);
+        self.call_stack.pop();
+    }
+
+    pub(crate) fn finish(self) -> KernelProfile {
+        self.profile
+    }
+
+    /// Updates the call stack so that `frame` is at its top.
+    fn enter(&mut self, frame: &str) {
+        if self.call_stack.last().is_some_and(|top| top == frame) {
+            return;
+        }
+        match self.call_stack.iter().rposition(|f| f == frame) {
+            Some(pos) => self.call_stack.truncate(pos + 1),
+            None => self.call_stack.push(frame.to_string()),
+        }
+    }
+
+    fn record(&mut self, rows: TableRows) {
+        let Some(top) = self.call_stack.last() else {
+            return;
+        };
+        let routines = &mut self.profile.routines;
+        add_rows(
+            &mut routines.entry(top.clone()).or_default().exclusive,
+            &rows,
+        );
+        for frame in self.call_stack.iter().unique() {
+            add_rows(
+                &mut routines.entry(frame.clone()).or_default().inclusive,
+                &rows,
+            );
+        }
+        add_rows(
+            self.profile
+                .stacks
+                .entry(self.call_stack.clone())
+                .or_insert([0; NUM_TABLES]),
+            &rows,
+        );
+    }
+}
+
+fn add_rows(acc: &mut TableRows, rows: &TableRows) {
+    for (a, r) in acc.iter_mut().zip(rows) {
+        *a += r;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::all_stark::{Table, NUM_TABLES};
+    use crate::cpu::kernel::aggregator::KERNEL;
+    use crate::cpu::kernel::interpreter::Interpreter;
+    use crate::generation::profiler::{KernelProfiler, USER_CODE_FRAME};
+    use crate::memory::segments::Segment;
+
+    fn cpu_rows(n: usize) -> [usize; NUM_TABLES] {
+        let mut rows = [0; NUM_TABLES];
+        rows[Table::Cpu as usize] = n;
+        rows
+    }
+
+    #[test]
+    fn test_inclusive_exclusive() {
+        let main = KERNEL.global_labels["main"];
+        let mload_packing = KERNEL.global_labels["mload_packing"];
+
+        let mut profiler = KernelProfiler::default();
+        profiler.record_instruction(main, true, cpu_rows(1));
+        profiler.record_instruction(mload_packing, true, cpu_rows(2));
+        profiler.record_instruction(0, false, cpu_rows(4));
+        profiler.record_instruction(mload_packing, true, cpu_rows(8));
+        profiler.record_instruction(main, true, cpu_rows(16));
+        let profile = profiler.finish();
+
+        let cpu = Table::Cpu as usize;
+        assert_eq!(profile.routines["main"].inclusive[cpu], 31);
+        assert_eq!(profile.routines["main"].exclusive[cpu], 17);
+        assert_eq!(profile.routines["mload_packing"].inclusive[cpu], 14);
+        assert_eq!(profile.routines["mload_packing"].exclusive[cpu], 10);
+        assert_eq!(profile.routines[USER_CODE_FRAME].exclusive[cpu], 4);
+        assert_eq!(profile.total()[cpu], 31);
+
+        let mut collapsed = vec![];
+        profile
+            .write_collapsed_stacks(Table::Cpu, &mut collapsed)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(collapsed).unwrap(),
+            "main 17\nmain;mload_packing 10\nmain;mload_packing;<user_code> 4\n"
+        );
+    }
+
+    #[test]
+    fn test_interpreter_profile() -> anyhow::Result<()> {
+        let retdest = 0xDEADBEEFu32.into();
+        let initial_stack = vec![
+            retdest,
+            1.into(),
+            0.into(),
+            (Segment::MainMemory as usize).into(),
+            0.into(),
+        ];
+        let mut interpreter =
+            Interpreter::new_with_kernel(KERNEL.global_labels["mload_packing"], initial_stack);
+        interpreter.enable_profiling();
+        interpreter.run()?;
+        let profile = interpreter.take_profile().unwrap();
+
+        // MLOAD_32BYTES, SWAP1, JUMP
+        let cpu = Table::Cpu as usize;
+        assert_eq!(profile.routines["mload_packing"].exclusive[cpu], 3);
+        assert_eq!(profile.total()[cpu], 3);
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::mem::transmute;
 use std::str::FromStr;
 
@@ -5,6 +6,10 @@ use anyhow::{bail, Error};
 use ethereum_types::{BigEndianHash, H256, U256, U512};
 use itertools::Itertools;
 use num_bigint::BigUint;
+use plonky2::field::bn254_base::Bn254BaseParameters;
+use plonky2::field::montgomery::{MontgomeryField, MontgomeryParameters};
+use plonky2::field::secp256k1_base::Secp256K1BaseParameters;
+use plonky2::field::secp256k1_scalar::Secp256K1ScalarParameters;
 use plonky2::field::types::Field;
 use serde::{Deserialize, Serialize};
 
@@ -32,6 +37,12 @@ impl From<Vec<String>> for ProverInputFn {
     }
 }
 
+impl fmt::Display for ProverInputFn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join("::"))
+    }
+}
+
 impl<F: Field> GenerationState<F> {
     pub(crate) fn prover_input(&mut self, input_fn: &ProverInputFn) -> Result<U256, ProgramError> {
         match input_fn.0[0].as_str() {
@@ -296,7 +307,12 @@ impl EvmField {
         if x >= n {
             return Err(ProgramError::ProverInputError(InvalidInput));
         };
-        modexp(x, n - 2, n)
+        match self {
+            EvmField::Bn254Base => Ok(montgomery_inverse::<Bn254BaseParameters>(x)),
+            EvmField::Secp256k1Base => Ok(montgomery_inverse::<Secp256K1BaseParameters>(x)),
+            EvmField::Secp256k1Scalar => Ok(montgomery_inverse::<Secp256K1ScalarParameters>(x)),
+            _ => modexp(x, n - 2, n),
+        }
     }
 
     fn sqrt(&self, x: U256) -> Result<U256, ProgramError> {
@@ -369,6 +385,16 @@ impl EvmField {
     }
 }
 
+/// Inverts a canonical element of the field described by `P`, mapping zero to zero like `modexp`.
+fn montgomery_inverse<P: MontgomeryParameters<4>>(x: U256) -> U256 {
+    let x = MontgomeryField::<P, 4>::from_canonical_limbs(x.0);
+    U256(
+        x.try_inverse()
+            .unwrap_or(MontgomeryField::<P, 4>::ZERO)
+            .to_canonical_limbs(),
+    )
+}
+
 fn modexp(x: U256, e: U256, n: U256) -> Result<U256, ProgramError> {
     let mut current = x;
     let mut product = U256::one();
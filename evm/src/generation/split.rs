@@ -0,0 +1,309 @@
+//! Splitting of a block into transaction ranges, each small enough to be proven by the circuits
+//! preprocessed in `AllRecursiveCircuits`.
+//!
+//! The block is first executed one transaction at a time, recording the tries, gas used and bloom
+//! filter between consecutive transactions. These intermediate values are then used to build a
+//! `GenerationInputs` for any range of transactions, which can be passed to `prove_root`, and the
+//! resulting proofs aggregated with `prove_aggregation`.
+//!
+//! If some transaction can't be executed, `ExecutedBlock::execute_prefix` keeps the transactions
+//! before it, so that this prefix of the block can still be split and proven.
+
+use core::fmt;
+use core::ops::Range;
+use std::collections::HashMap;
+
+use anyhow::{anyhow, ensure};
+use eth_trie_utils::partial_trie::PartialTrie;
+use ethereum_types::{H256, U256};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::util::timing::TimingTree;
+
+use crate::all_stark::NUM_TABLES;
+use crate::config::StarkConfig;
+use crate::cpu::bootstrap_kernel::generate_bootstrap_kernel;
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::generation::state::GenerationState;
+use crate::generation::trace_sizes::estimate_trace_sizes;
+use crate::generation::trie_extractor::read_trie_inputs;
+use crate::generation::{apply_metadata_and_tries_memops, GenerationInputs, TrieInputs};
+use crate::memory::segments::Segment;
+use crate::proof::TrieRoots;
+use crate::witness::memory::MemoryAddress;
+use crate::witness::transition::transition;
+use crate::witness::util::stack_peek;
+
+/// The block-level values before some transaction of the block, or after its last transaction.
+#[derive(Clone, Debug)]
+struct TxnBoundary {
+    tries: TrieInputs,
+    gas_used: U256,
+    block_bloom: [U256; 8],
+}
+
+impl TxnBoundary {
+    fn trie_roots(&self) -> TrieRoots {
+        TrieRoots {
+            state_root: self.tries.state_trie.hash(),
+            transactions_root: self.tries.transactions_trie.hash(),
+            receipts_root: self.tries.receipts_trie.hash(),
+        }
+    }
+}
+
+/// A transaction which could not be executed, e.g. because it hit a kernel bug or an unsupported
+/// feature.
+#[derive(Debug)]
+pub struct TxnFailure {
+    /// The index of the transaction, from the first transaction of the block.
+    pub txn_index: usize,
+    /// The execution error, which wraps an `ExecutionError` if it was raised by the CPU.
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for TxnFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Failed to execute transaction {}: {:#}",
+            self.txn_index, self.error
+        )
+    }
+}
+
+impl std::error::Error for TxnFailure {}
+
+/// A block whose transactions have been executed, without generating any proof, so that
+/// `GenerationInputs` can be built for any range of its transactions.
+#[derive(Clone, Debug)]
+pub struct ExecutedBlock {
+    inputs: GenerationInputs,
+    /// The values before each transaction, followed by the values after the last one.
+    boundaries: Vec<TxnBoundary>,
+    /// The code of all contracts known at the start of the block or deployed during it.
+    contract_code: HashMap<H256, Vec<u8>>,
+}
+
+impl ExecutedBlock {
+    /// Executes all transactions of a block. The input tries should include all nodes accessed by
+    /// the whole block; `trie_roots_after`, `gas_used_after` and `block_bloom_after` are ignored,
+    /// since they are computed during execution.
+    pub fn new<F: RichField + Extendable<D>, const D: usize>(
+        inputs: GenerationInputs,
+    ) -> anyhow::Result<Self> {
+        match Self::execute_prefix::<F, D>(inputs) {
+            (block, None) => Ok(block),
+            (_, Some(failure)) => Err(failure.into()),
+        }
+    }
+
+    /// Executes the transactions of a block one at a time, each from a fresh `GenerationState`
+    /// seeded with the values left by the previous one, and stops at the first transaction which
+    /// fails. Returns the block truncated to the transactions executed successfully, along with
+    /// the failure, if any.
+    pub fn execute_prefix<F: RichField + Extendable<D>, const D: usize>(
+        mut inputs: GenerationInputs,
+    ) -> (Self, Option<TxnFailure>) {
+        let mut boundary = TxnBoundary {
+            tries: inputs.tries.clone(),
+            gas_used: inputs.gas_used_before,
+            block_bloom: inputs.block_bloom_before,
+        };
+        let mut contract_code = inputs.contract_code.clone();
+        let mut boundaries = vec![boundary.clone()];
+        let mut failure = None;
+
+        for (i, txn) in inputs.signed_txns.iter().enumerate() {
+            let txn_inputs = GenerationInputs {
+                txn_number_before: inputs.txn_number_before + i,
+                gas_used_before: boundary.gas_used,
+                block_bloom_before: boundary.block_bloom,
+                signed_txns: vec![txn.clone()],
+                tries: boundary.tries,
+                contract_code: contract_code.clone(),
+                ..inputs.clone()
+            };
+            match execute_txns::<F, D>(txn_inputs) {
+                Ok((next_boundary, next_contract_code)) => {
+                    boundary = next_boundary;
+                    contract_code = next_contract_code;
+                    boundaries.push(boundary.clone());
+                }
+                Err(error) => {
+                    failure = Some(TxnFailure {
+                        txn_index: i,
+                        error,
+                    });
+                    break;
+                }
+            }
+        }
+
+        inputs.signed_txns.truncate(boundaries.len() - 1);
+        let block = Self {
+            inputs,
+            boundaries,
+            contract_code,
+        };
+        (block, failure)
+    }
+
+    pub fn num_txns(&self) -> usize {
+        self.inputs.signed_txns.len()
+    }
+
+    /// The trie roots after the last transaction of the block.
+    pub fn trie_roots_after(&self) -> TrieRoots {
+        self.boundaries[self.num_txns()].trie_roots()
+    }
+
+    /// The tries after the last transaction of the block.
+    pub fn tries_after(&self) -> &TrieInputs {
+        &self.boundaries[self.num_txns()].tries
+    }
+
+    /// The cumulative gas used after the last transaction of the block.
+    pub fn gas_used_after(&self) -> U256 {
+        self.boundaries[self.num_txns()].gas_used
+    }
+
+    /// The bloom filter after the last transaction of the block.
+    pub fn block_bloom_after(&self) -> [U256; 8] {
+        self.boundaries[self.num_txns()].block_bloom
+    }
+
+    /// Builds the inputs for proving the given range of transactions, indexed from the first
+    /// transaction of the block.
+    pub fn txn_range_inputs(&self, txns: Range<usize>) -> GenerationInputs {
+        assert!(
+            txns.start <= txns.end && txns.end <= self.num_txns(),
+            "Invalid transaction range {txns:?}"
+        );
+        let before = &self.boundaries[txns.start];
+        let after = &self.boundaries[txns.end];
+
+        GenerationInputs {
+            txn_number_before: self.inputs.txn_number_before + txns.start,
+            gas_used_before: before.gas_used,
+            block_bloom_before: before.block_bloom,
+            gas_used_after: after.gas_used,
+            block_bloom_after: after.block_bloom,
+            signed_txns: self.inputs.signed_txns[txns].to_vec(),
+            tries: before.tries.clone(),
+            trie_roots_after: after.trie_roots(),
+            contract_code: self.contract_code.clone(),
+            ..self.inputs.clone()
+        }
+    }
+
+    /// Greedily splits the block into consecutive ranges of transactions, such that each table's
+    /// trace for a range fits the given `degree_bits_ranges`, as passed to
+    /// `AllRecursiveCircuits::new`. Returns the inputs for each range, in order.
+    ///
+    /// Traces only grow as transactions are added to a range, so the longest range starting at
+    /// some transaction is found with a binary search, each step of which runs the kernel on the
+    /// candidate range without generating the tables. A range with a table smaller than its degree
+    /// range allows can't be made to fit by adding transactions, and is reported as an error.
+    pub fn split<F: RichField + Extendable<D>, const D: usize>(
+        &self,
+        degree_bits_ranges: &[Range<usize>; NUM_TABLES],
+        config: &StarkConfig,
+        timing: &mut TimingTree,
+    ) -> anyhow::Result<Vec<GenerationInputs>> {
+        let num_txns = self.num_txns();
+        if num_txns == 0 {
+            return Ok(vec![self.txn_range_inputs(0..0)]);
+        }
+
+        let mut sizes_of = |txns: Range<usize>| {
+            estimate_trace_sizes::<F, D>(self.txn_range_inputs(txns), config, timing)
+        };
+
+        let mut segments = vec![];
+        let mut start = 0;
+        while start < num_txns {
+            let mut sizes = sizes_of(start..start + 1)?;
+            ensure!(
+                !sizes.requires_split(degree_bits_ranges),
+                "Transaction {} alone is too large for the given degree ranges",
+                start
+            );
+
+            // `start..end` isn't too large, and no range ending after `last` is.
+            let mut end = start + 1;
+            let mut last = num_txns;
+            while end < last {
+                let mid = end + (last - end + 1) / 2;
+                let mid_sizes = sizes_of(start..mid)?;
+                if mid_sizes.requires_split(degree_bits_ranges) {
+                    last = mid - 1;
+                } else {
+                    end = mid;
+                    sizes = mid_sizes;
+                }
+            }
+
+            let unfit_tables = sizes.unfit_tables(degree_bits_ranges);
+            ensure!(
+                unfit_tables.is_empty(),
+                "Transactions {}..{} don't fit the given degree ranges: {:?}",
+                start,
+                end,
+                unfit_tables
+            );
+            segments.push(self.txn_range_inputs(start..end));
+            start = end;
+        }
+
+        Ok(segments)
+    }
+}
+
+/// Executes a block and splits it into transaction ranges fitting the given `degree_bits_ranges`.
+/// See `ExecutedBlock::split`.
+pub fn split_block<F: RichField + Extendable<D>, const D: usize>(
+    inputs: GenerationInputs,
+    degree_bits_ranges: &[Range<usize>; NUM_TABLES],
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> anyhow::Result<Vec<GenerationInputs>> {
+    ExecutedBlock::new::<F, D>(inputs)?.split::<F, D>(degree_bits_ranges, config, timing)
+}
+
+/// Runs the kernel on the given inputs until it is about to check the final block values, and
+/// reads these values from memory instead. Also returns the code of all contracts known at that
+/// point.
+fn execute_txns<F: RichField + Extendable<D>, const D: usize>(
+    inputs: GenerationInputs,
+) -> anyhow::Result<(TxnBoundary, HashMap<H256, Vec<u8>>)> {
+    let mut state = GenerationState::<F>::new(inputs.clone(), &KERNEL.code)
+        .map_err(|err| anyhow!("Failed to parse all the initial prover inputs: {:?}", err))?;
+    apply_metadata_and_tries_memops::<F, D>(&mut state, &inputs);
+    generate_bootstrap_kernel::<F>(&mut state);
+
+    let hash_final_tries = KERNEL.global_labels["hash_final_tries"];
+    while !(state.registers.is_kernel && state.registers.program_counter == hash_final_tries) {
+        transition(&mut state)?;
+    }
+
+    // At `hash_final_tries`, the cumulative gas used is at the top of the stack.
+    let gas_used = stack_peek(&state, 0).map_err(|err| anyhow!("{:?}", err))?;
+    let bloom_bytes: Vec<u8> = (0..256)
+        .map(|i| {
+            let address = MemoryAddress::new(0, Segment::BlockBloom, i);
+            state.memory.get(address).low_u32() as u8
+        })
+        .collect();
+    let block_bloom =
+        core::array::from_fn(|i| U256::from_big_endian(&bloom_bytes[i * 32..(i + 1) * 32]));
+    let tries = read_trie_inputs(&state.memory)
+        .map_err(|err| anyhow!("Failed to read the tries: {:?}", err))?;
+
+    let boundary = TxnBoundary {
+        tries,
+        gas_used,
+        block_bloom,
+    };
+    Ok((boundary, state.inputs.contract_code))
+}
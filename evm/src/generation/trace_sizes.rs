@@ -0,0 +1,174 @@
+//! Dry runs of trace generation, to find out how large each table's trace will be before proving.
+//!
+//! This lets callers check up front whether a batch of transactions fits in the circuits
+//! preprocessed by `AllRecursiveCircuits`, or whether it must be split into several
+//! transaction-range proofs.
+
+use core::ops::Range;
+
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::util::log2_strict;
+use plonky2::util::timing::TimingTree;
+
+use crate::all_stark::{Table, NUM_TABLES};
+use crate::arithmetic::arithmetic_stark::RANGE_MAX;
+use crate::byte_packing::byte_packing_stark::BYTE_RANGE_MAX;
+use crate::config::StarkConfig;
+use crate::generation::{run_kernel, GenerationInputs};
+use crate::memory::memory_stark::MemoryStark;
+use crate::witness::memory::MemoryOp;
+use crate::witness::traces::TraceCheckpoint;
+
+/// The sizes of each table's trace for some `GenerationInputs`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TraceSizes {
+    /// The number of rows of each table before padding, indexed by `Table`. For the memory table,
+    /// this includes the rows added to fill gaps between accessed addresses.
+    pub rows: [usize; NUM_TABLES],
+    /// The log2 of the padded length of each table's trace, indexed by `Table`. This is the size
+    /// of the STARK proof which will be generated for this table.
+    pub degree_bits: [usize; NUM_TABLES],
+}
+
+/// How a table's trace compares to a range of degrees supported by the recursive circuits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TableFit {
+    /// The trace fits the circuit preprocessed at the given index within the range.
+    InRange { circuit_index: usize },
+    /// The trace is smaller than the smallest supported degree.
+    BelowRange,
+    /// The trace is larger than the largest supported degree, so the transactions must be split
+    /// across several proofs.
+    AboveRange,
+}
+
+impl TraceSizes {
+    /// Compares the size of each table to the given `degree_bits_ranges`, as passed to
+    /// `AllRecursiveCircuits::new`.
+    pub fn table_fits(
+        &self,
+        degree_bits_ranges: &[Range<usize>; NUM_TABLES],
+    ) -> [TableFit; NUM_TABLES] {
+        core::array::from_fn(|i| {
+            let degree_bits = self.degree_bits[i];
+            let range = &degree_bits_ranges[i];
+            if degree_bits < range.start {
+                TableFit::BelowRange
+            } else if degree_bits >= range.end {
+                TableFit::AboveRange
+            } else {
+                TableFit::InRange {
+                    circuit_index: degree_bits - range.start,
+                }
+            }
+        })
+    }
+
+    /// Returns the tables which don't fit the given `degree_bits_ranges`, along with how they
+    /// compare to them.
+    pub fn unfit_tables(
+        &self,
+        degree_bits_ranges: &[Range<usize>; NUM_TABLES],
+    ) -> Vec<(Table, TableFit)> {
+        Table::all()
+            .into_iter()
+            .zip(self.table_fits(degree_bits_ranges))
+            .filter(|(_, fit)| !matches!(fit, TableFit::InRange { .. }))
+            .collect()
+    }
+
+    /// Whether some table is too large for the given `degree_bits_ranges`, in which case the
+    /// transactions must be split across several transaction-range proofs.
+    pub fn requires_split(&self, degree_bits_ranges: &[Range<usize>; NUM_TABLES]) -> bool {
+        self.table_fits(degree_bits_ranges)
+            .contains(&TableFit::AboveRange)
+    }
+}
+
+/// Runs the kernel on the given inputs, without generating the tables' traces or proving, and
+/// returns the size of each table.
+///
+/// The padded size of each table is derived from its number of rows, in the same way as trace
+/// generation pads it.
+pub fn estimate_trace_sizes<F: RichField + Extendable<D>, const D: usize>(
+    inputs: GenerationInputs,
+    config: &StarkConfig,
+    timing: &mut TimingTree,
+) -> anyhow::Result<TraceSizes> {
+    let mut state = run_kernel::<F, D>(&inputs, timing, None, None)?;
+    let mut rows = state.traces.rows_since(TraceCheckpoint::default());
+
+    // As in `MemoryStark::generate_trace`, dummy reads are added between distant operations.
+    let memory_ops = &mut state.traces.memory_ops;
+    memory_ops.sort_by_key(MemoryOp::sorting_key);
+    MemoryStark::<F, D>::fill_gaps(memory_ops);
+    rows[Table::Memory as usize] = memory_ops.len();
+
+    let degree_bits = padded_degree_bits(&rows, config.fri_config.num_cap_elements());
+
+    Ok(TraceSizes { rows, degree_bits })
+}
+
+/// Returns the log2 of the padded length of each table's trace, given its number of rows and the
+/// number of Merkle cap elements, which is a lower bound for most tables' lengths.
+fn padded_degree_bits(rows: &[usize; NUM_TABLES], cap_elements: usize) -> [usize; NUM_TABLES] {
+    core::array::from_fn(|i| {
+        let min_rows = match Table::all()[i] {
+            Table::Arithmetic => RANGE_MAX,
+            Table::BytePacking => BYTE_RANGE_MAX.max(cap_elements),
+            Table::Cpu | Table::Memory => 1,
+            Table::Keccak | Table::KeccakSponge | Table::Logic => cap_elements,
+        };
+        log2_strict(rows[i].max(min_rows).next_power_of_two())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::all_stark::{Table, NUM_TABLES};
+    use crate::arithmetic::arithmetic_stark::RANGE_MAX;
+    use crate::generation::trace_sizes::{padded_degree_bits, TableFit, TraceSizes};
+
+    #[test]
+    fn test_table_fits() {
+        let sizes = TraceSizes {
+            rows: [0; NUM_TABLES],
+            degree_bits: [16, 10, 15, 14, 9, 12, 18],
+        };
+        let mut ranges = [16..20, 10..11, 15..16, 14..15, 9..10, 12..13, 18..19];
+        assert!(sizes.unfit_tables(&ranges).is_empty());
+        assert_eq!(
+            sizes.table_fits(&ranges)[Table::Arithmetic as usize],
+            TableFit::InRange { circuit_index: 0 }
+        );
+        assert!(!sizes.requires_split(&ranges));
+
+        ranges[Table::Cpu as usize] = 10..15;
+        ranges[Table::Memory as usize] = 19..22;
+        assert_eq!(
+            sizes.unfit_tables(&ranges),
+            vec![
+                (Table::Cpu, TableFit::AboveRange),
+                (Table::Memory, TableFit::BelowRange)
+            ]
+        );
+        assert!(sizes.requires_split(&ranges));
+    }
+    #[test]
+    fn test_padded_degree_bits() {
+        let mut rows = [0; NUM_TABLES];
+        rows[Table::Arithmetic as usize] = 3;
+        rows[Table::Cpu as usize] = 1 << 10;
+        rows[Table::Memory as usize] = (1 << 12) + 1;
+        let degree_bits = padded_degree_bits(&rows, 16);
+        assert_eq!(
+            degree_bits[Table::Arithmetic as usize],
+            RANGE_MAX.trailing_zeros() as usize
+        );
+        assert_eq!(degree_bits[Table::BytePacking as usize], 8);
+        assert_eq!(degree_bits[Table::Cpu as usize], 10);
+        assert_eq!(degree_bits[Table::Keccak as usize], 4);
+        assert_eq!(degree_bits[Table::Memory as usize], 13);
+    }
+}
@@ -0,0 +1,252 @@
+//! Tracing of user code execution, in the JSON lines format of EIP-3155.
+//!
+//! One step is recorded for each instruction executed in user mode; kernel instructions, including
+//! those of the syscalls invoked by user code, are not traced. The output can be diffed against
+//! the traces of other clients, e.g. `evm --json run`.
+//!
+//! Since the kernel charges gas lazily, the `gasCost` of a step is computed as the decrease of the
+//! context's remaining gas until its next traced step, or until the context terminates. For calls
+//! and contract creations, this means the cost is net of the gas returned by the callee, unlike in
+//! geth which reports the gas made available to the callee.
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use ethereum_types::U256;
+use plonky2::field::types::Field;
+use serde::{Serialize, Serializer};
+
+use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
+use crate::cpu::kernel::interpreter::get_mnemonic;
+use crate::generation::state::GenerationState;
+use crate::memory::segments::Segment;
+use crate::witness::errors::ProgramError;
+use crate::witness::memory::MemoryAddress;
+use crate::witness::transition::decode;
+use crate::witness::util::stack_peek;
+
+/// The exception handlers of the kernel, with the error message reported for each of them.
+const EXCEPTIONS: [(&str, &str); 6] = [
+    ("exc_out_of_gas", "out of gas"),
+    ("exc_invalid_opcode", "invalid opcode"),
+    ("exc_stack_underflow", "stack underflow"),
+    ("exc_invalid_jump_destination", "invalid jump destination"),
+    ("exc_invalid_jumpi_destination", "invalid jump destination"),
+    ("exc_stack_overflow", "stack limit reached"),
+];
+
+/// The state of the EVM before executing a user instruction.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceStep {
+    /// The index, within `GenerationInputs::signed_txns`, of the transaction being executed.
+    #[serde(skip)]
+    pub txn_index: usize,
+    pub pc: usize,
+    pub op: u8,
+    /// The gas remaining in the current context.
+    #[serde(serialize_with = "serialize_hex")]
+    pub gas: u64,
+    /// The gas consumed by this instruction.
+    #[serde(serialize_with = "serialize_hex")]
+    pub gas_cost: u64,
+    /// The size of the memory, in bytes.
+    pub mem_size: usize,
+    /// The stack, bottom first.
+    pub stack: Vec<U256>,
+    pub depth: usize,
+    pub refund: u64,
+    pub op_name: String,
+    /// The exception raised by this instruction, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Records a `TraceStep` for each user instruction executed by the CPU.
+#[derive(Debug)]
+pub struct ExecutionTracer {
+    steps: Vec<TraceStep>,
+    /// For each context, the index of its last step, whose gas cost isn't known yet.
+    pending: HashMap<usize, usize>,
+    terminate_common_pc: usize,
+    exception_pcs: HashMap<usize, &'static str>,
+}
+
+impl Default for ExecutionTracer {
+    fn default() -> Self {
+        Self {
+            steps: vec![],
+            pending: HashMap::new(),
+            terminate_common_pc: KERNEL.global_labels["terminate_common"],
+            exception_pcs: EXCEPTIONS
+                .iter()
+                .map(|&(label, error)| (KERNEL.global_labels[label], error))
+                .collect(),
+        }
+    }
+}
+
+impl ExecutionTracer {
+    pub fn steps(&self) -> &[TraceStep] {
+        &self.steps
+    }
+
+    /// Writes all steps, one JSON object per line.
+    pub fn write_json_lines<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write_steps(self.steps.iter(), out)
+    }
+
+    /// Writes the steps of the given transaction, one JSON object per line.
+    pub fn write_txn_json_lines<W: Write>(&self, txn_index: usize, out: &mut W) -> io::Result<()> {
+        write_steps(
+            self.steps.iter().filter(|step| step.txn_index == txn_index),
+            out,
+        )
+    }
+
+    /// Observes the state before an instruction is executed. A step is recorded for user
+    /// instructions, and the gas cost of the pending step of a context is completed when the
+    /// kernel terminates that context.
+    pub(crate) fn record_instruction<F: Field>(
+        &mut self,
+        state: &GenerationState<F>,
+    ) -> Result<(), ProgramError> {
+        let registers = &state.registers;
+        if registers.is_kernel {
+            if registers.program_counter == self.terminate_common_pc {
+                // stack: success, leftover_gas
+                let leftover_gas = stack_peek(state, 1)?.low_u64();
+                if let Some(i) = self.pending.remove(&registers.context) {
+                    let step = &mut self.steps[i];
+                    step.gas_cost = step.gas.saturating_sub(leftover_gas);
+                }
+            }
+            return Ok(());
+        }
+
+        let context = registers.context;
+        let read_ctx_metadata = |field: ContextMetadata| {
+            state.memory.get(MemoryAddress::new(
+                context,
+                Segment::ContextMetadata,
+                field as usize,
+            ))
+        };
+        let read_metadata = |field| state.memory.read_global_metadata(field);
+
+        let gas_limit = read_ctx_metadata(ContextMetadata::GasLimit).low_u64();
+        let gas = gas_limit.saturating_sub(registers.gas_used);
+        if let Some(i) = self.pending.insert(context, self.steps.len()) {
+            let step = &mut self.steps[i];
+            step.gas_cost = step.gas.saturating_sub(gas);
+        }
+
+        let pc = registers.program_counter;
+        let op = state
+            .memory
+            .get(MemoryAddress::new(
+                registers.code_context(),
+                Segment::Code,
+                pc,
+            ))
+            .low_u32() as u8;
+        let op_name = match decode(*registers, op) {
+            Ok(_) => get_mnemonic(op).to_string(),
+            Err(_) => format!("opcode {op:#x} not defined"),
+        };
+        let stack = (0..registers.stack_len)
+            .rev()
+            .map(|i| stack_peek(state, i))
+            .collect::<Result<_, _>>()?;
+
+        self.steps.push(TraceStep {
+            txn_index: state.next_txn_index.saturating_sub(1),
+            pc,
+            op,
+            gas,
+            gas_cost: 0,
+            mem_size: read_ctx_metadata(ContextMetadata::MemWords).as_usize() * 32,
+            stack,
+            // EIP-3155 depths start at 1 for the transaction's own context.
+            depth: read_metadata(GlobalMetadata::CallStackDepth).as_usize() + 1,
+            refund: read_metadata(GlobalMetadata::RefundCounter).low_u64(),
+            op_name,
+            error: None,
+        });
+        Ok(())
+    }
+
+    /// Observes the state after a user instruction was executed, and attaches an error to its step
+    /// if it raised an exception.
+    pub(crate) fn record_exception<F: Field>(&mut self, state: &GenerationState<F>) {
+        if !state.registers.is_kernel {
+            return;
+        }
+        let Some(&error) = self.exception_pcs.get(&state.registers.program_counter) else {
+            return;
+        };
+        if let Some(step) = self.steps.last_mut() {
+            step.error = Some(error.to_string());
+        }
+    }
+}
+
+fn write_steps<'a, W: Write>(
+    steps: impl Iterator<Item = &'a TraceStep>,
+    out: &mut W,
+) -> io::Result<()> {
+    for step in steps {
+        serde_json::to_writer(&mut *out, step)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn serialize_hex<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{value:#x}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generation::tracer::{ExecutionTracer, TraceStep};
+
+    #[test]
+    fn test_json_lines() {
+        let step = |txn_index, pc, op: u8, op_name: &str| TraceStep {
+            txn_index,
+            pc,
+            op,
+            gas: 0x5f5e100,
+            gas_cost: 3,
+            mem_size: 0,
+            stack: vec![1.into(), 0x20.into()],
+            depth: 1,
+            refund: 0,
+            op_name: op_name.to_string(),
+            error: None,
+        };
+        let mut tracer = ExecutionTracer::default();
+        tracer.steps.push(step(0, 0, 0x60, "PUSH1"));
+        tracer.steps.push(TraceStep {
+            error: Some("out of gas".to_string()),
+            ..step(1, 2, 0x01, "ADD")
+        });
+
+        let mut out = vec![];
+        tracer.write_txn_json_lines(1, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            concat!(
+                r#"{"pc":2,"op":1,"gas":"0x5f5e100","gasCost":"0x3","memSize":0,"#,
+                r#""stack":["0x1","0x20"],"depth":1,"refund":0,"opName":"ADD","error":"out of gas"}"#,
+                "\n"
+            )
+        );
+
+        let mut out = vec![];
+        tracer.write_json_lines(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 2);
+    }
+}
@@ -1,15 +1,22 @@
-//! Code for extracting trie data after witness generation. This is intended only for debugging.
+//! Code for extracting trie data after witness generation. `read_trie` is intended only for
+//! debugging, while `read_trie_inputs` reads back partial tries which can be fed to a subsequent
+//! execution.
 
 use std::collections::HashMap;
 
 use eth_trie_utils::nibbles::Nibbles;
-use ethereum_types::{BigEndianHash, H256, U256, U512};
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{Address, BigEndianHash, H256, U256, U512};
 
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::kernel::constants::trie_type::PartialTrieType;
+use crate::generation::mpt::{AccountRlp, LegacyReceiptRlp, LogRlp};
+use crate::generation::TrieInputs;
 use crate::memory::segments::Segment;
 use crate::util::u256_to_usize;
 use crate::witness::errors::ProgramError;
 use crate::witness::memory::{MemoryAddress, MemoryState};
+use crate::Node;
 
 /// Account data as it's stored in the state trie, with a pointer to the storage trie.
 #[derive(Debug)]
@@ -109,3 +116,205 @@ pub(crate) fn read_trie_helper<V>(
         }
     }
 }
+
+/// Reads the state, transaction and receipt tries, along with the storage tries of all accounts in
+/// the state trie, in the format of `TrieInputs`. Hash nodes are preserved.
+pub(crate) fn read_trie_inputs(memory: &MemoryState) -> Result<TrieInputs, ProgramError> {
+    let root_ptr = |field| u256_to_usize(memory.read_global_metadata(field));
+    let load = |offset| memory.get(MemoryAddress::new(0, Segment::TrieData, offset));
+
+    let mut storage_tries = vec![];
+    let state_trie = read_partial_trie(
+        memory,
+        root_ptr(GlobalMetadata::StateTrieRoot)?,
+        &mut |key, value_ptr| {
+            let account = read_state_trie_value(&[
+                load(value_ptr),
+                load(value_ptr + 1),
+                load(value_ptr + 2),
+                load(value_ptr + 3),
+            ])?;
+            let storage_trie =
+                read_partial_trie(memory, account.storage_ptr, &mut |_, storage_ptr| {
+                    Ok(rlp::encode(&load(storage_ptr)).to_vec())
+                })?;
+            let account_rlp = AccountRlp {
+                nonce: account.nonce.into(),
+                balance: account.balance,
+                storage_root: storage_trie.hash(),
+                code_hash: account.code_hash,
+            };
+            let state_key = key
+                .try_into_u256()
+                .map_err(|_| ProgramError::IntegerTooLarge)?;
+            storage_tries.push((H256::from_uint(&state_key), storage_trie));
+            Ok(rlp::encode(&account_rlp).to_vec())
+        },
+    )?;
+
+    let transactions_trie = read_partial_trie(
+        memory,
+        root_ptr(GlobalMetadata::TransactionTrieRoot)?,
+        &mut |_, value_ptr| {
+            let len = u256_to_usize(load(value_ptr))?;
+            Ok((1..=len)
+                .map(|i| load(value_ptr + i).low_u32() as u8)
+                .collect())
+        },
+    )?;
+
+    let receipts_trie = read_partial_trie(
+        memory,
+        root_ptr(GlobalMetadata::ReceiptTrieRoot)?,
+        &mut |_, value_ptr| read_receipt(memory, value_ptr),
+    )?;
+
+    Ok(TrieInputs {
+        state_trie,
+        transactions_trie,
+        receipts_trie,
+        storage_tries,
+    })
+}
+
+/// Reads a trie as a `HashedPartialTrie`. Unlike `read_trie`, hash nodes are kept. `read_value`
+/// is given the key and the pointer of each value, and returns the value as it should be stored in
+/// the trie.
+pub(crate) fn read_partial_trie<R>(
+    memory: &MemoryState,
+    ptr: usize,
+    read_value: &mut R,
+) -> Result<HashedPartialTrie, ProgramError>
+where
+    R: FnMut(Nibbles, usize) -> Result<Vec<u8>, ProgramError>,
+{
+    let empty_nibbles = Nibbles {
+        count: 0,
+        packed: U512::zero(),
+    };
+    let node = read_partial_trie_helper(memory, ptr, read_value, empty_nibbles)?;
+    Ok(HashedPartialTrie::new(node))
+}
+
+fn read_partial_trie_helper<R>(
+    memory: &MemoryState,
+    ptr: usize,
+    read_value: &mut R,
+    prefix: Nibbles,
+) -> Result<Node, ProgramError>
+where
+    R: FnMut(Nibbles, usize) -> Result<Vec<u8>, ProgramError>,
+{
+    let load = |offset| memory.get(MemoryAddress::new(0, Segment::TrieData, offset));
+    let load_nibbles = |offset| -> Result<Nibbles, ProgramError> {
+        Ok(Nibbles {
+            count: u256_to_usize(load(offset))?,
+            packed: load(offset + 1).into(),
+        })
+    };
+
+    let trie_type = PartialTrieType::all()[u256_to_usize(load(ptr))?];
+    match trie_type {
+        PartialTrieType::Empty => Ok(Node::Empty),
+        PartialTrieType::Hash => Ok(Node::Hash(H256::from_uint(&load(ptr + 1)))),
+        PartialTrieType::Branch => {
+            let ptr_payload = ptr + 1;
+            let mut children = core::array::from_fn(|_| Node::Empty.into());
+            for (i, child) in children.iter_mut().enumerate() {
+                let child_ptr = u256_to_usize(load(ptr_payload + i))?;
+                *child = read_partial_trie_helper(
+                    memory,
+                    child_ptr,
+                    read_value,
+                    prefix.merge_nibble(i as u8),
+                )?
+                .into();
+            }
+            let value_ptr = u256_to_usize(load(ptr_payload + 16))?;
+            let value = if value_ptr != 0 {
+                read_value(prefix, value_ptr)?
+            } else {
+                vec![]
+            };
+
+            Ok(Node::Branch { children, value })
+        }
+        PartialTrieType::Extension => {
+            let nibbles = load_nibbles(ptr + 1)?;
+            let child_ptr = u256_to_usize(load(ptr + 3))?;
+            let child = read_partial_trie_helper(
+                memory,
+                child_ptr,
+                read_value,
+                prefix.merge_nibbles(&nibbles),
+            )?;
+
+            Ok(Node::Extension {
+                nibbles,
+                child: child.into(),
+            })
+        }
+        PartialTrieType::Leaf => {
+            let nibbles = load_nibbles(ptr + 1)?;
+            let value_ptr = u256_to_usize(load(ptr + 3))?;
+            let value = read_value(prefix.merge_nibbles(&nibbles), value_ptr)?;
+
+            Ok(Node::Leaf { nibbles, value })
+        }
+    }
+}
+
+/// Reads a receipt, stored as in `create_receipt.asm`, and returns its encoding, prefixed with the
+/// transaction type for typed transactions.
+fn read_receipt(memory: &MemoryState, value_ptr: usize) -> Result<Vec<u8>, ProgramError> {
+    let load = |offset| memory.get(MemoryAddress::new(0, Segment::TrieData, offset));
+    let load_bytes = |offset, len| -> Vec<u8> {
+        (offset..offset + len)
+            .map(|i| load(i).low_u32() as u8)
+            .collect()
+    };
+
+    // The first value is either the transaction type or the payload length, which is at least 256
+    // because of the bloom filter.
+    let first_value = load(value_ptr);
+    let (txn_type, payload_ptr) = if first_value < U256::from(3) {
+        (Some(first_value.low_u32() as u8), value_ptr + 1)
+    } else {
+        (None, value_ptr)
+    };
+
+    let status = !load(payload_ptr + 1).is_zero();
+    let cum_gas_used = load(payload_ptr + 2);
+    let bloom = load_bytes(payload_ptr + 3, 256);
+    let num_logs = u256_to_usize(load(payload_ptr + 260))?;
+
+    let mut logs = Vec::with_capacity(num_logs);
+    let mut log_ptr = payload_ptr + 261;
+    for _ in 0..num_logs {
+        // Skip the log's payload length.
+        let address = Address::from(H256::from_uint(&load(log_ptr + 1)));
+        let num_topics = u256_to_usize(load(log_ptr + 2))?;
+        let topics = (0..num_topics)
+            .map(|i| H256::from_uint(&load(log_ptr + 3 + i)))
+            .collect();
+        let data_len_ptr = log_ptr + 3 + num_topics;
+        let data_len = u256_to_usize(load(data_len_ptr))?;
+        let data = load_bytes(data_len_ptr + 1, data_len);
+        logs.push(LogRlp {
+            address,
+            topics,
+            data: data.into(),
+        });
+        log_ptr = data_len_ptr + 1 + data_len;
+    }
+
+    let receipt = LegacyReceiptRlp {
+        status,
+        cum_gas_used,
+        bloom: bloom.into(),
+        logs,
+    };
+    let mut encoded = txn_type.into_iter().collect::<Vec<_>>();
+    encoded.extend(rlp::encode(&receipt));
+    Ok(encoded)
+}
@@ -74,6 +74,7 @@ fn observe_block_metadata<
     for i in 0..8 {
         challenger.observe_elements(&u256_limbs(block_metadata.block_bloom[i]));
     }
+    challenger.observe_element(F::from_canonical_u8(block_metadata.hardfork as u8));
 
     Ok(())
 }
@@ -98,6 +99,7 @@ fn observe_block_metadata_target<
     challenger.observe_elements(&block_metadata.block_base_fee);
     challenger.observe_elements(&block_metadata.block_gas_used);
     challenger.observe_elements(&block_metadata.block_bloom);
+    challenger.observe_element(block_metadata.hardfork);
 }
 
 fn observe_extra_block_data<
@@ -223,12 +225,13 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> A
         let ctl_challenges =
             get_grand_product_challenge_set(&mut challenger, config.num_challenges);
 
+        // The tables are proven concurrently, each from a copy of the challenger in this state.
+        challenger.compact();
         Ok(AllProofChallenges {
             stark_challenges: core::array::from_fn(|i| {
-                challenger.compact();
                 self.stark_proofs[i]
                     .proof
-                    .get_challenges(&mut challenger, config)
+                    .get_challenges(&mut challenger.clone(), config)
             }),
             ctl_challenges,
         })
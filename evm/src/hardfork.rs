@@ -0,0 +1,52 @@
+use ethereum_types::U256;
+use serde::{Deserialize, Serialize};
+
+/// The Ethereum hardforks whose rules the kernel can apply. The selected hardfork is part of the
+/// `BlockMetadata`, and hence of the public values, so that a verifier knows which rules were
+/// used to execute the block.
+///
+/// Forks are ordered chronologically, and the kernel enables each rule change for its fork and
+/// all later ones. Rules which aren't listed below are shared by all supported forks. In
+/// particular, all forks have the precompiles `0x01` to `0x09`.
+#[derive(
+    Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd, Debug, Default, Deserialize, Serialize,
+)]
+pub enum Hardfork {
+    /// EIP-2929 gas costs and EIP-2930 access list transactions. Blocks must have a base fee of 0.
+    Berlin = 0,
+    /// EIP-1559 fee market transactions, the `BASEFEE` opcode (EIP-3198), reduced refunds
+    /// (EIP-3529) and rejection of new code starting with `0xEF` (EIP-3541).
+    London = 1,
+    /// Warm coinbase (EIP-3651), the `PUSH0` opcode (EIP-3855), and limit and metering of
+    /// initcode (EIP-3860).
+    #[default]
+    Shanghai = 2,
+    /// `SELFDESTRUCT` only deletes accounts created in the same transaction (EIP-6780). The other
+    /// changes of Cancun, such as transient storage or blob transactions, aren't supported yet,
+    /// so trace generation rejects it. Only the kernel tests run its rules.
+    Cancun = 3,
+}
+
+impl Hardfork {
+    pub(crate) const COUNT: usize = 4;
+
+    pub(crate) fn all() -> [Self; Self::COUNT] {
+        [Self::Berlin, Self::London, Self::Shanghai, Self::Cancun]
+    }
+
+    /// The variable name that gets passed into kernel assembly code.
+    pub(crate) fn var_name(&self) -> &'static str {
+        match self {
+            Self::Berlin => "HARDFORK_BERLIN",
+            Self::London => "HARDFORK_LONDON",
+            Self::Shanghai => "HARDFORK_SHANGHAI",
+            Self::Cancun => "HARDFORK_CANCUN",
+        }
+    }
+}
+
+impl From<Hardfork> for U256 {
+    fn from(hardfork: Hardfork) -> Self {
+        (hardfork as u32).into()
+    }
+}
@@ -18,16 +18,19 @@ pub mod extension_tower;
 pub mod fixed_recursive_verifier;
 pub mod generation;
 mod get_challenges;
+pub mod hardfork;
 pub mod keccak;
 pub mod keccak_sponge;
 pub mod logic;
 pub mod lookup;
 pub mod memory;
 pub mod proof;
+pub mod proof_file;
 pub mod prover;
 pub mod recursive_verifier;
 pub mod stark;
 pub mod stark_testing;
+pub mod state_tests;
 pub mod util;
 pub mod vanishing_poly;
 pub mod verifier;
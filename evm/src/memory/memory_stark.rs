@@ -160,7 +160,7 @@ impl<F: RichField + Extendable<D>, const D: usize> MemoryStark<F, D> {
     /// For example, say there are 32 memory operations, and a particular address is accessed at
     /// timestamps 20 and 100. 80 would fail the range check, so this method would add two dummy
     /// reads to the same address, say at timestamps 50 and 80.
-    fn fill_gaps(memory_ops: &mut Vec<MemoryOp>) {
+    pub(crate) fn fill_gaps(memory_ops: &mut Vec<MemoryOp>) {
         let max_rc = memory_ops.len().next_power_of_two() - 1;
         for (mut curr, next) in memory_ops.clone().into_iter().tuple_windows() {
             if curr.address.context != next.address.context
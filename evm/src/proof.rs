@@ -7,6 +7,7 @@ use plonky2::fri::structure::{
     FriOpeningBatch, FriOpeningBatchTarget, FriOpenings, FriOpeningsTarget,
 };
 use plonky2::hash::hash_types::{MerkleCapTarget, RichField};
+use plonky2::hash::hashing::PlonkyPermutation;
 use plonky2::hash::merkle_tree::MerkleCap;
 use plonky2::iop::ext_target::ExtensionTarget;
 use plonky2::iop::target::{BoolTarget, Target};
@@ -14,14 +15,18 @@ use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::config::{GenericConfig, Hasher};
 use plonky2::util::serialization::{Buffer, IoResult, Read, Write};
 use plonky2_maybe_rayon::*;
-use serde::{Deserialize, Serialize};
+use serde::de::Error as _;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::all_stark::NUM_TABLES;
 use crate::config::StarkConfig;
 use crate::cross_table_lookup::GrandProductChallengeSet;
+use crate::hardfork::Hardfork;
 
 /// A STARK proof for each table, plus some metadata used to create recursive wrapper proofs.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct AllProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
     pub stark_proofs: [StarkProofWithMetadata<F, C, D>; NUM_TABLES],
     pub(crate) ctl_challenges: GrandProductChallengeSet<F>,
@@ -49,7 +54,7 @@ pub struct PublicValues {
     pub extra_block_data: ExtraBlockData,
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TrieRoots {
     pub state_root: H256,
     pub transactions_root: H256,
@@ -110,6 +115,9 @@ pub struct BlockMetadata {
     /// The block bloom of this block, represented as the consecutive
     /// 32-byte chunks of a block's final bloom filter string.
     pub block_bloom: [U256; 8],
+    /// The hardfork whose rules apply to this block.
+    #[serde(default)]
+    pub hardfork: Hardfork,
 }
 
 /// Additional block data that are specific to the local transaction being proven,
@@ -181,6 +189,7 @@ impl PublicValuesTarget {
             block_base_fee,
             block_gas_used,
             block_bloom,
+            hardfork,
         } = self.block_metadata;
 
         buffer.write_target_array(&block_beneficiary)?;
@@ -193,6 +202,7 @@ impl PublicValuesTarget {
         buffer.write_target_array(&block_base_fee)?;
         buffer.write_target_array(&block_gas_used)?;
         buffer.write_target_array(&block_bloom)?;
+        buffer.write_target(hardfork)?;
 
         let BlockHashesTarget {
             prev_hashes,
@@ -245,6 +255,7 @@ impl PublicValuesTarget {
             block_base_fee: buffer.read_target_array()?,
             block_gas_used: buffer.read_target_array()?,
             block_bloom: buffer.read_target_array()?,
+            hardfork: buffer.read_target()?,
         };
 
         let block_hashes = BlockHashesTarget {
@@ -419,10 +430,11 @@ pub struct BlockMetadataTarget {
     pub block_base_fee: [Target; 2],
     pub block_gas_used: [Target; 2],
     pub block_bloom: [Target; 64],
+    pub hardfork: Target,
 }
 
 impl BlockMetadataTarget {
-    pub const SIZE: usize = 87;
+    pub const SIZE: usize = 88;
 
     pub fn from_public_inputs(pis: &[Target]) -> Self {
         let block_beneficiary = pis[0..5].try_into().unwrap();
@@ -435,6 +447,7 @@ impl BlockMetadataTarget {
         let block_base_fee = pis[19..21].try_into().unwrap();
         let block_gas_used = pis[21..23].try_into().unwrap();
         let block_bloom = pis[23..87].try_into().unwrap();
+        let hardfork = pis[87];
 
         Self {
             block_beneficiary,
@@ -447,6 +460,7 @@ impl BlockMetadataTarget {
             block_base_fee,
             block_gas_used,
             block_bloom,
+            hardfork,
         }
     }
 
@@ -483,6 +497,7 @@ impl BlockMetadataTarget {
             block_bloom: core::array::from_fn(|i| {
                 builder.select(condition, bm0.block_bloom[i], bm1.block_bloom[i])
             }),
+            hardfork: builder.select(condition, bm0.hardfork, bm1.hardfork),
         }
     }
 
@@ -513,6 +528,7 @@ impl BlockMetadataTarget {
         for i in 0..64 {
             builder.connect(bm0.block_bloom[i], bm1.block_bloom[i])
         }
+        builder.connect(bm0.hardfork, bm1.hardfork);
     }
 }
 
@@ -666,7 +682,8 @@ impl ExtraBlockDataTarget {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct StarkProof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
     /// Merkle cap of LDEs of trace values.
     pub trace_cap: MerkleCap<F, C::Hasher>,
@@ -692,6 +709,51 @@ where
     pub(crate) proof: StarkProof<F, C, D>,
 }
 
+// The permutation state isn't serializable, so it's serialized as the list of its elements.
+impl<F, C, const D: usize> Serialize for StarkProofWithMetadata<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("StarkProofWithMetadata", 2)?;
+        state.serialize_field("init_challenger_state", self.init_challenger_state.as_ref())?;
+        state.serialize_field("proof", &self.proof)?;
+        state.end()
+    }
+}
+
+impl<'de, F, C, const D: usize> Deserialize<'de> for StarkProofWithMetadata<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+        #[derive(Deserialize)]
+        #[serde(bound = "")]
+        struct Fields<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+            init_challenger_state: Vec<F>,
+            proof: StarkProof<F, C, D>,
+        }
+
+        let Fields {
+            init_challenger_state,
+            proof,
+        } = Fields::<F, C, D>::deserialize(deserializer)?;
+        let width = <<C::Hasher as Hasher<F>>::Permutation as PlonkyPermutation<F>>::WIDTH;
+        if init_challenger_state.len() != width {
+            return Err(De::Error::invalid_length(
+                init_challenger_state.len(),
+                &"the width of the challenger's permutation",
+            ));
+        }
+        Ok(Self {
+            init_challenger_state: PlonkyPermutation::new(init_challenger_state),
+            proof,
+        })
+    }
+}
+
 impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> StarkProof<F, C, D> {
     /// Recover the length of the trace from a STARK proof and a STARK config.
     pub fn recover_degree_bits(&self, config: &StarkConfig) -> usize {
@@ -771,7 +833,8 @@ pub(crate) struct StarkProofChallengesTarget<const D: usize> {
 }
 
 /// Purported values of each polynomial at the challenge point.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
 pub struct StarkOpeningSet<F: RichField + Extendable<D>, const D: usize> {
     /// Openings of trace polynomials at `zeta`.
     pub local_values: Vec<F::Extension>,
@@ -0,0 +1,109 @@
+//! A standalone serialization format for `AllProof`, so that proofs can be stored, shared and
+//! verified natively with `verify_proof`, without the recursive circuits.
+//!
+//! A proof file holds a format version, the `StarkConfig` the proof was generated with, and the
+//! proof itself, including its public values. The verifier provides the config it expects, and
+//! only accepts proofs generated with that config. It can be encoded as JSON, or as compact bytes with
+//! bincode. In both encodings, the version comes first, so that files written with another version
+//! of the format are rejected with a clear error.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{ensure, Context, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::GenericConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::all_stark::AllStark;
+use crate::config::StarkConfig;
+use crate::proof::{AllProof, PublicValues};
+use crate::verifier::verify_proof;
+
+/// The version of the proof file format, to be bumped whenever the serialization of `AllProof` or
+/// `StarkConfig` changes.
+pub const PROOF_FILE_VERSION: u32 = 1;
+
+/// An `AllProof` along with everything needed to verify it, besides the `AllStark`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ProofFile<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> {
+    pub version: u32,
+    pub config: StarkConfig,
+    pub proof: AllProof<F, C, D>,
+}
+
+/// The first field of a proof file, which can be read regardless of the version.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize> ProofFile<F, C, D> {
+    pub fn new(config: StarkConfig, proof: AllProof<F, C, D>) -> Self {
+        Self {
+            version: PROOF_FILE_VERSION,
+            config,
+            proof,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let header: Header = serde_json::from_str(json).context("Invalid proof file header")?;
+        check_version(header.version)?;
+        serde_json::from_str(json).context("Invalid proof file")
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let version: u32 = bincode::deserialize(bytes).context("Invalid proof file header")?;
+        check_version(version)?;
+        bincode::deserialize(bytes).context("Invalid proof file")
+    }
+
+    /// Reads a proof file in either encoding, which is told apart by its first character.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).with_context(|| format!("Unable to read {:?}", path))?;
+        if bytes.trim_ascii_start().starts_with(b"{") {
+            Self::from_json(std::str::from_utf8(&bytes).context("Invalid UTF-8 in proof file")?)
+        } else {
+            Self::from_bytes(&bytes)
+        }
+    }
+
+    /// Verifies the proof with the expected `config`, and returns its public values. The config
+    /// stored in the file can't be trusted, as it could lower the security of the proof, so a file
+    /// whose config differs from `config` is rejected.
+    pub fn verify(self, all_stark: &AllStark<F, D>, config: &StarkConfig) -> Result<PublicValues> {
+        ensure!(
+            self.config.security_bits == config.security_bits
+                && self.config.num_challenges == config.num_challenges
+                && self.config.fri_config == config.fri_config,
+            "Proof file config {:?} differs from the expected config {:?}",
+            self.config,
+            config
+        );
+        let public_values = self.proof.public_values.clone();
+        verify_proof(all_stark, self.proof, config)?;
+        Ok(public_values)
+    }
+}
+
+fn check_version(version: u32) -> Result<()> {
+    ensure!(
+        version == PROOF_FILE_VERSION,
+        "Unsupported proof file version {}, expected version {}",
+        version,
+        PROOF_FILE_VERSION
+    );
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use std::any::type_name;
+use std::mem::size_of;
 
 use anyhow::{ensure, Result};
-use itertools::Itertools;
 use once_cell::sync::Lazy;
 use plonky2::field::extension::Extendable;
 use plonky2::field::packable::Packable;
@@ -11,6 +11,7 @@ use plonky2::field::types::Field;
 use plonky2::field::zero_poly_coset::ZeroPolyOnCoset;
 use plonky2::fri::oracle::PolynomialBatch;
 use plonky2::hash::hash_types::RichField;
+use plonky2::hash::merkle_tree::MerkleCap;
 use plonky2::iop::challenger::Challenger;
 use plonky2::plonk::config::GenericConfig;
 use plonky2::timed;
@@ -34,9 +35,13 @@ use crate::get_challenges::observe_public_values;
 use crate::lookup::{lookup_helper_columns, Lookup, LookupCheckVars};
 use crate::proof::{AllProof, PublicValues, StarkOpeningSet, StarkProof, StarkProofWithMetadata};
 use crate::stark::Stark;
+use crate::util::{add_table_timings, run_table_task};
 use crate::vanishing_poly::eval_vanishing_poly;
 
 /// Generate traces, then create all STARK proofs.
+///
+/// If witness generation fails, the returned error wraps an
+/// [`ExecutionError`](crate::witness::errors::ExecutionError) describing where it happened.
 pub fn prove<F, C, const D: usize>(
     all_stark: &AllStark<F, D>,
     config: &StarkConfig,
@@ -85,38 +90,41 @@ where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
 {
-    let rate_bits = config.fri_config.rate_bits;
-    let cap_height = config.fri_config.cap_height;
+    let table_groups = table_groups(config, &trace_poly_values);
 
-    let trace_commitments = timed!(
+    // In low-memory mode, only the Merkle caps of the trace commitments are kept, and each table's
+    // commitment is recomputed when proving it.
+    let (trace_commitments, trace_caps) = timed!(
         timing,
         "compute all trace commitments",
-        trace_poly_values
-            .iter()
-            .zip_eq(Table::all())
-            .map(|(trace, table)| {
-                timed!(
-                    timing,
-                    &format!("compute trace commitment for {:?}", table),
-                    PolynomialBatch::<F, C, D>::from_values(
-                        // TODO: Cloning this isn't great; consider having `from_values` accept a reference,
-                        // or having `compute_permutation_z_polys` read trace values from the `PolynomialBatch`.
-                        trace.clone(),
-                        rate_bits,
-                        false,
-                        cap_height,
-                        timing,
-                        None,
-                    )
-                )
-            })
-            .collect::<Vec<_>>()
+        if config.memory_budget.is_some() {
+            let mut trace_caps = Vec::with_capacity(NUM_TABLES);
+            for group in &table_groups {
+                let group_caps = group
+                    .par_iter()
+                    .map(|&table| {
+                        let (commitment, table_timing) =
+                            run_commit_task::<F, C, D>(config, &trace_poly_values, table);
+                        (commitment.merkle_tree.cap, table_timing)
+                    })
+                    .collect::<Vec<_>>();
+                trace_caps.extend(add_table_timings(group_caps, timing));
+            }
+            (None, trace_caps)
+        } else {
+            let trace_commitments = Table::all()
+                .par_iter()
+                .map(|&table| run_commit_task::<F, C, D>(config, &trace_poly_values, table))
+                .collect::<Vec<_>>();
+            let trace_commitments = add_table_timings(trace_commitments, timing);
+            let trace_caps = trace_commitments
+                .iter()
+                .map(|c| c.merkle_tree.cap.clone())
+                .collect::<Vec<_>>();
+            (Some(trace_commitments), trace_caps)
+        }
     );
 
-    let trace_caps = trace_commitments
-        .iter()
-        .map(|c| c.merkle_tree.cap.clone())
-        .collect::<Vec<_>>();
     let mut challenger = Challenger::<F, C::Hasher>::new();
     for cap in &trace_caps {
         challenger.observe_cap(cap);
@@ -142,12 +150,14 @@ where
         prove_with_commitments(
             all_stark,
             config,
+            &table_groups,
             trace_poly_values,
             trace_commitments,
+            &trace_caps,
             ctl_data_per_table,
-            &mut challenger,
+            &challenger,
             &ctl_challenges,
-            timing
+            timing,
         )?
     );
 
@@ -158,13 +168,20 @@ where
     })
 }
 
+/// Proves the tables of each group concurrently, one group after the other. Each table's proof
+/// starts from its own copy of the challenger, in the state reached after drawing the CTL
+/// challenges. If `trace_commitments` is `None`, each table's trace commitment is recomputed right
+/// before proving it, and dropped afterwards.
+#[allow(clippy::too_many_arguments)]
 fn prove_with_commitments<F, C, const D: usize>(
     all_stark: &AllStark<F, D>,
     config: &StarkConfig,
+    table_groups: &[Vec<Table>],
     trace_poly_values: [Vec<PolynomialValues<F>>; NUM_TABLES],
-    trace_commitments: Vec<PolynomialBatch<F, C, D>>,
+    trace_commitments: Option<Vec<PolynomialBatch<F, C, D>>>,
+    trace_caps: &[MerkleCap<F, C::Hasher>],
     ctl_data_per_table: [CtlData<F>; NUM_TABLES],
-    challenger: &mut Challenger<F, C::Hasher>,
+    challenger: &Challenger<F, C::Hasher>,
     ctl_challenges: &GrandProductChallengeSet<F>,
     timing: &mut TimingTree,
 ) -> Result<[StarkProofWithMetadata<F, C, D>; NUM_TABLES]>
@@ -172,114 +189,192 @@ where
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F = F>,
 {
-    let arithmetic_proof = timed!(
-        timing,
-        "prove Arithmetic STARK",
-        prove_single_table(
-            &all_stark.arithmetic_stark,
-            config,
-            &trace_poly_values[Table::Arithmetic as usize],
-            &trace_commitments[Table::Arithmetic as usize],
-            &ctl_data_per_table[Table::Arithmetic as usize],
-            ctl_challenges,
-            challenger,
-            timing,
-        )?
-    );
-    let byte_packing_proof = timed!(
-        timing,
-        "prove byte packing STARK",
-        prove_single_table(
-            &all_stark.byte_packing_stark,
-            config,
-            &trace_poly_values[Table::BytePacking as usize],
-            &trace_commitments[Table::BytePacking as usize],
-            &ctl_data_per_table[Table::BytePacking as usize],
-            ctl_challenges,
-            challenger,
-            timing,
-        )?
-    );
-    let cpu_proof = timed!(
-        timing,
-        "prove CPU STARK",
-        prove_single_table(
-            &all_stark.cpu_stark,
-            config,
-            &trace_poly_values[Table::Cpu as usize],
-            &trace_commitments[Table::Cpu as usize],
-            &ctl_data_per_table[Table::Cpu as usize],
-            ctl_challenges,
-            challenger,
-            timing,
-        )?
-    );
-    let keccak_proof = timed!(
-        timing,
-        "prove Keccak STARK",
-        prove_single_table(
-            &all_stark.keccak_stark,
-            config,
-            &trace_poly_values[Table::Keccak as usize],
-            &trace_commitments[Table::Keccak as usize],
-            &ctl_data_per_table[Table::Keccak as usize],
-            ctl_challenges,
-            challenger,
-            timing,
-        )?
-    );
-    let keccak_sponge_proof = timed!(
-        timing,
-        "prove Keccak sponge STARK",
-        prove_single_table(
-            &all_stark.keccak_sponge_stark,
-            config,
-            &trace_poly_values[Table::KeccakSponge as usize],
-            &trace_commitments[Table::KeccakSponge as usize],
-            &ctl_data_per_table[Table::KeccakSponge as usize],
-            ctl_challenges,
-            challenger,
-            timing,
-        )?
-    );
-    let logic_proof = timed!(
-        timing,
-        "prove logic STARK",
-        prove_single_table(
-            &all_stark.logic_stark,
-            config,
-            &trace_poly_values[Table::Logic as usize],
-            &trace_commitments[Table::Logic as usize],
-            &ctl_data_per_table[Table::Logic as usize],
-            ctl_challenges,
-            challenger,
-            timing,
-        )?
-    );
-    let memory_proof = timed!(
+    let mut proofs = Vec::with_capacity(NUM_TABLES);
+    for group in table_groups {
+        let group_proofs = group
+            .par_iter()
+            .map(|&table| {
+                run_table_task(
+                    config.threads_per_table,
+                    table,
+                    &format!("prove {:?} STARK", table),
+                    |timing| {
+                        let recomputed_commitment;
+                        let trace_commitment = match &trace_commitments {
+                            Some(trace_commitments) => &trace_commitments[table as usize],
+                            None => {
+                                recomputed_commitment = timed!(
+                                    timing,
+                                    "recompute trace commitment",
+                                    commit_to_trace::<F, C, D>(
+                                        config,
+                                        &trace_poly_values,
+                                        table,
+                                        timing
+                                    )
+                                );
+                                debug_assert_eq!(
+                                    recomputed_commitment.merkle_tree.cap,
+                                    trace_caps[table as usize]
+                                );
+                                &recomputed_commitment
+                            }
+                        };
+                        prove_table(
+                            all_stark,
+                            table,
+                            config,
+                            &trace_poly_values[table as usize],
+                            trace_commitment,
+                            &ctl_data_per_table[table as usize],
+                            ctl_challenges,
+                            &mut challenger.clone(),
+                            timing,
+                        )
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        for proof in add_table_timings(group_proofs, timing) {
+            proofs.push(proof?);
+        }
+    }
+
+    Ok(proofs
+        .try_into()
+        .expect("There should be exactly one proof per table"))
+}
+
+/// Computes the commitment to the trace of the given table in the table's own task, see
+/// [`run_table_task`].
+fn run_commit_task<F, C, const D: usize>(
+    config: &StarkConfig,
+    trace_poly_values: &[Vec<PolynomialValues<F>>; NUM_TABLES],
+    table: Table,
+) -> (PolynomialBatch<F, C, D>, TimingTree)
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    run_table_task(
+        config.threads_per_table,
+        table,
+        &format!("compute trace commitment for {:?}", table),
+        |timing| commit_to_trace::<F, C, D>(config, trace_poly_values, table, timing),
+    )
+}
+
+/// Computes the commitment to the trace of the given table.
+fn commit_to_trace<F, C, const D: usize>(
+    config: &StarkConfig,
+    trace_poly_values: &[Vec<PolynomialValues<F>>; NUM_TABLES],
+    table: Table,
+    timing: &mut TimingTree,
+) -> PolynomialBatch<F, C, D>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    PolynomialBatch::<F, C, D>::from_values(
+        // TODO: Cloning this isn't great; consider having `from_values` accept a reference,
+        // or having `compute_permutation_z_polys` read trace values from the `PolynomialBatch`.
+        trace_poly_values[table as usize].clone(),
+        config.fri_config.rate_bits,
+        false,
+        config.fri_config.cap_height,
         timing,
-        "prove memory STARK",
-        prove_single_table(
-            &all_stark.memory_stark,
-            config,
-            &trace_poly_values[Table::Memory as usize],
-            &trace_commitments[Table::Memory as usize],
-            &ctl_data_per_table[Table::Memory as usize],
-            ctl_challenges,
-            challenger,
-            timing,
-        )?
-    );
+        None,
+    )
+}
+
+/// Splits the tables into consecutive groups, such that the estimated memory needed to prove the
+/// tables of a group concurrently fits within `config.memory_budget`. Without a budget, all tables
+/// are in a single group.
+fn table_groups<F: Field>(
+    config: &StarkConfig,
+    trace_poly_values: &[Vec<PolynomialValues<F>>; NUM_TABLES],
+) -> Vec<Vec<Table>> {
+    let Some(budget) = config.memory_budget else {
+        return vec![Table::all().to_vec()];
+    };
 
-    Ok([
-        arithmetic_proof,
-        byte_packing_proof,
-        cpu_proof,
-        keccak_proof,
-        keccak_sponge_proof,
-        logic_proof,
-        memory_proof,
-    ])
+    let mut groups: Vec<Vec<Table>> = vec![];
+    let mut group_memory = 0;
+    for table in Table::all() {
+        let memory = estimate_proving_memory(config, &trace_poly_values[table as usize]);
+        if memory > budget {
+            log::warn!(
+                "Proving the {:?} table needs about {} bytes, more than the memory budget of {} \
+                 bytes",
+                table,
+                memory,
+                budget
+            );
+        }
+        match groups.last_mut() {
+            Some(group) if group_memory + memory <= budget => {
+                group.push(table);
+                group_memory += memory;
+            }
+            _ => {
+                groups.push(vec![table]);
+                group_memory = memory;
+            }
+        }
+    }
+    groups
+}
+
+/// A rough estimate, in bytes, of the memory needed to prove a table with the given trace. Besides
+/// the LDE of the trace and its Merkle tree, this accounts for the auxiliary and quotient
+/// polynomials, which are assumed to take about as much space.
+fn estimate_proving_memory<F: Field>(config: &StarkConfig, trace: &[PolynomialValues<F>]) -> usize {
+    const LDES_PER_TRACE: usize = 4;
+    let lde_size = trace.first().map_or(0, |poly| poly.len()) << config.fri_config.rate_bits;
+    LDES_PER_TRACE * trace.len() * lde_size * size_of::<F>()
+}
+
+/// Dispatches to `prove_single_table` with the STARK of the given table.
+#[allow(clippy::too_many_arguments)]
+fn prove_table<F, C, const D: usize>(
+    all_stark: &AllStark<F, D>,
+    table: Table,
+    config: &StarkConfig,
+    trace_poly_values: &[PolynomialValues<F>],
+    trace_commitment: &PolynomialBatch<F, C, D>,
+    ctl_data: &CtlData<F>,
+    ctl_challenges: &GrandProductChallengeSet<F>,
+    challenger: &mut Challenger<F, C::Hasher>,
+    timing: &mut TimingTree,
+) -> Result<StarkProofWithMetadata<F, C, D>>
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    macro_rules! prove {
+        ($stark:expr) => {
+            prove_single_table(
+                $stark,
+                config,
+                trace_poly_values,
+                trace_commitment,
+                ctl_data,
+                ctl_challenges,
+                challenger,
+                timing,
+            )
+        };
+    }
+
+    match table {
+        Table::Arithmetic => prove!(&all_stark.arithmetic_stark),
+        Table::BytePacking => prove!(&all_stark.byte_packing_stark),
+        Table::Cpu => prove!(&all_stark.cpu_stark),
+        Table::Keccak => prove!(&all_stark.keccak_stark),
+        Table::KeccakSponge => prove!(&all_stark.keccak_sponge_stark),
+        Table::Logic => prove!(&all_stark.logic_stark),
+        Table::Memory => prove!(&all_stark.memory_stark),
+    }
 }
 
 /// Compute proof for a single STARK table.
@@ -709,3 +804,33 @@ fn check_constraints<'a, F, C, S, const D: usize>(
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField as F;
+
+    use super::*;
+
+    #[test]
+    fn test_table_groups() {
+        let mut config = StarkConfig::standard_fast_config();
+        let trace_poly_values = core::array::from_fn(|_| vec![PolynomialValues::<F>::zero(8)]);
+        assert_eq!(
+            table_groups(&config, &trace_poly_values),
+            vec![Table::all().to_vec()]
+        );
+
+        // Each table fits alone, and two fit together.
+        let table_memory = estimate_proving_memory(&config, &trace_poly_values[0]);
+        config.memory_budget = Some(2 * table_memory + 1);
+        assert_eq!(
+            table_groups(&config, &trace_poly_values),
+            vec![
+                vec![Table::Arithmetic, Table::BytePacking],
+                vec![Table::Cpu, Table::Keccak],
+                vec![Table::KeccakSponge, Table::Logic],
+                vec![Table::Memory],
+            ]
+        );
+    }
+}
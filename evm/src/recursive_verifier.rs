@@ -33,6 +33,7 @@ use crate::cross_table_lookup::{
     CrossTableLookup, CtlCheckVarsTarget, GrandProductChallenge, GrandProductChallengeSet,
 };
 use crate::evaluation_frame::StarkEvaluationFrame;
+use crate::hardfork::Hardfork;
 use crate::lookup::LookupCheckVarsTarget;
 use crate::memory::segments::Segment;
 use crate::memory::VALUE_LIMBS;
@@ -53,7 +54,6 @@ pub(crate) struct PublicInputs<T: Copy + Default + Eq + PartialEq + Debug, P: Pl
     pub(crate) ctl_zs_first: Vec<T>,
     pub(crate) ctl_challenges: GrandProductChallengeSet<T>,
     pub(crate) challenger_state_before: P,
-    pub(crate) challenger_state_after: P,
 }
 
 impl<T: Copy + Debug + Default + Eq + PartialEq, P: PlonkyPermutation<T>> PublicInputs<T, P> {
@@ -75,7 +75,9 @@ impl<T: Copy + Debug + Default + Eq + PartialEq, P: PlonkyPermutation<T>> Public
                 .collect(),
         };
         let challenger_state_before = P::new(&mut iter);
-        let challenger_state_after = P::new(&mut iter);
+        // Skip the challenger state after the proof, which isn't needed since tables are proven
+        // independently.
+        P::new(&mut iter);
         let ctl_zs_first: Vec<_> = iter.collect();
 
         Self {
@@ -83,7 +85,6 @@ impl<T: Copy + Debug + Default + Eq + PartialEq, P: PlonkyPermutation<T>> Public
             ctl_zs_first,
             ctl_challenges,
             challenger_state_before,
-            challenger_state_after,
         }
     }
 }
@@ -447,6 +448,10 @@ pub(crate) fn get_memory_extra_looking_products_circuit<
             GlobalMetadata::BlockChainId as usize,
             public_values.block_metadata.block_chain_id,
         ),
+        (
+            GlobalMetadata::Hardfork as usize,
+            public_values.block_metadata.hardfork,
+        ),
         (
             GlobalMetadata::TxnNumberBefore as usize,
             public_values.extra_block_data.txn_number_before,
@@ -710,6 +715,16 @@ pub(crate) fn add_virtual_block_metadata<F: RichField + Extendable<D>, const D:
     let block_base_fee = builder.add_virtual_public_input_arr();
     let block_gas_used = builder.add_virtual_public_input_arr();
     let block_bloom = builder.add_virtual_public_input_arr();
+    let hardfork = builder.add_virtual_public_input();
+
+    // The hardfork must be one of the variants of `Hardfork`.
+    let mut hardfork_check = builder.one();
+    for fork in Hardfork::all() {
+        let diff = builder.add_const(hardfork, -F::from_canonical_usize(fork as usize));
+        hardfork_check = builder.mul(hardfork_check, diff);
+    }
+    builder.assert_zero(hardfork_check);
+
     BlockMetadataTarget {
         block_beneficiary,
         block_timestamp,
@@ -721,6 +736,7 @@ pub(crate) fn add_virtual_block_metadata<F: RichField + Extendable<D>, const D:
         block_base_fee,
         block_gas_used,
         block_bloom,
+        hardfork,
     }
 }
 
@@ -975,6 +991,10 @@ where
         limbs.copy_from_slice(&u256_limbs(block_metadata.block_bloom[i]));
     }
     witness.set_target_arr(&block_metadata_target.block_bloom, &block_bloom_limbs);
+    witness.set_target(
+        block_metadata_target.hardfork,
+        F::from_canonical_u8(block_metadata.hardfork as u8),
+    );
 
     Ok(())
 }
@@ -0,0 +1,432 @@
+//! A runner for the `GeneralStateTests` of https://github.com/ethereum/tests.
+//!
+//! A state test file contains a pre-state, a block environment and, for each fork, a list of
+//! transactions along with the expected post-state root and logs hash. Each transaction is
+//! executed as a single-transaction block on top of the pre-state, and the resulting state root
+//! and logs hash are compared with the expected ones. By default, only the witness is generated;
+//! a STARK proof of the block can optionally be generated and verified as well.
+//!
+//! Transactions are read from the `txbytes` field of the post-states, so test files produced by
+//! older versions of the test fillers, which don't include it, are skipped.
+
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fmt, fs};
+
+use anyhow::{anyhow, Context};
+use eth_trie_utils::nibbles::Nibbles;
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{BigEndianHash, H256, U256};
+use keccak_hash::keccak;
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::config::GenericConfig;
+use plonky2::util::timing::TimingTree;
+use serde::Deserialize;
+
+use crate::all_stark::AllStark;
+use crate::config::StarkConfig;
+use crate::generation::block_witness::{
+    parse_address, parse_bytes, parse_h256, parse_u256, AccountJson, PreState,
+};
+use crate::generation::mpt::{LegacyReceiptRlp, LogRlp};
+use crate::generation::split::{ExecutedBlock, TxnFailure};
+use crate::generation::GenerationInputs;
+use crate::hardfork::Hardfork;
+use crate::proof::{BlockHashes, BlockMetadata};
+use crate::prover::prove;
+use crate::verifier::verify_proof;
+use crate::witness::errors::{ExecutionError, ProgramError};
+use crate::Node;
+
+#[derive(Deserialize)]
+struct StateTestJson {
+    env: EnvJson,
+    pre: BTreeMap<String, AccountJson>,
+    post: BTreeMap<String, Vec<PostStateJson>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnvJson {
+    current_coinbase: String,
+    current_difficulty: Option<String>,
+    current_random: Option<String>,
+    current_gas_limit: String,
+    current_number: String,
+    current_timestamp: String,
+    current_base_fee: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PostStateJson {
+    hash: String,
+    logs: String,
+    txbytes: Option<String>,
+    indexes: TxnIndexes,
+    expect_exception: Option<String>,
+}
+
+/// The indexes, within the lists of the test's transaction, of the data, gas limit and value of a
+/// transaction.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub struct TxnIndexes {
+    pub data: usize,
+    pub gas: usize,
+    pub value: usize,
+}
+
+impl fmt::Display for TxnIndexes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "d={} g={} v={}", self.data, self.gas, self.value)
+    }
+}
+
+/// The result of running a `StateTestCase`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Passed,
+    /// The test failed, for the given reason.
+    Failed(String),
+    /// The test wasn't run, for the given reason.
+    Skipped(String),
+}
+
+/// A transaction of a state test, with the fork whose rules it should be executed under.
+#[derive(Clone, Debug)]
+pub struct StateTestCase {
+    /// The name of the test, as given in the test file.
+    pub name: String,
+    /// The name of the fork, as given in the test file.
+    pub fork: String,
+    pub indexes: TxnIndexes,
+    /// The inputs of the single-transaction block, or the reason why the case can't be run. The
+    /// post-block values are left empty, and computed during execution.
+    inputs: Result<GenerationInputs, String>,
+    expected_state_root: H256,
+    expected_logs_hash: H256,
+    /// The exception expected from the transaction, which makes it invalid.
+    expect_exception: Option<String>,
+}
+
+impl StateTestCase {
+    /// Executes the transaction and compares the post-state root and logs hash with the expected
+    /// ones. If `prover` is given, the block is also proven, and its proof verified.
+    pub fn run<F, C, const D: usize>(
+        &self,
+        prover: Option<(&AllStark<F, D>, &StarkConfig)>,
+    ) -> Outcome
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let inputs = match &self.inputs {
+            Ok(inputs) => inputs.clone(),
+            Err(reason) => return Outcome::Skipped(reason.clone()),
+        };
+        self.try_run::<F, C, D>(inputs, prover)
+            .unwrap_or_else(|err| Outcome::Failed(format!("{err:#}")))
+    }
+
+    fn try_run<F, C, const D: usize>(
+        &self,
+        inputs: GenerationInputs,
+        prover: Option<(&AllStark<F, D>, &StarkConfig)>,
+    ) -> anyhow::Result<Outcome>
+    where
+        F: RichField + Extendable<D>,
+        C: GenericConfig<D, F = F>,
+    {
+        let executed = match (ExecutedBlock::new::<F, D>(inputs), &self.expect_exception) {
+            (Ok(executed), None) => executed,
+            (Err(err), None) => return Err(err.context("Execution failed")),
+            (Ok(_), Some(exception)) => {
+                return Ok(Outcome::Failed(format!(
+                    "Expected {exception}, but the transaction was executed"
+                )))
+            }
+            (Err(err), Some(exception)) => {
+                return Ok(if is_txn_rejection(&err) && is_txn_exception(exception) {
+                    Outcome::Passed
+                } else {
+                    Outcome::Failed(format!("Expected {exception}, but got {err:#}"))
+                })
+            }
+        };
+
+        let state_root = executed.trie_roots_after().state_root;
+        if state_root != self.expected_state_root {
+            return Ok(Outcome::Failed(format!(
+                "State root {:?}, expected {:?}",
+                state_root, self.expected_state_root
+            )));
+        }
+        let logs_hash = logs_hash(&executed.tries_after().receipts_trie)?;
+        if logs_hash != self.expected_logs_hash {
+            return Ok(Outcome::Failed(format!(
+                "Logs hash {:?}, expected {:?}",
+                logs_hash, self.expected_logs_hash
+            )));
+        }
+
+        if let Some((all_stark, config)) = prover {
+            let mut inputs = executed.txn_range_inputs(0..1);
+            inputs.block_metadata.block_gas_used = inputs.gas_used_after;
+            inputs.block_metadata.block_bloom = inputs.block_bloom_after;
+            let mut timing = TimingTree::new("prove", log::Level::Debug);
+            let proof = prove::<F, C, D>(all_stark, config, inputs, &mut timing)?;
+            verify_proof(all_stark, proof, config)?;
+        }
+
+        Ok(Outcome::Passed)
+    }
+}
+
+/// Whether `err` is the kernel rejecting an invalid transaction, which it does by panicking while
+/// executing it.
+fn is_txn_rejection(err: &anyhow::Error) -> bool {
+    let Some(failure) = err.downcast_ref::<TxnFailure>() else {
+        return false;
+    };
+    matches!(
+        failure.error.downcast_ref::<ExecutionError>(),
+        Some(ExecutionError {
+            error: ProgramError::KernelPanic,
+            txn_index: Some(_),
+            ..
+        })
+    )
+}
+
+/// Whether all the alternatives of an `expectException` of a test file are exceptions making a
+/// transaction invalid, as opposed to e.g. block-level exceptions, which the kernel can't raise.
+fn is_txn_exception(exception: &str) -> bool {
+    exception
+        .split('|')
+        .all(|e| e.starts_with("TR_") || e.starts_with("TransactionException."))
+}
+
+/// The hardfork whose rules are applied for the given fork name of the test files, if supported.
+/// Paris is executed as London: its only execution change, `DIFFICULTY` becoming `PREVRANDAO`, is
+/// covered by the `block_random` of the block metadata. Cancun isn't supported, since transient
+/// storage, `MCOPY` and blob transactions aren't implemented.
+pub fn parse_fork(name: &str) -> Option<Hardfork> {
+    match name {
+        "Berlin" => Some(Hardfork::Berlin),
+        "London" | "Merge" | "Paris" => Some(Hardfork::London),
+        "Shanghai" => Some(Hardfork::Shanghai),
+        _ => None,
+    }
+}
+
+/// Parses a state test file, and returns its cases, ordered by test name, fork and position in
+/// the file.
+pub fn parse_state_tests(json: &str) -> anyhow::Result<Vec<StateTestCase>> {
+    let tests: BTreeMap<String, StateTestJson> =
+        serde_json::from_str(json).context("Invalid state test")?;
+
+    let mut cases = vec![];
+    for (name, test) in tests {
+        let pre_state =
+            PreState::from_accounts(&test.pre).with_context(|| format!("Test {name}"))?;
+        for (fork, post_states) in &test.post {
+            for post in post_states {
+                let inputs = match (parse_fork(fork), &post.txbytes) {
+                    (None, _) => Err(format!("Unsupported fork {fork}")),
+                    (Some(_), None) => Err("Missing txbytes".to_string()),
+                    (Some(hardfork), Some(txbytes)) => Ok(block_inputs(
+                        &pre_state,
+                        &test.env,
+                        hardfork,
+                        parse_bytes(txbytes)?,
+                    )?),
+                };
+                cases.push(StateTestCase {
+                    name: name.clone(),
+                    fork: fork.clone(),
+                    indexes: post.indexes,
+                    inputs,
+                    expected_state_root: parse_h256(&post.hash)?,
+                    expected_logs_hash: parse_h256(&post.logs)?,
+                    expect_exception: post.expect_exception.clone(),
+                });
+            }
+        }
+    }
+    Ok(cases)
+}
+
+/// Reads and parses a state test file.
+pub fn load_state_tests(path: &Path) -> anyhow::Result<Vec<StateTestCase>> {
+    let json =
+        fs::read_to_string(path).with_context(|| format!("Unable to read {}", path.display()))?;
+    parse_state_tests(&json).with_context(|| format!("Unable to parse {}", path.display()))
+}
+
+/// The paths of all JSON files under the given path, which may be a single file, in order.
+pub fn state_test_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    let mut files = vec![];
+    for entry in entries {
+        if entry.is_dir() {
+            files.extend(state_test_files(&entry)?);
+        } else if entry.extension().is_some_and(|ext| ext == "json") {
+            files.push(entry);
+        }
+    }
+    Ok(files)
+}
+
+/// The outcomes of a run of state tests.
+#[derive(Clone, Debug, Default)]
+pub struct StateTestReport {
+    results: Vec<(String, String, Outcome)>,
+}
+
+impl StateTestReport {
+    pub fn record(&mut self, case: &StateTestCase, outcome: Outcome) {
+        let name = format!("{} [{}]", case.name, case.indexes);
+        self.results.push((name, case.fork.clone(), outcome));
+    }
+
+    /// Records a test file which couldn't be loaded as a failure.
+    pub fn record_invalid_file(&mut self, path: &Path, err: &anyhow::Error) {
+        let outcome = Outcome::Failed(format!("{err:#}"));
+        self.results
+            .push((path.display().to_string(), "-".to_string(), outcome));
+    }
+
+    pub fn num_failed(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|(_, _, outcome)| matches!(outcome, Outcome::Failed(_)))
+            .count()
+    }
+
+    /// Writes the number of passed, failed and skipped cases for each fork, followed by the
+    /// reason of each failure.
+    pub fn write_summary<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let mut counts: BTreeMap<&str, [usize; 3]> = BTreeMap::new();
+        for (_, fork, outcome) in &self.results {
+            let i = match outcome {
+                Outcome::Passed => 0,
+                Outcome::Failed(_) => 1,
+                Outcome::Skipped(_) => 2,
+            };
+            counts.entry(fork).or_default()[i] += 1;
+        }
+
+        writeln!(
+            out,
+            "{:<12} {:>8} {:>8} {:>8}",
+            "fork", "passed", "failed", "skipped"
+        )?;
+        let mut total = [0; 3];
+        for (fork, [passed, failed, skipped]) in counts {
+            writeln!(out, "{fork:<12} {passed:>8} {failed:>8} {skipped:>8}")?;
+            total[0] += passed;
+            total[1] += failed;
+            total[2] += skipped;
+        }
+        let [passed, failed, skipped] = total;
+        writeln!(out, "{:<12} {passed:>8} {failed:>8} {skipped:>8}", "total")?;
+
+        if failed > 0 {
+            writeln!(out, "\nFailures:")?;
+            for (name, fork, outcome) in &self.results {
+                if let Outcome::Failed(reason) = outcome {
+                    writeln!(out, "{name} ({fork}): {reason}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The inputs of the first block after the pre-state, containing only the given transaction.
+fn block_inputs(
+    pre_state: &PreState,
+    env: &EnvJson,
+    hardfork: Hardfork,
+    txn: Vec<u8>,
+) -> anyhow::Result<GenerationInputs> {
+    let difficulty = env
+        .current_difficulty
+        .as_deref()
+        .map_or(Ok(U256::zero()), parse_u256)?;
+    // Before Paris, `PREVRANDAO` was `DIFFICULTY`.
+    let random = match &env.current_random {
+        Some(random) => parse_h256(random)?,
+        None => H256::from_uint(&difficulty),
+    };
+    // Blocks must have a base fee of 0 before London.
+    let base_fee = match &env.current_base_fee {
+        Some(base_fee) if hardfork >= Hardfork::London => parse_u256(base_fee)?,
+        _ => U256::zero(),
+    };
+    let block_number = parse_u256(&env.current_number)?;
+
+    let block_metadata = BlockMetadata {
+        block_beneficiary: parse_address(&env.current_coinbase)?,
+        block_timestamp: parse_u256(&env.current_timestamp)?,
+        block_number,
+        block_difficulty: difficulty,
+        block_random: random,
+        block_gaslimit: parse_u256(&env.current_gas_limit)?,
+        block_chain_id: 1.into(),
+        block_base_fee: base_fee,
+        block_gas_used: U256::zero(),
+        block_bloom: [U256::zero(); 8],
+        hardfork,
+    };
+
+    Ok(GenerationInputs {
+        signed_txns: vec![txn],
+        tries: pre_state.tries.clone(),
+        contract_code: pre_state.contract_code.clone(),
+        block_metadata,
+        genesis_state_trie_root: HashedPartialTrie::from(Node::Empty).hash(),
+        block_hashes: test_block_hashes(block_number.low_u64()),
+        addresses: pre_state.addresses.clone(),
+        ..GenerationInputs::default()
+    })
+}
+
+/// The hashes of the 256 blocks preceding the given block number. As in the reference test
+/// runners, the hash of block `n` is the Keccak hash of `n` written in decimal.
+fn test_block_hashes(block_number: u64) -> BlockHashes {
+    let prev_hashes = (0..256)
+        .map(|i| match (block_number + i).checked_sub(256) {
+            Some(n) => keccak(n.to_string()),
+            None => H256::zero(),
+        })
+        .collect();
+    BlockHashes {
+        prev_hashes,
+        cur_hash: H256::zero(),
+    }
+}
+
+/// The hash of the RLP-encoded list of logs of the block's only receipt.
+fn logs_hash(receipts_trie: &HashedPartialTrie) -> anyhow::Result<H256> {
+    let key = Nibbles::from_str("0x80").unwrap();
+    let receipt = receipts_trie.get(key).context("Missing receipt")?;
+    // Receipts of typed transactions are prefixed by the transaction type.
+    let payload = match receipt.first() {
+        Some(&txn_type) if txn_type < 0x80 => &receipt[1..],
+        _ => receipt,
+    };
+    let receipt: LegacyReceiptRlp =
+        rlp::decode(payload).map_err(|err| anyhow!("Invalid receipt: {:?}", err))?;
+    Ok(keccak(rlp::encode_list::<LogRlp, _>(&receipt.logs)))
+}
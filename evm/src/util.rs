@@ -1,4 +1,6 @@
 use std::mem::{size_of, transmute_copy, ManuallyDrop};
+#[cfg(feature = "parallel")]
+use std::sync::{Arc, Mutex, PoisonError};
 
 use ethereum_types::{H160, H256, U256};
 use itertools::Itertools;
@@ -9,8 +11,12 @@ use plonky2::field::polynomial::PolynomialValues;
 use plonky2::field::types::Field;
 use plonky2::hash::hash_types::RichField;
 use plonky2::iop::ext_target::ExtensionTarget;
+use plonky2::util::timing::TimingTree;
 use plonky2::util::transpose;
+#[cfg(feature = "parallel")]
+use plonky2_maybe_rayon::rayon;
 
+use crate::all_stark::Table;
 use crate::witness::errors::ProgramError;
 
 /// Construct an integer from its constituent bits (in little-endian order)
@@ -35,6 +41,80 @@ pub fn limb_from_bits_le_recursive<F: RichField + Extendable<D>, const D: usize>
         })
 }
 
+/// The thread pools of the tables, built on first use and then reused by all tasks of a table.
+#[cfg(feature = "parallel")]
+static TABLE_POOLS: [Mutex<Option<Arc<rayon::ThreadPool>>>; crate::all_stark::NUM_TABLES] =
+    [const { Mutex::new(None) }; crate::all_stark::NUM_TABLES];
+
+/// Returns the pool of `num_threads` threads dedicated to `table`, building it if needed.
+#[cfg(feature = "parallel")]
+fn table_pool(
+    table: Table,
+    num_threads: usize,
+) -> Result<Arc<rayon::ThreadPool>, rayon::ThreadPoolBuildError> {
+    let mut pool = TABLE_POOLS[table as usize]
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    match &*pool {
+        Some(pool) if pool.current_num_threads() == num_threads => Ok(pool.clone()),
+        _ => {
+            let new_pool = Arc::new(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(num_threads)
+                    .build()?,
+            );
+            *pool = Some(new_pool.clone());
+            Ok(new_pool)
+        }
+    }
+}
+
+/// Runs `op`, which processes `table`, in a dedicated pool of `threads_per_table` threads if
+/// given, or in the current thread pool otherwise. Since tables are processed concurrently, `op`
+/// gets its own timing tree, which is returned so that the caller can add it to its own tree once
+/// all tables are done.
+pub(crate) fn run_table_task<R: Send>(
+    threads_per_table: Option<usize>,
+    table: Table,
+    name: &str,
+    op: impl FnOnce(&mut TimingTree) -> R + Send,
+) -> (R, TimingTree) {
+    let mut timing = TimingTree::new(name, log::Level::Debug);
+    let task = || op(&mut timing);
+
+    #[cfg(feature = "parallel")]
+    let result = match threads_per_table.map(|num_threads| table_pool(table, num_threads)) {
+        Some(Ok(pool)) => pool.install(task),
+        Some(Err(err)) => {
+            log::warn!("Failed to build a thread pool for the {table:?} table: {err}");
+            task()
+        }
+        None => task(),
+    };
+    #[cfg(not(feature = "parallel"))]
+    let result = {
+        let _ = (threads_per_table, table);
+        task()
+    };
+
+    timing.pop();
+    (result, timing)
+}
+
+/// Adds the timing trees returned by [`run_table_task`] to `timing`, and returns the results.
+pub(crate) fn add_table_timings<R>(
+    results: Vec<(R, TimingTree)>,
+    timing: &mut TimingTree,
+) -> Vec<R> {
+    results
+        .into_iter()
+        .map(|(result, table_timing)| {
+            timing.add_child(table_timing);
+            result
+        })
+        .collect()
+}
+
 /// A helper function to transpose a row-wise trace and put it in the format that `prove` expects.
 pub fn trace_rows_to_poly_values<F: Field, const COLUMNS: usize>(
     trace_rows: Vec<[F; COLUMNS]>,
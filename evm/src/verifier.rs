@@ -186,6 +186,10 @@ where
             GlobalMetadata::BlockBaseFee,
             public_values.block_metadata.block_base_fee,
         ),
+        (
+            GlobalMetadata::Hardfork,
+            public_values.block_metadata.hardfork.into(),
+        ),
         (
             GlobalMetadata::BlockCurrentHash,
             h2u(public_values.block_hashes.cur_hash),
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use ethereum_types::U256;
 
 #[allow(dead_code)]
@@ -36,3 +38,53 @@ pub enum ProverInputError {
     InvalidInput,
     InvalidFunction,
 }
+
+/// A `ProgramError` which aborted witness generation, along with the execution context in which it
+/// was raised.
+#[derive(Debug)]
+pub struct ExecutionError {
+    pub error: ProgramError,
+    /// The program counter of the failing instruction.
+    pub program_counter: usize,
+    /// The nearest global label at or before `program_counter`, if the error was raised in the
+    /// kernel.
+    pub kernel_location: Option<String>,
+    pub context: usize,
+    /// The index of the transaction being executed, or `None` if no transaction has started yet.
+    pub txn_index: Option<usize>,
+    pub call_depth: usize,
+    /// The name of the prover input function called by the failing instruction, e.g.
+    /// `ff::bn254_base::inverse`, if it is a `PROVER_INPUT`. Its arguments are on top of `stack`.
+    pub prover_input: Option<String>,
+    /// The topmost stack elements, top first.
+    pub stack: Vec<U256>,
+    /// A description of the cause of the error, when it could be determined.
+    pub details: Option<String>,
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.error)?;
+        if let Some(details) = &self.details {
+            write!(f, " ({details})")?;
+        }
+        match &self.kernel_location {
+            Some(location) => write!(f, " in kernel at {location} (pc={})", self.program_counter)?,
+            None => write!(f, " in user code at pc={}", self.program_counter)?,
+        }
+        if let Some(prover_input) = &self.prover_input {
+            write!(f, ", in PROVER_INPUT({prover_input})")?;
+        }
+        match self.txn_index {
+            Some(txn_index) => write!(f, ", txn={txn_index}")?,
+            None => write!(f, ", before the first txn")?,
+        }
+        write!(
+            f,
+            ", context={}, call_depth={}, stack={:?}",
+            self.context, self.call_depth, self.stack
+        )
+    }
+}
+
+impl std::error::Error for ExecutionError {}
@@ -1,4 +1,4 @@
-pub(crate) mod errors;
+pub mod errors;
 mod gas;
 pub(crate) mod memory;
 mod operation;
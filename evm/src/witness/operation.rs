@@ -9,11 +9,13 @@ use crate::cpu::columns::CpuColumnsView;
 use crate::cpu::kernel::aggregator::KERNEL;
 use crate::cpu::kernel::assembler::BYTES_PER_OFFSET;
 use crate::cpu::kernel::constants::context_metadata::ContextMetadata;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::membus::NUM_GP_CHANNELS;
 use crate::cpu::simple_logic::eq_iszero::generate_pinv_diff;
 use crate::cpu::stack_bounds::MAX_USER_STACK_SIZE;
 use crate::extension_tower::BN_BASE;
 use crate::generation::state::GenerationState;
+use crate::hardfork::Hardfork;
 use crate::memory::segments::Segment;
 use crate::util::u256_to_usize;
 use crate::witness::errors::MemoryError::{ContextTooLarge, SegmentTooLarge, VirtTooLarge};
@@ -402,6 +404,10 @@ pub(crate) fn generate_push<F: Field>(
     state: &mut GenerationState<F>,
     mut row: CpuColumnsView<F>,
 ) -> Result<(), ProgramError> {
+    if n == 0 {
+        return generate_push0(state, row);
+    }
+
     let code_context = state.registers.code_context();
     let num_bytes = n as usize;
     if num_bytes > 32 {
@@ -432,6 +438,32 @@ pub(crate) fn generate_push<F: Field>(
     Ok(())
 }
 
+/// `PUSH0` also reads the hardfork, to check that it is valid in user mode. See `cpu/push0.rs`.
+fn generate_push0<F: Field>(
+    state: &mut GenerationState<F>,
+    mut row: CpuColumnsView<F>,
+) -> Result<(), ProgramError> {
+    let address = MemoryAddress::new(
+        0,
+        Segment::GlobalMetadata,
+        GlobalMetadata::Hardfork as usize,
+    );
+    let (hardfork, log_in) = mem_read_gp_with_log_and_fill(1, address, state, &mut row);
+    let shanghai = U256::from(Hardfork::Shanghai);
+    row.general.push0_mut().shanghai_offset = if hardfork >= shanghai {
+        F::from_canonical_usize((hardfork - shanghai).as_usize())
+    } else {
+        // The kernel raises an invalid opcode exception instead, see `try_perform_instruction`.
+        F::ZERO
+    };
+
+    push_with_write(state, &mut row, U256::zero())?;
+    state.traces.push_memory(log_in);
+    state.traces.push_cpu(row);
+
+    Ok(())
+}
+
 // This instruction is special. The order of the operations are:
 // - Write `stack_top` at `stack[stack_len - 1]`
 // - Read `val` at `stack[stack_len - 1 - n]`
@@ -6,19 +6,23 @@ use plonky2::field::polynomial::PolynomialValues;
 use plonky2::hash::hash_types::RichField;
 use plonky2::timed;
 use plonky2::util::timing::TimingTree;
+use plonky2_maybe_rayon::*;
 
-use crate::all_stark::{AllStark, NUM_TABLES};
+use crate::all_stark::{AllStark, Table, NUM_TABLES};
 use crate::arithmetic::{BinaryOperator, Operation};
 use crate::byte_packing::byte_packing_stark::BytePackingOp;
 use crate::config::StarkConfig;
 use crate::cpu::columns::CpuColumnsView;
 use crate::keccak_sponge::columns::KECCAK_WIDTH_BYTES;
 use crate::keccak_sponge::keccak_sponge_stark::KeccakSpongeOp;
-use crate::util::trace_rows_to_poly_values;
+use crate::util::{add_table_timings, run_table_task, trace_rows_to_poly_values};
 use crate::witness::memory::MemoryOp;
 use crate::{arithmetic, keccak, keccak_sponge, logic};
 
-#[derive(Clone, Copy, Debug)]
+/// Generates the trace of a single table, given a timing tree.
+type TraceTask<'a, T> = Box<dyn FnOnce(&mut TimingTree) -> Vec<PolynomialValues<T>> + Send + 'a>;
+
+#[derive(Clone, Copy, Debug, Default)]
 pub struct TraceCheckpoint {
     pub(self) arithmetic_len: usize,
     pub(self) byte_packing_len: usize,
@@ -56,30 +60,17 @@ impl<T: Copy> Traces<T> {
     /// Returns the actual trace lengths for each STARK module.
     //  Uses a `TraceCheckPoint` as return object for convenience.
     pub fn get_lengths(&self) -> TraceCheckpoint {
+        let rows = self.rows_since(TraceCheckpoint::default());
         TraceCheckpoint {
-            arithmetic_len: self
-                .arithmetic_ops
-                .iter()
-                .map(|op| match op {
-                    Operation::TernaryOperation { .. } => 2,
-                    Operation::BinaryOperation { operator, .. } => match operator {
-                        BinaryOperator::Div | BinaryOperator::Mod => 2,
-                        _ => 1,
-                    },
-                })
-                .sum(),
-            byte_packing_len: self.byte_packing_ops.iter().map(|op| op.bytes.len()).sum(),
-            cpu_len: self.cpu.len(),
-            keccak_len: self.keccak_inputs.len() * keccak::keccak_stark::NUM_ROUNDS,
-            keccak_sponge_len: self
-                .keccak_sponge_ops
-                .iter()
-                .map(|op| op.input.len() / keccak_sponge::columns::KECCAK_RATE_BYTES + 1)
-                .sum(),
-            logic_len: self.logic_ops.len(),
+            arithmetic_len: rows[Table::Arithmetic as usize],
+            byte_packing_len: rows[Table::BytePacking as usize],
+            cpu_len: rows[Table::Cpu as usize],
+            keccak_len: rows[Table::Keccak as usize],
+            keccak_sponge_len: rows[Table::KeccakSponge as usize],
+            logic_len: rows[Table::Logic as usize],
             // This is technically a lower-bound, as we may fill gaps,
             // but this gives a relatively good estimate.
-            memory_len: self.memory_ops.len(),
+            memory_len: rows[Table::Memory as usize],
         }
     }
 
@@ -96,6 +87,36 @@ impl<T: Copy> Traces<T> {
         }
     }
 
+    /// Returns the number of rows contributed to each table, indexed by `Table`, by the operations
+    /// recorded since `checkpoint` (which must have been obtained with `Traces::checkpoint`).
+    pub(crate) fn rows_since(&self, checkpoint: TraceCheckpoint) -> [usize; NUM_TABLES] {
+        let mut rows = [0; NUM_TABLES];
+        rows[Table::Arithmetic as usize] = self.arithmetic_ops[checkpoint.arithmetic_len..]
+            .iter()
+            .map(|op| match op {
+                Operation::TernaryOperation { .. } => 2,
+                Operation::BinaryOperation { operator, .. } => match operator {
+                    BinaryOperator::Div | BinaryOperator::Mod => 2,
+                    _ => 1,
+                },
+            })
+            .sum();
+        rows[Table::BytePacking as usize] = self.byte_packing_ops[checkpoint.byte_packing_len..]
+            .iter()
+            .map(|op| op.bytes.len())
+            .sum();
+        rows[Table::Cpu as usize] = self.cpu.len() - checkpoint.cpu_len;
+        rows[Table::Keccak as usize] =
+            (self.keccak_inputs.len() - checkpoint.keccak_len) * keccak::keccak_stark::NUM_ROUNDS;
+        rows[Table::KeccakSponge as usize] = self.keccak_sponge_ops[checkpoint.keccak_sponge_len..]
+            .iter()
+            .map(|op| op.input.len() / keccak_sponge::columns::KECCAK_RATE_BYTES + 1)
+            .sum();
+        rows[Table::Logic as usize] = self.logic_ops.len() - checkpoint.logic_len;
+        rows[Table::Memory as usize] = self.memory_ops.len() - checkpoint.memory_len;
+        rows
+    }
+
     pub fn rollback(&mut self, checkpoint: TraceCheckpoint) {
         self.arithmetic_ops.truncate(checkpoint.arithmetic_len);
         self.byte_packing_ops.truncate(checkpoint.byte_packing_len);
@@ -173,56 +194,78 @@ impl<T: Copy> Traces<T> {
             keccak_sponge_ops,
         } = self;
 
-        let arithmetic_trace = timed!(
-            timing,
-            "generate arithmetic trace",
-            all_stark.arithmetic_stark.generate_trace(arithmetic_ops)
-        );
-        let byte_packing_trace = timed!(
-            timing,
-            "generate byte packing trace",
-            all_stark
-                .byte_packing_stark
-                .generate_trace(byte_packing_ops, cap_elements, timing)
-        );
-        let cpu_rows = cpu.into_iter().map(|x| x.into()).collect();
-        let cpu_trace = trace_rows_to_poly_values(cpu_rows);
-        let keccak_trace = timed!(
-            timing,
-            "generate Keccak trace",
-            all_stark
-                .keccak_stark
-                .generate_trace(keccak_inputs, cap_elements, timing)
-        );
-        let keccak_sponge_trace = timed!(
-            timing,
-            "generate Keccak sponge trace",
-            all_stark
-                .keccak_sponge_stark
-                .generate_trace(keccak_sponge_ops, cap_elements, timing)
-        );
-        let logic_trace = timed!(
-            timing,
-            "generate logic trace",
-            all_stark
-                .logic_stark
-                .generate_trace(logic_ops, cap_elements, timing)
-        );
-        let memory_trace = timed!(
-            timing,
-            "generate memory trace",
-            all_stark.memory_stark.generate_trace(memory_ops, timing)
-        );
-
-        [
-            arithmetic_trace,
-            byte_packing_trace,
-            cpu_trace,
-            keccak_trace,
-            keccak_sponge_trace,
-            logic_trace,
-            memory_trace,
-        ]
+        // The tables are independent, so their traces are generated concurrently.
+        let tasks: [(&str, TraceTask<T>); NUM_TABLES] = [
+            (
+                "generate arithmetic trace",
+                Box::new(|_: &mut TimingTree| {
+                    all_stark.arithmetic_stark.generate_trace(arithmetic_ops)
+                }),
+            ),
+            (
+                "generate byte packing trace",
+                Box::new(|timing: &mut TimingTree| {
+                    all_stark.byte_packing_stark.generate_trace(
+                        byte_packing_ops,
+                        cap_elements,
+                        timing,
+                    )
+                }),
+            ),
+            (
+                "generate CPU trace",
+                Box::new(|_: &mut TimingTree| {
+                    let cpu_rows = cpu.into_iter().map(|x| x.into()).collect();
+                    trace_rows_to_poly_values(cpu_rows)
+                }),
+            ),
+            (
+                "generate Keccak trace",
+                Box::new(|timing: &mut TimingTree| {
+                    all_stark
+                        .keccak_stark
+                        .generate_trace(keccak_inputs, cap_elements, timing)
+                }),
+            ),
+            (
+                "generate Keccak sponge trace",
+                Box::new(|timing: &mut TimingTree| {
+                    all_stark.keccak_sponge_stark.generate_trace(
+                        keccak_sponge_ops,
+                        cap_elements,
+                        timing,
+                    )
+                }),
+            ),
+            (
+                "generate logic trace",
+                Box::new(|timing: &mut TimingTree| {
+                    all_stark
+                        .logic_stark
+                        .generate_trace(logic_ops, cap_elements, timing)
+                }),
+            ),
+            (
+                "generate memory trace",
+                Box::new(|timing: &mut TimingTree| {
+                    all_stark.memory_stark.generate_trace(memory_ops, timing)
+                }),
+            ),
+        ];
+
+        let traces = timed!(timing, "generate all table traces", {
+            let traces = Vec::from(tasks)
+                .into_par_iter()
+                .zip(Table::all())
+                .map(|((name, task), table)| {
+                    run_table_task(config.threads_per_table, table, name, task)
+                })
+                .collect::<Vec<_>>();
+            add_table_timings(traces, timing)
+        });
+        traces
+            .try_into()
+            .expect("There should be exactly one trace per table")
     }
 }
 
@@ -1,4 +1,4 @@
-use anyhow::bail;
+use ethereum_types::{BigEndianHash, H256, U256};
 use log::log_enabled;
 use plonky2::field::types::Field;
 
@@ -6,19 +6,22 @@ use super::memory::{MemoryOp, MemoryOpKind};
 use super::util::fill_channel_with_value;
 use crate::cpu::columns::CpuColumnsView;
 use crate::cpu::kernel::aggregator::KERNEL;
+use crate::cpu::kernel::constants::global_metadata::GlobalMetadata;
 use crate::cpu::stack::{
     EQ_STACK_BEHAVIOR, IS_ZERO_STACK_BEHAVIOR, JUMPI_OP, JUMP_OP, STACK_BEHAVIORS,
 };
 use crate::cpu::stack_bounds::MAX_USER_STACK_SIZE;
 use crate::generation::state::GenerationState;
+use crate::hardfork::Hardfork;
 use crate::memory::segments::Segment;
-use crate::witness::errors::ProgramError;
+use crate::util::u256_to_usize;
+use crate::witness::errors::{ExecutionError, ProgramError};
 use crate::witness::gas::gas_to_charge;
 use crate::witness::memory::MemoryAddress;
 use crate::witness::memory::MemoryChannel::GeneralPurpose;
 use crate::witness::operation::*;
 use crate::witness::state::RegistersState;
-use crate::witness::util::mem_read_code_with_log_and_fill;
+use crate::witness::util::{mem_read_code_with_log_and_fill, stack_peek};
 use crate::{arithmetic, logic};
 
 fn read_code_memory<F: Field>(state: &mut GenerationState<F>, row: &mut CpuColumnsView<F>) -> u8 {
@@ -33,7 +36,7 @@ fn read_code_memory<F: Field>(state: &mut GenerationState<F>, row: &mut CpuColum
     opcode
 }
 
-fn decode(registers: RegistersState, opcode: u8) -> Result<Operation, ProgramError> {
+pub(crate) fn decode(registers: RegistersState, opcode: u8) -> Result<Operation, ProgramError> {
     match (opcode, registers.is_kernel) {
         (0x00, _) => Ok(Operation::Syscall(opcode, 0, false)), // STOP
         (0x01, _) => Ok(Operation::BinaryArithmetic(arithmetic::BinaryOperator::Add)),
@@ -309,6 +312,14 @@ fn base_row<F: Field>(state: &mut GenerationState<F>) -> (CpuColumnsView<F>, u8)
 fn try_perform_instruction<F: Field>(state: &mut GenerationState<F>) -> Result<(), ProgramError> {
     let (mut row, opcode) = base_row(state);
     let op = decode(state.registers, opcode)?;
+    // PUSH0 was introduced in Shanghai, see EIP-3855.
+    if op == Operation::Push(0)
+        && !state.registers.is_kernel
+        && state.memory.read_global_metadata(GlobalMetadata::Hardfork)
+            < U256::from(Hardfork::Shanghai)
+    {
+        return Err(ProgramError::InvalidOpcode);
+    }
 
     if state.registers.is_kernel {
         log_kernel_instruction(state, op);
@@ -411,7 +422,7 @@ fn handle_error<F: Field>(state: &mut GenerationState<F>, err: ProgramError) ->
         ProgramError::InvalidJumpDestination => 3,
         ProgramError::InvalidJumpiDestination => 4,
         ProgramError::StackOverflow => 5,
-        _ => bail!("TODO: figure out what to do with this..."),
+        _ => return Err(execution_error(state, err, None).into()),
     };
 
     let checkpoint = state.checkpoint();
@@ -426,6 +437,70 @@ fn handle_error<F: Field>(state: &mut GenerationState<F>, err: ProgramError) ->
     Ok(())
 }
 
+/// If the kernel is panicking because it reached a hashed-out MPT node, describes that node, which
+/// should have been included in the trie inputs.
+fn missing_trie_node<F: Field>(state: &GenerationState<F>) -> Option<String> {
+    let pc = state.registers.program_counter;
+    // The stack is (node_type, node_payload_ptr, num_nibbles, key, ...), except in `mpt_delete`,
+    // which already consumed `node_type`.
+    let (payload_ptr_depth, operation) = if pc == KERNEL.global_labels["mpt_read_hash_node"] {
+        (1, "reading")
+    } else if pc == KERNEL.global_labels["mpt_insert_hash_node"] {
+        (1, "inserting")
+    } else if pc == KERNEL.global_labels["mpt_delete_hash_node"] {
+        (0, "deleting")
+    } else {
+        return None;
+    };
+
+    let payload_ptr = u256_to_usize(stack_peek(state, payload_ptr_depth).ok()?).ok()?;
+    let num_nibbles = stack_peek(state, payload_ptr_depth + 1).ok()?;
+    let key = stack_peek(state, payload_ptr_depth + 2).ok()?;
+    let hash = state
+        .memory
+        .get(MemoryAddress::new(0, Segment::TrieData, payload_ptr));
+    Some(format!(
+        "Missing trie node {:?}: it was hashed out in the trie inputs, but is needed for {} the \
+         key whose last {} nibbles are {:#x}",
+        H256::from_uint(&hash),
+        operation,
+        num_nibbles,
+        key
+    ))
+}
+
+/// Attaches to `error` the location and context of the instruction which raised it.
+fn execution_error<F: Field>(
+    state: &GenerationState<F>,
+    error: ProgramError,
+    details: Option<String>,
+) -> ExecutionError {
+    let program_counter = state.registers.program_counter;
+    let is_kernel = state.registers.is_kernel;
+    let prover_input = if is_kernel {
+        KERNEL
+            .prover_inputs
+            .get(&program_counter)
+            .map(|input_fn| input_fn.to_string())
+    } else {
+        None
+    };
+    ExecutionError {
+        error,
+        program_counter,
+        kernel_location: is_kernel.then(|| KERNEL.offset_name(program_counter)),
+        context: state.registers.context,
+        txn_index: state.next_txn_index.checked_sub(1),
+        call_depth: state
+            .memory
+            .read_global_metadata(GlobalMetadata::CallStackDepth)
+            .low_u64() as usize,
+        prover_input,
+        stack: state.stack(),
+        details,
+    }
+}
+
 pub(crate) fn transition<F: Field>(state: &mut GenerationState<F>) -> anyhow::Result<()> {
     let checkpoint = state.checkpoint();
     let result = try_perform_instruction(state);
@@ -439,17 +514,41 @@ pub(crate) fn transition<F: Field>(state: &mut GenerationState<F>) -> anyhow::Re
         }
         Err(e) => {
             if state.registers.is_kernel {
-                let offset_name = KERNEL.offset_name(state.registers.program_counter);
-                bail!(
-                    "{:?} in kernel at pc={}, stack={:?}, memory={:?}",
-                    e,
-                    offset_name,
-                    state.stack(),
-                    state.memory.contexts[0].segments[Segment::KernelGeneral as usize].content,
+                log::debug!(
+                    "Kernel general memory: {:?}",
+                    state.memory.contexts[0].segments[Segment::KernelGeneral as usize].content
                 );
+                let details = missing_trie_node(state);
+                return Err(execution_error(state, e, details).into());
             }
             state.rollback(checkpoint);
             handle_error(state, e)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField as F;
+
+    use super::*;
+    use crate::generation::GenerationInputs;
+
+    #[test]
+    fn test_kernel_panic_context() {
+        let mut state = GenerationState::<F>::new(GenerationInputs::default(), &KERNEL.code)
+            .expect("Invalid generation inputs");
+        state.registers.program_counter = KERNEL.global_labels["panic"];
+
+        let err = transition(&mut state).expect_err("PANIC should fail");
+        let err = err
+            .downcast_ref::<ExecutionError>()
+            .expect("Expected an ExecutionError");
+        assert!(matches!(err.error, ProgramError::KernelPanic));
+        assert_eq!(err.program_counter, KERNEL.global_labels["panic"]);
+        assert_eq!(err.kernel_location.as_deref(), Some("panic"));
+        assert_eq!(err.txn_index, None);
+        assert_eq!(err.call_depth, 0);
+        assert_eq!(err.prover_input, None);
+    }
+}
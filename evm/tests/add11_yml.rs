@@ -15,6 +15,7 @@ use plonky2_evm::all_stark::AllStark;
 use plonky2_evm::config::StarkConfig;
 use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp};
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
+use plonky2_evm::hardfork::Hardfork;
 use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
@@ -89,6 +90,7 @@ fn add11_yml() -> anyhow::Result<()> {
         block_base_fee: 0xa.into(),
         block_gas_used: 0xa868u64.into(),
         block_bloom: [0.into(); 8],
+        hardfork: Hardfork::Shanghai,
     };
 
     let mut contract_code = HashMap::new();
@@ -10,16 +10,22 @@ use hex_literal::hex;
 use keccak_hash::keccak;
 use plonky2::field::goldilocks_field::GoldilocksField;
 use plonky2::plonk::config::KeccakGoldilocksConfig;
+use plonky2::util::log2_strict;
 use plonky2::util::timing::TimingTree;
-use plonky2_evm::all_stark::AllStark;
+use plonky2_evm::all_stark::{AllStark, Table};
 use plonky2_evm::config::StarkConfig;
 use plonky2_evm::cpu::kernel::opcodes::{get_opcode, get_push_opcode};
 use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp};
-use plonky2_evm::generation::{GenerationInputs, TrieInputs};
+use plonky2_evm::generation::trace_sizes::estimate_trace_sizes;
+use plonky2_evm::generation::{
+    generate_traces, generate_traces_with_tracer, GenerationInputs, TrieInputs,
+};
+use plonky2_evm::hardfork::Hardfork;
 use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
 use plonky2_evm::Node;
+use serde_json::Value;
 
 type F = GoldilocksField;
 const D: usize = 2;
@@ -33,7 +39,76 @@ fn test_basic_smart_contract() -> anyhow::Result<()> {
 
     let all_stark = AllStark::<F, D>::default();
     let config = StarkConfig::standard_fast_config();
+    let inputs = basic_smart_contract_inputs();
 
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+
+    verify_proof(&all_stark, proof, &config)
+}
+
+/// Test the EIP-3155 trace of the contract code executed by the transaction.
+#[test]
+fn test_basic_smart_contract_trace() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+    let inputs = basic_smart_contract_inputs();
+
+    let mut timing = TimingTree::new("generate traces", log::Level::Debug);
+    let (_, _, _, tracer) = generate_traces_with_tracer(&all_stark, inputs, &config, &mut timing)?;
+    let mut out = vec![];
+    tracer.write_txn_json_lines(0, &mut out)?;
+    let steps = String::from_utf8(out)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<Vec<Value>, _>>()?;
+
+    // The gas limit of the transaction is 22000, and its intrinsic gas is 21000 + 2 * 16.
+    let expected = [
+        (0, "PUSH1", "0x3c8", "0x3"),
+        (2, "PUSH1", "0x3c5", "0x3"),
+        (4, "ADD", "0x3c2", "0x3"),
+        (5, "STOP", "0x3bf", "0x0"),
+    ];
+    assert_eq!(steps.len(), expected.len());
+    for (step, (pc, op_name, gas, gas_cost)) in steps.iter().zip(expected) {
+        assert_eq!(step["pc"], pc);
+        assert_eq!(step["opName"], op_name);
+        assert_eq!(step["gas"], gas);
+        assert_eq!(step["gasCost"], gas_cost);
+        // The transaction's own context is at depth 1.
+        assert_eq!(step["depth"], 1);
+    }
+    Ok(())
+}
+
+/// Test that the estimated trace sizes match those of the generated traces.
+#[test]
+fn test_basic_smart_contract_trace_sizes() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+    let inputs = basic_smart_contract_inputs();
+
+    let mut timing = TimingTree::new("generate traces", log::Level::Debug);
+    let sizes = estimate_trace_sizes::<F, D>(inputs.clone(), &config, &mut timing)?;
+    let (tables, _, _) = generate_traces::<F, D>(&all_stark, inputs, &config, &mut timing)?;
+    for (i, trace) in tables.iter().enumerate() {
+        assert_eq!(sizes.degree_bits[i], log2_strict(trace[0].len()));
+    }
+    let memory_len = tables[Table::Memory as usize][0].len();
+    assert_eq!(
+        sizes.rows[Table::Memory as usize].next_power_of_two(),
+        memory_len
+    );
+    Ok(())
+}
+
+fn basic_smart_contract_inputs() -> GenerationInputs {
     let beneficiary = hex!("deadbeefdeadbeefdeadbeefdeadbeefdeadbeef");
     let sender = hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23");
     let to = hex!("a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0");
@@ -114,6 +189,7 @@ fn test_basic_smart_contract() -> anyhow::Result<()> {
         block_gaslimit: 0xff112233u32.into(),
         block_gas_used: gas_used.into(),
         block_bloom: [0.into(); 8],
+        hardfork: Hardfork::Shanghai,
         block_base_fee: 0xa.into(),
         block_random: Default::default(),
     };
@@ -182,7 +258,7 @@ fn test_basic_smart_contract() -> anyhow::Result<()> {
         transactions_root: transactions_trie.hash(),
         receipts_root: receipts_trie.hash(),
     };
-    let inputs = GenerationInputs {
+    GenerationInputs {
         signed_txns: vec![txn.to_vec()],
         tries: tries_before,
         trie_roots_after,
@@ -199,13 +275,7 @@ fn test_basic_smart_contract() -> anyhow::Result<()> {
             cur_hash: H256::default(),
         },
         addresses: vec![],
-    };
-
-    let mut timing = TimingTree::new("prove", log::Level::Debug);
-    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
-    timing.filter(Duration::from_millis(100)).print();
-
-    verify_proof(&all_stark, proof, &config)
+    }
 }
 
 fn eth_to_wei(eth: U256) -> U256 {
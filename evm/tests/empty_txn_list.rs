@@ -14,6 +14,7 @@ use plonky2::util::timing::TimingTree;
 use plonky2_evm::all_stark::AllStark;
 use plonky2_evm::config::StarkConfig;
 use plonky2_evm::fixed_recursive_verifier::AllRecursiveCircuits;
+use plonky2_evm::generation::trace_sizes::estimate_trace_sizes;
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
 use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
 use plonky2_evm::Node;
@@ -77,6 +78,12 @@ fn test_empty_txn_list() -> anyhow::Result<()> {
         &config,
     );
 
+    let trace_sizes =
+        estimate_trace_sizes::<F, D>(inputs.clone(), &config, &mut TimingTree::default())?;
+    assert!(trace_sizes
+        .unfit_tables(&all_circuits.degree_bits_ranges()?)
+        .is_empty());
+
     {
         let gate_serializer = DefaultGateSerializer;
         let generator_serializer = DefaultGeneratorSerializer {
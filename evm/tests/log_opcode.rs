@@ -20,6 +20,7 @@ use plonky2_evm::fixed_recursive_verifier::AllRecursiveCircuits;
 use plonky2_evm::generation::mpt::transaction_testing::{AddressOption, LegacyTransactionRlp};
 use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp, LogRlp};
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
+use plonky2_evm::hardfork::Hardfork;
 use plonky2_evm::proof::{BlockHashes, BlockMetadata, ExtraBlockData, PublicValues, TrieRoots};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
@@ -142,6 +143,7 @@ fn test_log_opcodes() -> anyhow::Result<()> {
         block_base_fee: 0xa.into(),
         block_gas_used: 0.into(),
         block_bloom: [0.into(); 8],
+        hardfork: Hardfork::Shanghai,
     };
 
     let mut contract_code = HashMap::new();
@@ -376,6 +378,7 @@ fn test_log_with_aggreg() -> anyhow::Result<()> {
             U256::from_dec_str("2722259584404615024560450425766186844160").unwrap(),
         ],
         block_random: Default::default(),
+        hardfork: Hardfork::Shanghai,
     };
 
     let beneficiary_account_after = AccountRlp {
@@ -819,6 +822,7 @@ fn test_two_txn() -> anyhow::Result<()> {
         block_base_fee: 0xa.into(),
         block_gas_used: 0.into(),
         block_bloom: [0.into(); 8],
+        hardfork: Hardfork::Shanghai,
     };
 
     let mut contract_code = HashMap::new();
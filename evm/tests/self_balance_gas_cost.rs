@@ -15,6 +15,7 @@ use plonky2_evm::all_stark::AllStark;
 use plonky2_evm::config::StarkConfig;
 use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp};
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
+use plonky2_evm::hardfork::Hardfork;
 use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
@@ -103,6 +104,7 @@ fn self_balance_gas_cost() -> anyhow::Result<()> {
         block_gaslimit: 0xff112233u32.into(),
         block_gas_used: gas_used.into(),
         block_bloom: [0.into(); 8],
+        hardfork: Hardfork::Shanghai,
         block_base_fee: 0xa.into(),
         block_random: Default::default(),
     };
@@ -15,7 +15,9 @@ use plonky2_evm::all_stark::AllStark;
 use plonky2_evm::config::StarkConfig;
 use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp};
 use plonky2_evm::generation::{GenerationInputs, TrieInputs};
+use plonky2_evm::hardfork::Hardfork;
 use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
+use plonky2_evm::proof_file::ProofFile;
 use plonky2_evm::prover::prove;
 use plonky2_evm::verifier::verify_proof;
 use plonky2_evm::Node;
@@ -31,7 +33,69 @@ fn test_simple_transfer() -> anyhow::Result<()> {
 
     let all_stark = AllStark::<F, D>::default();
     let config = StarkConfig::standard_fast_config();
+    let inputs = simple_transfer_inputs();
 
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+
+    // The proof still verifies after a roundtrip through either encoding of a proof file.
+    let proof_file = ProofFile::new(config.clone(), proof.clone());
+    let from_json = ProofFile::<F, C, D>::from_json(&proof_file.to_json()?)?;
+    assert_eq!(
+        from_json.verify(&all_stark, &config)?.trie_roots_after,
+        proof.public_values.trie_roots_after
+    );
+    ProofFile::<F, C, D>::from_bytes(&proof_file.to_bytes()?)?.verify(&all_stark, &config)?;
+
+    // A proof file claiming a weaker config than the expected one is rejected.
+    let mut weak_proof_file = proof_file;
+    weak_proof_file.config.fri_config.num_query_rounds = 1;
+    assert!(weak_proof_file.verify(&all_stark, &config).is_err());
+
+    verify_proof(&all_stark, proof, &config)
+}
+
+/// Test the same transfer with a memory budget so small that each table is proven alone, from a
+/// recomputed trace commitment.
+#[test]
+fn test_simple_transfer_low_memory() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig {
+        memory_budget: Some(1),
+        ..StarkConfig::standard_fast_config()
+    };
+    let inputs = simple_transfer_inputs();
+
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+
+    verify_proof(&all_stark, proof, &config)
+}
+
+/// Test the same transfer with each table processed in its own pool of two threads.
+#[test]
+fn test_simple_transfer_threads_per_table() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig {
+        threads_per_table: Some(2),
+        ..StarkConfig::standard_fast_config()
+    };
+    let inputs = simple_transfer_inputs();
+
+    let mut timing = TimingTree::new("prove", log::Level::Debug);
+    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
+    timing.filter(Duration::from_millis(100)).print();
+
+    verify_proof(&all_stark, proof, &config)
+}
+
+fn simple_transfer_inputs() -> GenerationInputs {
     let beneficiary = hex!("deadbeefdeadbeefdeadbeefdeadbeefdeadbeef");
     let sender = hex!("2c7536e3605d9c16a7a3d7b1898e529396a65c23");
     let to = hex!("a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0");
@@ -78,6 +142,7 @@ fn test_simple_transfer() -> anyhow::Result<()> {
         block_base_fee: 0xa.into(),
         block_gas_used: 21032.into(),
         block_bloom: [0.into(); 8],
+        hardfork: Hardfork::Shanghai,
     };
 
     let mut contract_code = HashMap::new();
@@ -137,7 +202,7 @@ fn test_simple_transfer() -> anyhow::Result<()> {
         transactions_root: transactions_trie.hash(),
         receipts_root: receipts_trie.hash(),
     };
-    let inputs = GenerationInputs {
+    GenerationInputs {
         signed_txns: vec![txn.to_vec()],
         tries: tries_before,
         trie_roots_after,
@@ -154,13 +219,7 @@ fn test_simple_transfer() -> anyhow::Result<()> {
             cur_hash: H256::default(),
         },
         addresses: vec![],
-    };
-
-    let mut timing = TimingTree::new("prove", log::Level::Debug);
-    let proof = prove::<F, C, D>(&all_stark, &config, inputs, &mut timing)?;
-    timing.filter(Duration::from_millis(100)).print();
-
-    verify_proof(&all_stark, proof, &config)
+    }
 }
 
 fn eth_to_wei(eth: U256) -> U256 {
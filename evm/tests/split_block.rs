@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use env_logger::{try_init_from_env, Env, DEFAULT_FILTER_ENV};
+use eth_trie_utils::nibbles::Nibbles;
+use eth_trie_utils::partial_trie::{HashedPartialTrie, PartialTrie};
+use ethereum_types::{Address, BigEndianHash, H256};
+use hex_literal::hex;
+use keccak_hash::keccak;
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::util::timing::TimingTree;
+use plonky2_evm::all_stark::AllStark;
+use plonky2_evm::config::StarkConfig;
+use plonky2_evm::generation::mpt::{AccountRlp, LegacyReceiptRlp};
+use plonky2_evm::generation::split::{split_block, ExecutedBlock};
+use plonky2_evm::generation::{generate_traces, GenerationInputs, TrieInputs};
+use plonky2_evm::hardfork::Hardfork;
+use plonky2_evm::proof::{BlockHashes, BlockMetadata, TrieRoots};
+use plonky2_evm::witness::errors::{ExecutionError, ProgramError, ProverInputError};
+use plonky2_evm::Node;
+
+type F = GoldilocksField;
+const D: usize = 2;
+
+/// Executes a block of two transfers, and checks that the inputs built for each transaction range
+/// chain together and are accepted by trace generation.
+#[test]
+#[ignore] // Too slow to run on CI.
+fn test_split_block() -> anyhow::Result<()> {
+    init_logger();
+
+    let all_stark = AllStark::<F, D>::default();
+    let config = StarkConfig::standard_fast_config();
+
+    let beneficiary = hex!("2adc25665018aa1fe0e6bc666dac8fc2697ff9ba");
+    let sender = hex!("af1276cbb260bb13deddb4209ae99ae6e497f446");
+    // Private key: DCDFF53B4F013DBCDC717F89FE3BF4D8B10512AAE282B48E01D7530470382701
+    let to = hex!("095e7baea6a6c7c4c2dfeb977efac326af552d87");
+
+    let beneficiary_state_key = keccak(beneficiary);
+    let sender_state_key = keccak(sender);
+    let to_hashed = keccak(to);
+
+    let beneficiary_nibbles = Nibbles::from_bytes_be(beneficiary_state_key.as_bytes()).unwrap();
+    let sender_nibbles = Nibbles::from_bytes_be(sender_state_key.as_bytes()).unwrap();
+    let to_nibbles = Nibbles::from_bytes_be(to_hashed.as_bytes()).unwrap();
+
+    let beneficiary_account = AccountRlp {
+        nonce: 1.into(),
+        ..AccountRlp::default()
+    };
+    let sender_balance_before = 50000000000000000u64;
+    let sender_account_before = AccountRlp {
+        balance: sender_balance_before.into(),
+        ..AccountRlp::default()
+    };
+    let to_account_before = AccountRlp::default();
+
+    let mut state_trie_before = HashedPartialTrie::from(Node::Empty);
+    state_trie_before.insert(
+        beneficiary_nibbles,
+        rlp::encode(&beneficiary_account).to_vec(),
+    );
+    state_trie_before.insert(sender_nibbles, rlp::encode(&sender_account_before).to_vec());
+    state_trie_before.insert(to_nibbles, rlp::encode(&to_account_before).to_vec());
+
+    let tries_before = TrieInputs {
+        state_trie: state_trie_before,
+        transactions_trie: Node::Empty.into(),
+        receipts_trie: Node::Empty.into(),
+        storage_tries: vec![(to_hashed, Node::Empty.into())],
+    };
+
+    let gas_price = 10;
+    let txn_value = 0x11c37937e08000u64;
+    let txn_0 = hex!("f866800a82520894095e7baea6a6c7c4c2dfeb977efac326af552d878711c37937e080008026a01fcd0ce88ac7600698a771f206df24b70e67981b6f107bd7c1c24ea94f113bcba00d87cc5c7afc2988e4ff200b5a0c7016b0d5498bbc692065ca983fcbbfe02555");
+    let txn_1 = hex!("f866010a82520894095e7baea6a6c7c4c2dfeb977efac326af552d878711c37937e080008026a0d8123f5f537bd3a67283f67eb136f7accdfc4ef012cfbfd3fb1d0ac7fd01b96fa004666d9feef90a1eb568570374dd19977d4da231b289d769e6f95105c06fd672");
+
+    let block_metadata = BlockMetadata {
+        block_beneficiary: Address::from(beneficiary),
+        block_timestamp: 0x03e8.into(),
+        block_number: 1.into(),
+        block_difficulty: 0x020000.into(),
+        block_random: H256::from_uint(&0x020000.into()),
+        block_gaslimit: 0xffffffffu32.into(),
+        block_chain_id: 1.into(),
+        block_base_fee: 0xa.into(),
+        block_gas_used: 42000u64.into(),
+        block_bloom: [0.into(); 8],
+        hardfork: Hardfork::Shanghai,
+    };
+
+    let mut contract_code = HashMap::new();
+    contract_code.insert(keccak(vec![]), vec![]);
+
+    // The expected tries after both transfers.
+    let sender_account_after = AccountRlp {
+        balance: (sender_balance_before - gas_price * 21000 * 2 - txn_value * 2).into(),
+        nonce: 2.into(),
+        ..AccountRlp::default()
+    };
+    let to_account_after = AccountRlp {
+        balance: (2 * txn_value).into(),
+        ..AccountRlp::default()
+    };
+    let mut expected_state_trie_after = HashedPartialTrie::from(Node::Empty);
+    expected_state_trie_after.insert(
+        beneficiary_nibbles,
+        rlp::encode(&beneficiary_account).to_vec(),
+    );
+    expected_state_trie_after.insert(sender_nibbles, rlp::encode(&sender_account_after).to_vec());
+    expected_state_trie_after.insert(to_nibbles, rlp::encode(&to_account_after).to_vec());
+
+    let mut receipts_trie = HashedPartialTrie::from(Node::Empty);
+    for (key, cum_gas_used) in [("0x80", 21000u64), ("0x01", 42000u64)] {
+        let receipt = LegacyReceiptRlp {
+            status: true,
+            cum_gas_used: cum_gas_used.into(),
+            bloom: [0x00; 256].to_vec().into(),
+            logs: vec![],
+        };
+        receipts_trie.insert(
+            Nibbles::from_str(key).unwrap(),
+            rlp::encode(&receipt).to_vec(),
+        );
+    }
+
+    let mut transactions_trie = HashedPartialTrie::from(Node::Empty);
+    transactions_trie.insert(Nibbles::from_str("0x80").unwrap(), txn_0.to_vec());
+    transactions_trie.insert(Nibbles::from_str("0x01").unwrap(), txn_1.to_vec());
+
+    let expected_trie_roots_after = TrieRoots {
+        state_root: expected_state_trie_after.hash(),
+        transactions_root: transactions_trie.hash(),
+        receipts_root: receipts_trie.hash(),
+    };
+
+    let inputs = GenerationInputs {
+        signed_txns: vec![txn_0.to_vec(), txn_1.to_vec()],
+        tries: tries_before,
+        trie_roots_after: TrieRoots::default(),
+        contract_code,
+        genesis_state_trie_root: HashedPartialTrie::from(Node::Empty).hash(),
+        block_metadata,
+        txn_number_before: 0.into(),
+        gas_used_before: 0.into(),
+        gas_used_after: 0.into(),
+        block_bloom_before: [0.into(); 8],
+        block_bloom_after: [0.into(); 8],
+        block_hashes: BlockHashes {
+            prev_hashes: vec![H256::default(); 256],
+            cur_hash: H256::default(),
+        },
+        addresses: vec![],
+    };
+
+    let block = ExecutedBlock::new::<F, D>(inputs.clone())?;
+    assert_eq!(block.num_txns(), 2);
+    assert_eq!(block.trie_roots_after(), expected_trie_roots_after);
+
+    // Split the block into one range per transaction.
+    let first = block.txn_range_inputs(0..1);
+    let second = block.txn_range_inputs(1..2);
+    assert_eq!(first.gas_used_after, 21000u64.into());
+    assert_eq!(second.txn_number_before, 1.into());
+    assert_eq!(second.gas_used_before, first.gas_used_after);
+    assert_eq!(second.block_bloom_before, first.block_bloom_after);
+    assert_eq!(
+        second.tries.state_trie.hash(),
+        first.trie_roots_after.state_root
+    );
+    assert_eq!(second.trie_roots_after, expected_trie_roots_after);
+
+    // Trace generation checks the trie roots, gas used and bloom filter of each range.
+    for range_inputs in [first, second] {
+        let mut timing = TimingTree::new("generate traces", log::Level::Debug);
+        generate_traces(&all_stark, range_inputs, &config, &mut timing)?;
+    }
+
+    // With large enough circuits, the whole block fits in a single range.
+    let mut timing = TimingTree::new("split block", log::Level::Debug);
+    let degree_bits_ranges = core::array::from_fn(|_| 0..32);
+    let segments = split_block::<F, D>(inputs, &degree_bits_ranges, &config, &mut timing)?;
+    assert_eq!(segments.len(), 1);
+    assert_eq!(segments[0].signed_txns.len(), 2);
+    assert_eq!(segments[0].trie_roots_after, expected_trie_roots_after);
+    assert_eq!(segments[0].gas_used_after, 42000u64.into());
+
+    Ok(())
+}
+
+/// Executes a block whose second transaction calls a contract with missing code, and checks that
+/// the first transaction is kept.
+#[test]
+fn test_execute_prefix() {
+    init_logger();
+
+    let beneficiary = hex!("2adc25665018aa1fe0e6bc666dac8fc2697ff9ba");
+    let sender = hex!("af1276cbb260bb13deddb4209ae99ae6e497f446");
+    // Private key: DCDFF53B4F013DBCDC717F89FE3BF4D8B10512AAE282B48E01D7530470382701
+    let to = hex!("095e7baea6a6c7c4c2dfeb977efac326af552d87");
+    let contract = hex!("1111111111111111111111111111111111111111");
+
+    let beneficiary_account = AccountRlp {
+        nonce: 1.into(),
+        ..AccountRlp::default()
+    };
+    let sender_account_before = AccountRlp {
+        balance: 50000000000000000u64.into(),
+        ..AccountRlp::default()
+    };
+    let contract_account = AccountRlp {
+        code_hash: keccak([0x00]),
+        ..AccountRlp::default()
+    };
+
+    let mut state_trie_before = HashedPartialTrie::from(Node::Empty);
+    for (address, account) in [
+        (beneficiary, beneficiary_account),
+        (sender, sender_account_before),
+        (to, AccountRlp::default()),
+        (contract, contract_account),
+    ] {
+        state_trie_before.insert(
+            Nibbles::from_h256_be(keccak(address)),
+            rlp::encode(&account).to_vec(),
+        );
+    }
+
+    // A transfer to `to`, then a call to `contract`, whose code isn't provided.
+    let txn_0 = hex!("f866800a82520894095e7baea6a6c7c4c2dfeb977efac326af552d878711c37937e080008026a01fcd0ce88ac7600698a771f206df24b70e67981b6f107bd7c1c24ea94f113bcba00d87cc5c7afc2988e4ff200b5a0c7016b0d5498bbc692065ca983fcbbfe02555");
+    let txn_1 = hex!("f860010a830186a0941111111111111111111111111111111111111111018026a0c4d3d08e7e08127a7e20937d22227bdf05c6ebef756a99d2e6116a25233a7d27a0152391ced1686e8f8e594b4b0b97cf43b3da04eb4290e5030a31ece6567451f9");
+
+    let mut contract_code = HashMap::new();
+    contract_code.insert(keccak(vec![]), vec![]);
+
+    let inputs = GenerationInputs {
+        signed_txns: vec![txn_0.to_vec(), txn_1.to_vec()],
+        tries: TrieInputs {
+            state_trie: state_trie_before,
+            transactions_trie: Node::Empty.into(),
+            receipts_trie: Node::Empty.into(),
+            storage_tries: vec![
+                (keccak(to), Node::Empty.into()),
+                (keccak(contract), Node::Empty.into()),
+            ],
+        },
+        contract_code,
+        genesis_state_trie_root: HashedPartialTrie::from(Node::Empty).hash(),
+        block_metadata: BlockMetadata {
+            block_beneficiary: Address::from(beneficiary),
+            block_timestamp: 0x03e8.into(),
+            block_number: 1.into(),
+            block_difficulty: 0x020000.into(),
+            block_random: H256::from_uint(&0x020000.into()),
+            block_gaslimit: 0xffffffffu32.into(),
+            block_chain_id: 1.into(),
+            block_base_fee: 0xa.into(),
+            block_gas_used: 21000u64.into(),
+            block_bloom: [0.into(); 8],
+            hardfork: Hardfork::Shanghai,
+        },
+        block_hashes: BlockHashes {
+            prev_hashes: vec![H256::default(); 256],
+            cur_hash: H256::default(),
+        },
+        ..GenerationInputs::default()
+    };
+
+    let (prefix, failure) = ExecutedBlock::execute_prefix::<F, D>(inputs);
+    let failure = failure.expect("The call to the contract should fail");
+    assert_eq!(failure.txn_index, 1);
+    let error = failure
+        .error
+        .downcast_ref::<ExecutionError>()
+        .expect("Expected an ExecutionError");
+    assert!(matches!(
+        error.error,
+        ProgramError::ProverInputError(ProverInputError::CodeHashNotFound)
+    ));
+    assert_eq!(error.prover_input.as_deref(), Some("account_code::length"));
+
+    assert_eq!(prefix.num_txns(), 1);
+    assert_eq!(prefix.gas_used_after(), 21000u64.into());
+    let prefix_inputs = prefix.txn_range_inputs(0..1);
+    assert_eq!(prefix_inputs.signed_txns, vec![txn_0.to_vec()]);
+    assert_eq!(prefix_inputs.trie_roots_after, prefix.trie_roots_after());
+}
+
+fn init_logger() {
+    let _ = try_init_from_env(Env::default().filter_or(DEFAULT_FILTER_ENV, "info"));
+}
@@ -0,0 +1,120 @@
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::plonk::config::KeccakGoldilocksConfig;
+use plonky2_evm::state_tests::{parse_state_tests, Outcome};
+
+type F = GoldilocksField;
+const D: usize = 2;
+type C = KeccakGoldilocksConfig;
+
+/// The post-state root of the `add11` state test, as given in its fixture.
+const ADD11_STATE_ROOT: &str = "0xe8010ce590f401c9d61fef8ab05bea9bcec24281b795e5868809bc4e515aa530";
+/// The hash of an empty list of logs, as given in the fixture.
+const EMPTY_LOGS_HASH: &str = "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347";
+
+/// The `add11` state test from https://github.com/ethereum/tests, with the post-states of
+/// unsupported forks and of a fork without `txbytes` added.
+fn add11_json(state_root: &str) -> String {
+    let txbytes = "0xf863800a83061a8094095e7baea6a6c7c4c2dfeb977efac326af552d87830186a0801ba0ffb600e63115a7362e7811894a91d8ba4330e526f22121c994c4692035dfdfd5a06198379fcac8de3dbfac48b165df4bf88e2088f294b61efb9a65fe2281c76e16";
+    format!(
+        r#"{{
+  "add11": {{
+    "env": {{
+      "currentCoinbase": "0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba",
+      "currentDifficulty": "0x020000",
+      "currentGasLimit": "0xff112233",
+      "currentNumber": "0x01",
+      "currentTimestamp": "0x03e8",
+      "currentBaseFee": "0x0a",
+      "currentRandom": "0x0000000000000000000000000000000000000000000000000000000000020000"
+    }},
+    "pre": {{
+      "0x095e7baea6a6c7c4c2dfeb977efac326af552d87": {{
+        "balance": "0x0de0b6b3a7640000",
+        "code": "0x600160010160005500",
+        "nonce": "0x00",
+        "storage": {{}}
+      }},
+      "0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba": {{
+        "balance": "0x00",
+        "code": "0x",
+        "nonce": "0x01",
+        "storage": {{}}
+      }},
+      "0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b": {{
+        "balance": "0x0de0b6b3a7640000",
+        "code": "0x",
+        "nonce": "0x00",
+        "storage": {{}}
+      }}
+    }},
+    "post": {{
+      "Cancun": [
+        {{
+          "hash": "{state_root}",
+          "indexes": {{ "data": 0, "gas": 0, "value": 0 }},
+          "logs": "{EMPTY_LOGS_HASH}",
+          "txbytes": "{txbytes}"
+        }}
+      ],
+      "Istanbul": [
+        {{
+          "hash": "{state_root}",
+          "indexes": {{ "data": 0, "gas": 0, "value": 0 }},
+          "logs": "{EMPTY_LOGS_HASH}",
+          "txbytes": "{txbytes}"
+        }}
+      ],
+      "London": [
+        {{
+          "hash": "{state_root}",
+          "indexes": {{ "data": 0, "gas": 0, "value": 0 }},
+          "logs": "{EMPTY_LOGS_HASH}"
+        }}
+      ],
+      "Shanghai": [
+        {{
+          "hash": "{state_root}",
+          "indexes": {{ "data": 0, "gas": 0, "value": 0 }},
+          "logs": "{EMPTY_LOGS_HASH}",
+          "txbytes": "{txbytes}"
+        }}
+      ]
+    }}
+  }}
+}}"#
+    )
+}
+
+#[test]
+fn test_state_test_runner() -> anyhow::Result<()> {
+    let cases = parse_state_tests(&add11_json(ADD11_STATE_ROOT))?;
+    let outcomes = cases
+        .iter()
+        .map(|case| (case.fork.as_str(), case.run::<F, C, D>(None)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        outcomes,
+        vec![
+            (
+                "Cancun",
+                Outcome::Skipped("Unsupported fork Cancun".to_string())
+            ),
+            (
+                "Istanbul",
+                Outcome::Skipped("Unsupported fork Istanbul".to_string())
+            ),
+            ("London", Outcome::Skipped("Missing txbytes".to_string())),
+            ("Shanghai", Outcome::Passed),
+        ]
+    );
+
+    let wrong_state_root = format!("{:#066x}", 0);
+    let cases = parse_state_tests(&add11_json(&wrong_state_root))?;
+    let outcome = cases[3].run::<F, C, D>(None);
+    assert!(
+        matches!(&outcome, Outcome::Failed(reason) if reason.starts_with("State root")),
+        "{outcome:?}"
+    );
+
+    Ok(())
+}
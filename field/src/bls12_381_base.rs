@@ -0,0 +1,49 @@
+use crate::montgomery::{MontgomeryField, MontgomeryParameters};
+
+/// Parameters of [`Bls12381Base`].
+#[derive(Copy, Clone, Debug)]
+pub struct Bls12381BaseParameters;
+
+impl MontgomeryParameters<6> for Bls12381BaseParameters {
+    const MODULUS: [u64; 6] = [
+        0xB9FEFFFFFFFFAAAB,
+        0x1EABFFFEB153FFFF,
+        0x6730D2A0F6B0F624,
+        0x64774B84F38512BF,
+        0x4B1BA7B6434BACD7,
+        0x1A0111EA397FE69A,
+    ];
+
+    const TWO_ADICITY: usize = 1;
+
+    // The usual generator, e.g. in arkworks. It is a quadratic non-residue, and
+    // `2^((p - 1) / q) != 1` for each of the known prime factors `q` of `p - 1`.
+    const MULTIPLICATIVE_GROUP_GENERATOR: [u64; 6] = [2, 0, 0, 0, 0, 0];
+
+    // `g_2 = g^((p - 1) / 2)`, i.e. -1
+    const POWER_OF_TWO_GENERATOR: [u64; 6] = [
+        0xB9FEFFFFFFFFAAAA,
+        0x1EABFFFEB153FFFF,
+        0x6730D2A0F6B0F624,
+        0x64774B84F38512BF,
+        0x4B1BA7B6434BACD7,
+        0x1A0111EA397FE69A,
+    ];
+}
+
+/// The base field of the BLS12-381 elliptic curve.
+///
+/// Its order is
+/// ```ignore
+/// P = 0x1A0111EA397FE69A 4B1BA7B6434BACD7 64774B84F38512BF
+///       6730D2A0F6B0F624 1EABFFFEB153FFFF B9FEFFFFFFFFAAAB
+/// ```
+pub type Bls12381Base = MontgomeryField<Bls12381BaseParameters, 6>;
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_field_arithmetic, test_prime_field_arithmetic_biguint};
+
+    test_field_arithmetic!(crate::bls12_381_base::Bls12381Base);
+    test_prime_field_arithmetic_biguint!(crate::bls12_381_base::Bls12381Base);
+}
@@ -0,0 +1,44 @@
+use crate::montgomery::{MontgomeryField, MontgomeryParameters};
+
+/// Parameters of [`Bn254Base`].
+#[derive(Copy, Clone, Debug)]
+pub struct Bn254BaseParameters;
+
+impl MontgomeryParameters<4> for Bn254BaseParameters {
+    const MODULUS: [u64; 4] = [
+        0x3C208C16D87CFD47,
+        0x97816A916871CA8D,
+        0xB85045B68181585D,
+        0x30644E72E131A029,
+    ];
+
+    const TWO_ADICITY: usize = 1;
+
+    // Sage: `g = GF(p).multiplicative_generator()`
+    const MULTIPLICATIVE_GROUP_GENERATOR: [u64; 4] = [3, 0, 0, 0];
+
+    // Sage: `g_2 = g^((p - 1) / 2)`, i.e. -1
+    const POWER_OF_TWO_GENERATOR: [u64; 4] = [
+        0x3C208C16D87CFD46,
+        0x97816A916871CA8D,
+        0xB85045B68181585D,
+        0x30644E72E131A029,
+    ];
+}
+
+/// The base field of the BN254 elliptic curve.
+///
+/// Its order is
+/// ```ignore
+/// P = 0x30644E72E131A029B85045B68181585D97816A916871CA8D3C208C16D87CFD47
+///   = 21888242871839275222246405745257275088696311157297823662689037894645226208583
+/// ```
+pub type Bn254Base = MontgomeryField<Bn254BaseParameters, 4>;
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_field_arithmetic, test_prime_field_arithmetic_biguint};
+
+    test_field_arithmetic!(crate::bn254_base::Bn254Base);
+    test_prime_field_arithmetic_biguint!(crate::bn254_base::Bn254Base);
+}
@@ -0,0 +1,45 @@
+use crate::montgomery::{MontgomeryField, MontgomeryParameters};
+
+/// Parameters of [`Bn254Scalar`].
+#[derive(Copy, Clone, Debug)]
+pub struct Bn254ScalarParameters;
+
+impl MontgomeryParameters<4> for Bn254ScalarParameters {
+    const MODULUS: [u64; 4] = [
+        0x43E1F593F0000001,
+        0x2833E84879B97091,
+        0xB85045B68181585D,
+        0x30644E72E131A029,
+    ];
+
+    const TWO_ADICITY: usize = 28;
+
+    // Sage: `g = GF(p).multiplicative_generator()`
+    const MULTIPLICATIVE_GROUP_GENERATOR: [u64; 4] = [5, 0, 0, 0];
+
+    // Sage: `g_2 = power_mod(g, (p - 1) // 2^28), p)`
+    // 19103219067921713944291392827692070036145651957329286315305642004821462161904
+    const POWER_OF_TWO_GENERATOR: [u64; 4] = [
+        0x9BD61B6E725B19F0,
+        0x402D111E41112ED4,
+        0x00E0A7EB8EF62ABC,
+        0x2A3C09F0A58A7E85,
+    ];
+}
+
+/// The scalar field of the BN254 elliptic curve.
+///
+/// Its order is
+/// ```ignore
+/// P = 0x30644E72E131A029B85045B68181585D2833E84879B9709143E1F593F0000001
+///   = 21888242871839275222246405745257275088548364400416034343698204186575808495617
+/// ```
+pub type Bn254Scalar = MontgomeryField<Bn254ScalarParameters, 4>;
+
+#[cfg(test)]
+mod tests {
+    use crate::{test_field_arithmetic, test_prime_field_arithmetic_biguint};
+
+    test_field_arithmetic!(crate::bn254_scalar::Bn254Scalar);
+    test_prime_field_arithmetic_biguint!(crate::bn254_scalar::Bn254Scalar);
+}
@@ -0,0 +1,87 @@
+use crate::montgomery::{MontgomeryField, MontgomeryParameters};
+
+/// Parameters of [`EcGFp5Scalar`].
+#[derive(Copy, Clone, Debug)]
+pub struct EcGFp5ScalarParameters;
+
+impl MontgomeryParameters<5> for EcGFp5ScalarParameters {
+    const MODULUS: [u64; 5] = [
+        0xE80FD996948BFFE1,
+        0xE8885C39D724A09C,
+        0x7FFFFFE6CFB80639,
+        0x7FFFFFF100000016,
+        0x7FFFFFFD80000007,
+    ];
+
+    const TWO_ADICITY: usize = 5;
+
+    // `p - 1 = 2^5 * 5 * 163 * 769 * 1059871 * q_1 * q_2`, where `q_1` and `q_2` are primes of
+    // 108 and 168 bits, listed in the tests below. 6 is the smallest element `g` such that
+    // `g^((p - 1) / q) != 1` for each prime factor `q`, so it generates the whole group.
+    const MULTIPLICATIVE_GROUP_GENERATOR: [u64; 5] = [6, 0, 0, 0, 0];
+
+    // Sage: `g_2 = power_mod(g, (p - 1) // 2^5), p)`
+    const POWER_OF_TWO_GENERATOR: [u64; 5] = [
+        0xCC13C747343470DD,
+        0xC09E9EF2B5CC8610,
+        0xBE95D8B7B2143AF3,
+        0xA774D847A1C95ECC,
+        0x6037242B8FA79C31,
+    ];
+}
+
+/// The scalar field of the ecGFp5 elliptic curve, i.e. the order of its prime-order subgroup.
+///
+/// Its order is
+/// ```ignore
+/// P = 0x7FFFFFFD800000077FFFFFF1000000167FFFFFE6CFB80639E8885C39D724A09CE80FD996948BFFE1
+///   = 1067993516717146951041484916571792702745057740581727230159139685185762082554198619328292418486241
+/// ```
+pub type EcGFp5Scalar = MontgomeryField<EcGFp5ScalarParameters, 5>;
+
+#[cfg(test)]
+mod tests {
+    use num::bigint::BigUint;
+
+    use crate::ecgfp5_scalar::EcGFp5Scalar;
+    use crate::types::Field;
+    use crate::{test_field_arithmetic, test_prime_field_arithmetic_biguint};
+
+    /// The prime factors of `p - 1`.
+    const PRIME_FACTORS_OF_ORDER_MINUS_ONE: [&str; 7] = [
+        "2",
+        "5",
+        "163",
+        "769",
+        "1059871",
+        "253243826720162431254857814100127",
+        "198400523053184002814403536918162724916343842520561",
+    ];
+
+    test_field_arithmetic!(crate::ecgfp5_scalar::EcGFp5Scalar);
+    test_prime_field_arithmetic_biguint!(crate::ecgfp5_scalar::EcGFp5Scalar);
+
+    #[test]
+    fn multiplicative_group_generator() {
+        let order_minus_one = EcGFp5Scalar::order() - 1u32;
+        let factors = PRIME_FACTORS_OF_ORDER_MINUS_ONE.map(|q| q.parse::<BigUint>().unwrap());
+
+        // The factors are complete, up to the multiplicity of 2.
+        let mut rest = order_minus_one.clone();
+        for q in &factors {
+            while (&rest % q) == BigUint::from(0u32) {
+                rest /= q;
+            }
+        }
+        assert_eq!(rest, BigUint::from(1u32));
+        assert_eq!(
+            order_minus_one.trailing_zeros(),
+            Some(EcGFp5Scalar::TWO_ADICITY as u64)
+        );
+
+        let g = EcGFp5Scalar::MULTIPLICATIVE_GROUP_GENERATOR;
+        for q in &factors {
+            assert_ne!(g.exp_biguint(&(&order_minus_one / q)), EcGFp5Scalar::ONE);
+        }
+    }
+}
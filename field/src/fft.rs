@@ -1,7 +1,9 @@
 use alloc::vec::Vec;
 use core::cmp::{max, min};
+use core::slice;
 
-use plonky2_util::{log2_strict, reverse_index_bits_in_place};
+use plonky2_maybe_rayon::*;
+use plonky2_util::{log2_strict, reverse_index_bits_in_place, transpose_in_place_square_matrix};
 use unroll::unroll_for_loops;
 
 use crate::packable::Packable;
@@ -32,20 +34,72 @@ pub fn fft_root_table<F: Field>(n: usize) -> FftRootTable<F> {
     root_table
 }
 
+/// Inputs of at least this size are split with [`fft_four_step`]; smaller ones fit in cache, where
+/// [`fft_classic`] is faster.
+const FOUR_STEP_MIN_LG_N: usize = 16;
+
 #[inline]
 fn fft_dispatch<F: Field>(
     input: &mut [F],
     zero_factor: Option<usize>,
     root_table: Option<&FftRootTable<F>>,
+) {
+    fft_in_place(input, zero_factor, root_table, false, false);
+}
+
+/// Computes the FFT of `values` in place.
+///
+/// `input_bit_reversed` and `output_bit_reversed` indicate whether `values` is in bit-reversed
+/// order before and after the transform, respectively. As in [`fft_with_options`], the
+/// `zero_factor` refers to the coefficients in their natural order.
+pub fn fft_in_place<F: Field>(
+    values: &mut [F],
+    zero_factor: Option<usize>,
+    root_table: Option<&FftRootTable<F>>,
+    input_bit_reversed: bool,
+    output_bit_reversed: bool,
 ) {
     let computed_root_table = if root_table.is_some() {
         None
     } else {
-        Some(fft_root_table(input.len()))
+        Some(fft_root_table(values.len()))
     };
     let used_root_table = root_table.or(computed_root_table.as_ref()).unwrap();
 
-    fft_classic(input, zero_factor.unwrap_or(0), used_root_table);
+    fft_in_place_with_table(
+        values,
+        zero_factor.unwrap_or(0),
+        used_root_table,
+        input_bit_reversed,
+        output_bit_reversed,
+    );
+}
+
+/// Same as [`fft_in_place`], but with a mandatory root table, which may be the prefix of the root
+/// table of a larger FFT.
+pub(crate) fn fft_in_place_with_table<F: Field>(
+    values: &mut [F],
+    r: usize,
+    root_table: &[Vec<F>],
+    input_bit_reversed: bool,
+    output_bit_reversed: bool,
+) {
+    if log2_strict(values.len()) < FOUR_STEP_MIN_LG_N {
+        // The classic FFT starts by bit-reversing its input, so we can skip that step.
+        if !input_bit_reversed {
+            reverse_index_bits_in_place(values);
+        }
+        fft_classic_bit_reversed(values, r, root_table);
+    } else {
+        if input_bit_reversed {
+            reverse_index_bits_in_place(values);
+        }
+        fft_four_step(values, r, root_table);
+    }
+
+    if output_bit_reversed {
+        reverse_index_bits_in_place(values);
+    }
 }
 
 #[inline]
@@ -100,7 +154,7 @@ fn fft_classic_simd<P: PackedField>(
     values: &mut [P::Scalar],
     r: usize,
     lg_n: usize,
-    root_table: &FftRootTable<P::Scalar>,
+    root_table: &[Vec<P::Scalar>],
 ) {
     let lg_packed_width = log2_strict(P::WIDTH); // 0 when P is a scalar.
     let packed_values = P::pack_slice_mut(values);
@@ -166,9 +220,13 @@ fn fft_classic_simd<P: PackedField>(
 /// The parameter r signifies that the first 1/2^r of the entries of
 /// input may be non-zero, but the last 1 - 1/2^r entries are
 /// definitely zero.
-pub(crate) fn fft_classic<F: Field>(values: &mut [F], r: usize, root_table: &FftRootTable<F>) {
+pub(crate) fn fft_classic<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
     reverse_index_bits_in_place(values);
+    fft_classic_bit_reversed(values, r, root_table);
+}
 
+/// Same as [`fft_classic`], but with the input already in bit-reversed order.
+fn fft_classic_bit_reversed<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
     let n = values.len();
     let lg_n = log2_strict(n);
 
@@ -205,16 +263,147 @@ pub(crate) fn fft_classic<F: Field>(values: &mut [F], r: usize, root_table: &Fft
     }
 }
 
+/// Natural-order FFT of `values`, for use on the rows and columns of [`fft_four_step`].
+fn fft_natural<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
+    if log2_strict(values.len()) < FOUR_STEP_MIN_LG_N {
+        fft_classic(values, r, root_table);
+    } else {
+        fft_four_step(values, r, root_table);
+    }
+}
+
+/// Cache-friendly FFT based on Bailey's four-step algorithm, ported from the Python prototype in
+/// `projects/cache-friendly-fft`. Both input and output are in natural order.
+///
+/// We view the `n = rows * cols` values as a `rows x cols` matrix in row-major order. We then
+///  1. transform each column, then multiply its `i`-th entry by `g^(i * j)`, where `j` is the
+///     column index and `g` generates the order-`n` subgroup,
+///  2. transform each row,
+///  3. transpose the matrix.
+///
+/// Columns are made contiguous by transposing before and after step 1. When `lg_n` is even the
+/// matrix is square. Otherwise `cols = 2 * rows`, so we transpose it as a square matrix of pairs,
+/// and deinterleave each row of the result to separate the two columns (or rows) it holds.
+///
+/// The sub-transforms are independent, so each step runs them in parallel.
+fn fft_four_step<F: Field>(values: &mut [F], r: usize, root_table: &[Vec<F>]) {
+    let n = values.len();
+    let lg_n = log2_strict(n);
+    if root_table.len() != lg_n {
+        panic!(
+            "Expected root table of length {}, but it was {}.",
+            lg_n,
+            root_table.len()
+        );
+    }
+
+    let lg_rows = lg_n / 2;
+    let lg_pair = lg_n % 2;
+    let rows = 1 << lg_rows;
+    let cols = rows << lg_pair;
+    // Powers of the generator of the order-`n` subgroup; we only need the first `cols` of them.
+    let omegas = &root_table[lg_n - 1];
+    // Trailing zero coefficients are trailing zero rows, i.e. trailing zeros of every column.
+    let column_r = min(r, lg_rows);
+
+    transpose_square_blocks(values, lg_rows, lg_pair);
+    values
+        .par_chunks_mut(cols)
+        .enumerate()
+        .for_each(|(i, chunk)| {
+            if lg_pair == 1 {
+                deinterleave(chunk);
+            }
+            for (k, column) in chunk.chunks_exact_mut(rows).enumerate() {
+                fft_natural(column, column_r, &root_table[..lg_rows]);
+                scale_by_powers(column, omegas[(i << lg_pair) + k]);
+            }
+            if lg_pair == 1 {
+                interleave(chunk);
+            }
+        });
+    transpose_square_blocks(values, lg_rows, lg_pair);
+
+    let lg_cols = lg_rows + lg_pair;
+    values
+        .par_chunks_mut(cols)
+        .for_each(|row| fft_natural(row, 0, &root_table[..lg_cols]));
+
+    transpose_square_blocks(values, lg_rows, lg_pair);
+    if lg_pair == 1 {
+        values.par_chunks_mut(cols).for_each(deinterleave);
+    }
+}
+
+/// Transposes in place the `1 << lg_size` by `1 << lg_size` square matrix whose entries are blocks
+/// of `1 << lg_block` consecutive values, where `lg_block` is either 0 or 1.
+fn transpose_square_blocks<F: Field>(values: &mut [F], lg_size: usize, lg_block: usize) {
+    if lg_block == 0 {
+        transpose_in_place_square_matrix(values, lg_size);
+    } else {
+        debug_assert_eq!(lg_block, 1);
+        // SAFETY: `[F; 2]` has the same alignment as `F`, and twice its size.
+        let pairs = unsafe {
+            slice::from_raw_parts_mut(values.as_mut_ptr().cast::<[F; 2]>(), values.len() / 2)
+        };
+        transpose_in_place_square_matrix(pairs, lg_size);
+    }
+}
+
+/// Rearranges `[a_0, b_0, a_1, b_1, ...]` into `[a_0, a_1, ..., b_0, b_1, ...]`.
+fn deinterleave<F: Field>(values: &mut [F]) {
+    let half_n = values.len() / 2;
+    let odds: Vec<F> = values.iter().skip(1).step_by(2).copied().collect();
+    for i in 0..half_n {
+        values[i] = values[2 * i];
+    }
+    values[half_n..].copy_from_slice(&odds);
+}
+
+/// Rearranges `[a_0, a_1, ..., b_0, b_1, ...]` into `[a_0, b_0, a_1, b_1, ...]`.
+fn interleave<F: Field>(values: &mut [F]) {
+    let half_n = values.len() / 2;
+    let evens = values[..half_n].to_vec();
+    for i in 0..half_n {
+        values[2 * i] = evens[i];
+        values[2 * i + 1] = values[half_n + i];
+    }
+}
+
+/// Multiplies each `values[i]` by `base^i`.
+fn scale_by_powers<F: Field>(values: &mut [F], base: F) {
+    let width = <F as Packable>::Packing::WIDTH;
+    if values.len() < width {
+        for (v, power) in values.iter_mut().zip(base.powers()) {
+            *v *= power;
+        }
+        return;
+    }
+
+    let mut powers = <F as Packable>::Packing::default();
+    for (p, power) in powers.as_slice_mut().iter_mut().zip(base.powers()) {
+        *p = power;
+    }
+    let step = base.exp_u64(width as u64);
+    for v in <F as Packable>::Packing::pack_slice_mut(values) {
+        *v *= powers;
+        powers *= step;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::vec::Vec;
 
-    use plonky2_util::{log2_ceil, log2_strict};
+    use plonky2_util::{log2_ceil, log2_strict, reverse_index_bits};
 
-    use crate::fft::{fft, fft_with_options, ifft};
+    use crate::fft::{
+        fft, fft_classic, fft_four_step, fft_in_place, fft_root_table, fft_with_options, ifft,
+        FOUR_STEP_MIN_LG_N,
+    };
     use crate::goldilocks_field::GoldilocksField;
     use crate::polynomial::{PolynomialCoeffs, PolynomialValues};
-    use crate::types::Field;
+    use crate::types::{Field, Sample};
 
     #[test]
     fn fft_and_ifft() {
@@ -252,6 +441,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn four_step_matches_classic() {
+        type F = GoldilocksField;
+        for lg_n in 1..=12 {
+            let n = 1 << lg_n;
+            let root_table = fft_root_table::<F>(n);
+            for r in [0, 1, lg_n / 2 + 1, lg_n] {
+                let mut coeffs = F::rand_vec(n >> r);
+                coeffs.resize(n, F::ZERO);
+
+                let mut expected = coeffs.clone();
+                fft_classic(&mut expected, r, &root_table);
+                let mut values = coeffs;
+                fft_four_step(&mut values, r, &root_table);
+                assert_eq!(values, expected, "lg_n = {lg_n}, r = {r}");
+            }
+        }
+    }
+
+    #[test]
+    fn fft_in_place_bit_reversal() {
+        type F = GoldilocksField;
+        for lg_n in [5, FOUR_STEP_MIN_LG_N + 1] {
+            let coeffs = F::rand_vec(1 << lg_n);
+            let expected = fft(PolynomialCoeffs::new(coeffs.clone())).values;
+            let coeffs_rev = reverse_index_bits(&coeffs);
+            let expected_rev = reverse_index_bits(&expected);
+
+            for input_bit_reversed in [false, true] {
+                for output_bit_reversed in [false, true] {
+                    let mut values = if input_bit_reversed {
+                        coeffs_rev.clone()
+                    } else {
+                        coeffs.clone()
+                    };
+                    fft_in_place(
+                        &mut values,
+                        None,
+                        None,
+                        input_bit_reversed,
+                        output_bit_reversed,
+                    );
+                    if output_bit_reversed {
+                        assert_eq!(values, expected_rev);
+                    } else {
+                        assert_eq!(values, expected);
+                    }
+                }
+            }
+        }
+    }
+
     fn evaluate_naive<F: Field>(coefficients: &PolynomialCoeffs<F>) -> PolynomialValues<F> {
         let degree = coefficients.len();
         let degree_padded = 1 << log2_ceil(degree);
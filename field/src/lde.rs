@@ -0,0 +1,236 @@
+//! Batched low-degree extensions of the columns of a matrix.
+
+use alloc::borrow::Cow;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use plonky2_maybe_rayon::*;
+
+use crate::fft::{fft_in_place_with_table, fft_root_table, FftRootTable};
+use crate::polynomial::{PolynomialCoeffs, PolynomialValues};
+use crate::types::Field;
+
+/// The number of columns extended at once. Each one needs a scratch buffer of the LDE size, so this
+/// bounds the extra memory, while leaving enough columns to keep the threads busy.
+const LDE_BATCH_SIZE: usize = 16;
+
+/// How a matrix is laid out in a contiguous buffer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MatrixLayout {
+    /// Entry `(i, j)` of a matrix of width `w` is stored at index `i * w + j`.
+    RowMajor,
+    /// Entry `(i, j)` of a matrix of height `h` is stored at index `j * h + i`.
+    ColumnMajor,
+}
+
+/// The result of [`CosetLde::lde_columns`] or [`CosetLde::lde_matrix`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LdeOutput<F: Field> {
+    /// The polynomials interpolating each column.
+    pub coeffs: Vec<PolynomialCoeffs<F>>,
+    /// The LDEs as rows in bit-reversed order, i.e. `leaves[i][j]` is the evaluation of the `j`-th
+    /// polynomial at `shift * g^reverse_bits(i)`, where `g` generates the LDE subgroup. This is the
+    /// layout expected by `MerkleTree::new`.
+    pub leaves: Vec<Vec<F>>,
+}
+
+/// Low-degree extends batches of columns of length `2^lg_n` onto the coset `shift * H`, where `H` is
+/// the subgroup of order `2^(lg_n + rate_bits)`.
+///
+/// Each column goes through the inverse FFT, the zero padding, the coset shift and the forward FFT
+/// in a single buffer, and all columns share the same twiddle tables.
+pub struct CosetLde<'a, F: Field> {
+    lg_n: usize,
+    rate_bits: usize,
+    /// Root table of the LDE size. Its prefix serves for the smaller inverse FFTs.
+    root_table: Cow<'a, FftRootTable<F>>,
+    n_inv: F,
+    /// `shift^i / n`, which both undoes the scaling of the inverse FFT and moves to the coset.
+    coset_factors: Vec<F>,
+}
+
+impl<'a, F: Field> CosetLde<'a, F> {
+    /// Prepares LDEs of columns of length `2^lg_n`. `root_table`, if given, must be the root table
+    /// of the LDE size `2^(lg_n + rate_bits)`.
+    pub fn new(
+        lg_n: usize,
+        rate_bits: usize,
+        shift: F,
+        root_table: Option<&'a FftRootTable<F>>,
+    ) -> Self {
+        let root_table = match root_table {
+            Some(table) => Cow::Borrowed(table),
+            None => Cow::Owned(fft_root_table(1 << (lg_n + rate_bits))),
+        };
+        assert_eq!(
+            root_table.len(),
+            lg_n + rate_bits,
+            "Root table does not match the LDE size"
+        );
+        let n_inv = F::inverse_2exp(lg_n);
+        let coset_factors = shift.powers().take(1 << lg_n).map(|s| s * n_inv).collect();
+        Self {
+            lg_n,
+            rate_bits,
+            root_table,
+            n_inv,
+            coset_factors,
+        }
+    }
+
+    /// Extends each of `columns`. Every leaf has spare capacity for `leaf_padding` more elements,
+    /// e.g. salt, so that appending them does not reallocate.
+    pub fn lde_columns(
+        &self,
+        columns: &[PolynomialValues<F>],
+        leaf_padding: usize,
+    ) -> LdeOutput<F> {
+        assert!(
+            columns.iter().all(|c| c.len() == 1 << self.lg_n),
+            "Polynomial degrees inconsistent"
+        );
+        self.lde(
+            columns.len(),
+            |j, out| out.copy_from_slice(&columns[j].values),
+            leaf_padding,
+        )
+    }
+
+    /// Extends each column of the matrix of width `width` stored in `values` with the given
+    /// `layout`. Every leaf has spare capacity for `leaf_padding` more elements.
+    pub fn lde_matrix(
+        &self,
+        values: &[F],
+        width: usize,
+        layout: MatrixLayout,
+        leaf_padding: usize,
+    ) -> LdeOutput<F> {
+        let n = 1 << self.lg_n;
+        assert_eq!(values.len(), n * width, "Matrix dimensions inconsistent");
+        match layout {
+            MatrixLayout::RowMajor => self.lde(
+                width,
+                |j, out| {
+                    for (o, &v) in out.iter_mut().zip(values[j..].iter().step_by(width)) {
+                        *o = v;
+                    }
+                },
+                leaf_padding,
+            ),
+            MatrixLayout::ColumnMajor => self.lde(
+                width,
+                |j, out| out.copy_from_slice(&values[j * n..(j + 1) * n]),
+                leaf_padding,
+            ),
+        }
+    }
+
+    fn lde<L>(&self, num_columns: usize, load_column: L, leaf_padding: usize) -> LdeOutput<F>
+    where
+        L: Fn(usize, &mut [F]) + Sync,
+    {
+        let n = 1 << self.lg_n;
+        let lde_size = n << self.rate_bits;
+
+        let mut leaves = (0..lde_size)
+            .into_par_iter()
+            .map(|_| {
+                let mut leaf = Vec::with_capacity(num_columns + leaf_padding);
+                leaf.resize(num_columns, F::ZERO);
+                leaf
+            })
+            .collect::<Vec<_>>();
+        let mut coeffs = Vec::with_capacity(num_columns);
+
+        // The columns are extended a batch at a time in a scratch buffer, which is then written
+        // straight into the leaves, so the LDEs are never all held in a second matrix.
+        let batch_size = num_columns.min(LDE_BATCH_SIZE);
+        let mut scratch = vec![F::ZERO; batch_size * lde_size];
+        for batch_start in (0..num_columns).step_by(LDE_BATCH_SIZE) {
+            let batch_len = LDE_BATCH_SIZE.min(num_columns - batch_start);
+            let batch = &mut scratch[..batch_len * lde_size];
+            let batch_coeffs = batch.par_chunks_mut(lde_size).enumerate().map(|(k, lde)| {
+                let (values, padding) = lde.split_at_mut(n);
+                load_column(batch_start + k, values);
+                padding.fill(F::ZERO);
+
+                // The inverse FFT is a forward FFT, followed by reversing all values but the first
+                // and dividing by `n`.
+                fft_in_place_with_table(values, 0, &self.root_table[..self.lg_n], false, false);
+                values[1..].reverse();
+                let mut coeffs = Vec::with_capacity(n);
+                for (v, &factor) in values.iter_mut().zip(&self.coset_factors) {
+                    coeffs.push(*v * self.n_inv);
+                    *v *= factor;
+                }
+
+                fft_in_place_with_table(lde, self.rate_bits, &self.root_table, false, true);
+                PolynomialCoeffs::new(coeffs)
+            });
+            coeffs.extend(batch_coeffs.collect::<Vec<_>>());
+
+            // Each LDE is in bit-reversed order, so leaf `i` takes the `i`-th value of each one.
+            let batch = &*batch;
+            leaves.par_iter_mut().enumerate().for_each(|(i, leaf)| {
+                let row = &mut leaf[batch_start..batch_start + batch_len];
+                for (v, lde) in row.iter_mut().zip(batch.chunks_exact(lde_size)) {
+                    *v = lde[i];
+                }
+            });
+        }
+
+        LdeOutput { coeffs, leaves }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use plonky2_util::reverse_index_bits;
+
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::lde::{CosetLde, MatrixLayout};
+    use crate::polynomial::PolynomialValues;
+    use crate::types::{Field, Sample};
+
+    #[test]
+    fn coset_lde_matches_lde_onto_coset() {
+        type F = GoldilocksField;
+        let lg_n = 5;
+        let rate_bits = 2;
+        // More columns than a batch, so that the last batch is partial.
+        let num_columns = 20;
+
+        let columns = (0..num_columns)
+            .map(|_| PolynomialValues::new(F::rand_vec(1 << lg_n)))
+            .collect::<Vec<_>>();
+        let expected_coeffs = columns.iter().map(|c| c.clone().ifft()).collect::<Vec<_>>();
+        let expected_ldes = columns
+            .iter()
+            .map(|c| reverse_index_bits(&c.clone().lde_onto_coset(rate_bits).values))
+            .collect::<Vec<_>>();
+
+        let coset_lde = CosetLde::new(lg_n, rate_bits, F::coset_shift(), None);
+        let column_major = columns
+            .iter()
+            .flat_map(|c| c.values.iter().copied())
+            .collect::<Vec<_>>();
+        let row_major = (0..1 << lg_n)
+            .flat_map(|i| columns.iter().map(move |c| c.values[i]))
+            .collect::<Vec<_>>();
+
+        for output in [
+            coset_lde.lde_columns(&columns, 0),
+            coset_lde.lde_matrix(&column_major, num_columns, MatrixLayout::ColumnMajor, 0),
+            coset_lde.lde_matrix(&row_major, num_columns, MatrixLayout::RowMajor, 0),
+        ] {
+            assert_eq!(output.coeffs, expected_coeffs);
+            assert_eq!(output.leaves.len(), 1 << (lg_n + rate_bits));
+            for (i, leaf) in output.leaves.iter().enumerate() {
+                let expected_leaf = expected_ldes.iter().map(|l| l[i]).collect::<Vec<_>>();
+                assert_eq!(leaf, &expected_leaf);
+            }
+        }
+    }
+}
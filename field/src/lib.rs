@@ -12,12 +12,18 @@ extern crate alloc;
 pub(crate) mod arch;
 
 pub mod batch_util;
+pub mod bls12_381_base;
+pub mod bn254_base;
+pub mod bn254_scalar;
 pub mod cosets;
+pub mod ecgfp5_scalar;
 pub mod extension;
 pub mod fft;
 pub mod goldilocks_extensions;
 pub mod goldilocks_field;
 pub mod interpolation;
+pub mod lde;
+pub mod montgomery;
 pub mod ops;
 pub mod packable;
 pub mod packed;
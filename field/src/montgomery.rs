@@ -0,0 +1,516 @@
+//! Prime fields of a few hundred bits, whose elements are stored in Montgomery form as `N`
+//! little-endian 64-bit limbs.
+//!
+//! The arithmetic only works on fixed-size limb arrays, so these fields are much faster than the
+//! `BigUint`-based ones such as [`Secp256K1Base`](crate::secp256k1_base::Secp256K1Base).
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug, Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::{Product, Sum};
+use core::marker::PhantomData;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use itertools::Itertools;
+use num::bigint::BigUint;
+use num::Integer;
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ops::Square;
+use crate::types::{Field, PrimeField, Sample};
+
+/// The parameters of a prime field whose elements have `N` limbs. All values are little-endian
+/// limbs of canonical (i.e. not Montgomery) representatives.
+pub trait MontgomeryParameters<const N: usize>: 'static + Send + Sync {
+    /// The order of the field, which must be greater than `2^128`.
+    const MODULUS: [u64; N];
+
+    /// The 2-adicity of the multiplicative group.
+    const TWO_ADICITY: usize;
+
+    /// Generator of the entire multiplicative group.
+    const MULTIPLICATIVE_GROUP_GENERATOR: [u64; N];
+
+    /// Generator of the multiplicative subgroup of order `2^TWO_ADICITY`.
+    const POWER_OF_TWO_GENERATOR: [u64; N];
+}
+
+/// An element of the prime field described by `P`, stored in Montgomery form, i.e. `x` is
+/// represented by `x * 2^(64 * N) mod p`.
+pub struct MontgomeryField<P: MontgomeryParameters<N>, const N: usize> {
+    /// The fully reduced Montgomery representation.
+    limbs: [u64; N],
+    _phantom: PhantomData<P>,
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> MontgomeryField<P, N> {
+    /// `-p^-1 mod 2^64`.
+    const INV: u64 = neg_inverse(P::MODULUS[0]);
+
+    /// `2^(64 * N) mod p`, i.e. the Montgomery representation of 1.
+    const R: [u64; N] = r_mod(&P::MODULUS);
+
+    /// `2^(128 * N) mod p`, which converts canonical values to Montgomery form.
+    const R2: [u64; N] = r2_mod(&P::MODULUS);
+
+    const fn from_montgomery_limbs(limbs: [u64; N]) -> Self {
+        Self {
+            limbs,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a field element from the little-endian limbs of a value less than the order.
+    pub const fn from_canonical_limbs(limbs: [u64; N]) -> Self {
+        assert!(!geq(&limbs, &P::MODULUS), "Value exceeds the field order");
+        Self::from_montgomery_limbs(mont_mul(&limbs, &Self::R2, &P::MODULUS, Self::INV))
+    }
+
+    /// Returns the little-endian limbs of the canonical representative of this element.
+    pub fn to_canonical_limbs(&self) -> [u64; N] {
+        let mut one = [0; N];
+        one[0] = 1;
+        mont_mul(&self.limbs, &one, &P::MODULUS, Self::INV)
+    }
+
+    fn exp_limbs(&self, exponent: &[u64; N]) -> Self {
+        let mut result = Self::ONE;
+        for &limb in exponent.iter().rev() {
+            for i in (0..64).rev() {
+                result = result.square();
+                if (limb >> i) & 1 == 1 {
+                    result *= *self;
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Copy for MontgomeryField<P, N> {}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Clone for MontgomeryField<P, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Default for MontgomeryField<P, N> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> PartialEq for MontgomeryField<P, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.limbs == other.limbs
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Eq for MontgomeryField<P, N> {}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Hash for MontgomeryField<P, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.limbs.hash(state)
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Display for MontgomeryField<P, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.to_canonical_biguint(), f)
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Debug for MontgomeryField<P, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&self.to_canonical_biguint(), f)
+    }
+}
+
+/// Elements are serialized as the limbs of their canonical representative.
+impl<P: MontgomeryParameters<N>, const N: usize> Serialize for MontgomeryField<P, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(N)?;
+        for limb in self.to_canonical_limbs() {
+            tuple.serialize_element(&limb)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de, P: MontgomeryParameters<N>, const N: usize> Deserialize<'de> for MontgomeryField<P, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct LimbsVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for LimbsVisitor<N> {
+            type Value = [u64; N];
+
+            fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+                write!(f, "an array of {} limbs", N)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut limbs = [0; N];
+                for (i, limb) in limbs.iter_mut().enumerate() {
+                    *limb = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+                }
+                Ok(limbs)
+            }
+        }
+
+        let limbs = deserializer.deserialize_tuple(N, LimbsVisitor::<N>)?;
+        if geq(&limbs, &P::MODULUS) {
+            return Err(de::Error::custom("value exceeds the field order"));
+        }
+        Ok(Self::from_canonical_limbs(limbs))
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Sample for MontgomeryField<P, N> {
+    #[inline]
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        use num::bigint::RandBigInt;
+        Self::from_noncanonical_biguint(rng.gen_biguint_below(&Self::order()))
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Field for MontgomeryField<P, N> {
+    const ZERO: Self = Self::from_montgomery_limbs([0; N]);
+    const ONE: Self = Self::from_montgomery_limbs(Self::R);
+    const TWO: Self = Self::from_montgomery_limbs(add_mod(&Self::R, &Self::R, &P::MODULUS));
+    const NEG_ONE: Self = Self::from_montgomery_limbs(sub_mod(&[0; N], &Self::R, &P::MODULUS));
+
+    const TWO_ADICITY: usize = P::TWO_ADICITY;
+    const CHARACTERISTIC_TWO_ADICITY: usize = Self::TWO_ADICITY;
+
+    const MULTIPLICATIVE_GROUP_GENERATOR: Self =
+        Self::from_canonical_limbs(P::MULTIPLICATIVE_GROUP_GENERATOR);
+    const POWER_OF_TWO_GENERATOR: Self = Self::from_canonical_limbs(P::POWER_OF_TWO_GENERATOR);
+
+    const BITS: usize = bits(&P::MODULUS);
+
+    fn order() -> BigUint {
+        biguint_from_limbs(&P::MODULUS)
+    }
+    fn characteristic() -> BigUint {
+        Self::order()
+    }
+
+    fn try_inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+
+        // Fermat's Little Theorem
+        let mut two = [0; N];
+        two[0] = 2;
+        Some(self.exp_limbs(&sub_limbs(&P::MODULUS, &two).0))
+    }
+
+    fn from_noncanonical_biguint(val: BigUint) -> Self {
+        Self::from_canonical_limbs(
+            val.mod_floor(&Self::order())
+                .to_u64_digits()
+                .into_iter()
+                .pad_using(N, |_| 0)
+                .collect::<Vec<_>>()[..]
+                .try_into()
+                .expect("error converting to u64 array"),
+        )
+    }
+
+    #[inline]
+    fn from_canonical_u64(n: u64) -> Self {
+        let mut limbs = [0; N];
+        limbs[0] = n;
+        Self::from_canonical_limbs(limbs)
+    }
+
+    #[inline]
+    fn from_noncanonical_u128(n: u128) -> Self {
+        let mut limbs = [0; N];
+        limbs[0] = n as u64;
+        limbs[1] = (n >> 64) as u64;
+        Self::from_canonical_limbs(limbs)
+    }
+
+    fn from_noncanonical_i64(n: i64) -> Self {
+        let f = Self::from_canonical_u64(n.unsigned_abs());
+        if n < 0 {
+            -f
+        } else {
+            f
+        }
+    }
+
+    fn from_noncanonical_u64(n: u64) -> Self {
+        Self::from_canonical_u64(n)
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> PrimeField for MontgomeryField<P, N> {
+    fn to_canonical_biguint(&self) -> BigUint {
+        biguint_from_limbs(&self.to_canonical_limbs())
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Neg for MontgomeryField<P, N> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self {
+        Self::from_montgomery_limbs(sub_mod(&[0; N], &self.limbs, &P::MODULUS))
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Add for MontgomeryField<P, N> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        Self::from_montgomery_limbs(add_mod(&self.limbs, &rhs.limbs, &P::MODULUS))
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> AddAssign for MontgomeryField<P, N> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Sum for MontgomeryField<P, N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Sub for MontgomeryField<P, N> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self {
+        Self::from_montgomery_limbs(sub_mod(&self.limbs, &rhs.limbs, &P::MODULUS))
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> SubAssign for MontgomeryField<P, N> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Mul for MontgomeryField<P, N> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self {
+        Self::from_montgomery_limbs(mont_mul(&self.limbs, &rhs.limbs, &P::MODULUS, Self::INV))
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> MulAssign for MontgomeryField<P, N> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Product for MontgomeryField<P, N> {
+    #[inline]
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.reduce(|acc, x| acc * x).unwrap_or(Self::ONE)
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> Div for MontgomeryField<P, N> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl<P: MontgomeryParameters<N>, const N: usize> DivAssign for MontgomeryField<P, N> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+fn biguint_from_limbs<const N: usize>(limbs: &[u64; N]) -> BigUint {
+    BigUint::from_slice(
+        &limbs
+            .iter()
+            .flat_map(|&limb| [limb as u32, (limb >> 32) as u32])
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// The bit length of a nonzero value.
+const fn bits<const N: usize>(a: &[u64; N]) -> usize {
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        if a[i] != 0 {
+            return 64 * (i + 1) - a[i].leading_zeros() as usize;
+        }
+    }
+    0
+}
+
+/// Computes `a + b * c + carry`, returning the low and high words.
+#[inline(always)]
+const fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let t = a as u128 + (b as u128) * (c as u128) + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// Computes `a + b`, and whether it overflowed.
+#[inline(always)]
+const fn add_limbs<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], bool) {
+    let mut result = [0; N];
+    let mut carry = false;
+    let mut i = 0;
+    while i < N {
+        let (sum, carry_1) = a[i].overflowing_add(b[i]);
+        let (sum, carry_2) = sum.overflowing_add(carry as u64);
+        result[i] = sum;
+        carry = carry_1 | carry_2;
+        i += 1;
+    }
+    (result, carry)
+}
+
+/// Computes `a - b`, and whether it underflowed.
+#[inline(always)]
+const fn sub_limbs<const N: usize>(a: &[u64; N], b: &[u64; N]) -> ([u64; N], bool) {
+    let mut result = [0; N];
+    let mut borrow = false;
+    let mut i = 0;
+    while i < N {
+        let (diff, borrow_1) = a[i].overflowing_sub(b[i]);
+        let (diff, borrow_2) = diff.overflowing_sub(borrow as u64);
+        result[i] = diff;
+        borrow = borrow_1 | borrow_2;
+        i += 1;
+    }
+    (result, borrow)
+}
+
+/// Returns whether `a >= b`.
+#[inline(always)]
+const fn geq<const N: usize>(a: &[u64; N], b: &[u64; N]) -> bool {
+    let mut i = N;
+    while i > 0 {
+        i -= 1;
+        if a[i] != b[i] {
+            return a[i] > b[i];
+        }
+    }
+    true
+}
+
+/// Computes `a + b mod m`, assuming `a, b < m`.
+#[inline(always)]
+const fn add_mod<const N: usize>(a: &[u64; N], b: &[u64; N], m: &[u64; N]) -> [u64; N] {
+    let (sum, carry) = add_limbs(a, b);
+    if carry || geq(&sum, m) {
+        sub_limbs(&sum, m).0
+    } else {
+        sum
+    }
+}
+
+/// Computes `a - b mod m`, assuming `a, b < m`.
+#[inline(always)]
+const fn sub_mod<const N: usize>(a: &[u64; N], b: &[u64; N], m: &[u64; N]) -> [u64; N] {
+    let (diff, borrow) = sub_limbs(a, b);
+    if borrow {
+        add_limbs(&diff, m).0
+    } else {
+        diff
+    }
+}
+
+/// Montgomery multiplication, i.e. computes `a * b * 2^(-64 * N) mod m` assuming `a, b < m`, with
+/// the coarsely integrated operand scanning (CIOS) method. `inv` must be `-m^-1 mod 2^64`.
+#[inline]
+const fn mont_mul<const N: usize>(a: &[u64; N], b: &[u64; N], m: &[u64; N], inv: u64) -> [u64; N] {
+    // The accumulator is `t + t_hi * 2^(64 * N)`, and stays below `2m` so `t_hi` is at most 1.
+    let mut t = [0u64; N];
+    let mut t_hi = 0u64;
+    let mut i = 0;
+    while i < N {
+        // t += a * b[i]
+        let mut carry = 0u64;
+        let mut j = 0;
+        while j < N {
+            (t[j], carry) = mac(t[j], a[j], b[i], carry);
+            j += 1;
+        }
+        let (sum, overflow) = t_hi.overflowing_add(carry);
+        t_hi = sum;
+
+        // t = (t + k * m) / 2^64, where k is chosen so that the division is exact.
+        let k = t[0].wrapping_mul(inv);
+        let (_, mut carry) = mac(t[0], k, m[0], 0);
+        let mut j = 1;
+        while j < N {
+            (t[j - 1], carry) = mac(t[j], k, m[j], carry);
+            j += 1;
+        }
+        let (sum, overflow_2) = t_hi.overflowing_add(carry);
+        t[N - 1] = sum;
+        t_hi = overflow as u64 + overflow_2 as u64;
+        i += 1;
+    }
+
+    if t_hi != 0 || geq(&t, m) {
+        sub_limbs(&t, m).0
+    } else {
+        t
+    }
+}
+
+/// Computes `-m^-1 mod 2^64` for an odd `m`, by Newton iteration.
+const fn neg_inverse(m: u64) -> u64 {
+    assert!(m & 1 == 1, "Modulus must be odd");
+    // Each iteration doubles the number of correct low bits, starting from 1.
+    let mut inv = 1u64;
+    let mut i = 0;
+    while i < 6 {
+        inv = inv.wrapping_mul(2u64.wrapping_sub(m.wrapping_mul(inv)));
+        i += 1;
+    }
+    inv.wrapping_neg()
+}
+
+/// Computes `2^(64 * N) mod m`.
+const fn r_mod<const N: usize>(m: &[u64; N]) -> [u64; N] {
+    // `0 - m` wraps around to `2^(64 * N) - m`.
+    let mut r = sub_limbs(&[0; N], m).0;
+    while geq(&r, m) {
+        r = sub_limbs(&r, m).0;
+    }
+    r
+}
+
+/// Computes `2^(128 * N) mod m`, by doubling `2^(64 * N) mod m` another `64 * N` times.
+const fn r2_mod<const N: usize>(m: &[u64; N]) -> [u64; N] {
+    let mut r2 = r_mod(m);
+    let mut i = 0;
+    while i < 64 * N {
+        r2 = add_mod(&r2, &r2, m);
+        i += 1;
+    }
+    r2
+}
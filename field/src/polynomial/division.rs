@@ -1,36 +1,68 @@
 use alloc::vec;
 use alloc::vec::Vec;
+use core::cmp::min;
 
-use plonky2_util::log2_ceil;
-
+use crate::fft::fft_root_table;
 use crate::polynomial::PolynomialCoeffs;
 use crate::types::Field;
 
+/// [`PolynomialCoeffs::div_rem`] falls back to long division when the divisor or the quotient has
+/// at most this many coefficients.
+const LONG_DIVISION_MAX_LEN: usize = 32;
+
 impl<F: Field> PolynomialCoeffs<F> {
     /// Polynomial division.
     /// Returns `(q, r)`, the quotient and remainder of the polynomial division of `a` by `b`.
+    ///
+    /// Short divisors and quotients use long division; otherwise `q` is computed from the reversed
+    /// polynomials with a Newton-iteration inverse, in `O(n log n)`.
     pub fn div_rem(&self, b: &Self) -> (Self, Self) {
-        let (a_degree_plug_1, b_degree_plus_1) = (self.degree_plus_one(), b.degree_plus_one());
-        if a_degree_plug_1 == 0 {
+        self.div_rem_with_table(b, None)
+    }
+
+    /// Same as [`Self::div_rem`], with a root table at least twice as large as `self` for the FFTs.
+    pub(crate) fn div_rem_with_table(
+        &self,
+        b: &Self,
+        root_table: Option<&[Vec<F>]>,
+    ) -> (Self, Self) {
+        let (a_degree_plus_1, b_degree_plus_1) = (self.degree_plus_one(), b.degree_plus_one());
+        if a_degree_plus_1 == 0 {
             (Self::zero(1), Self::empty())
         } else if b_degree_plus_1 == 0 {
             panic!("Division by zero polynomial");
-        } else if a_degree_plug_1 < b_degree_plus_1 {
+        } else if a_degree_plus_1 < b_degree_plus_1 {
             (Self::zero(1), self.clone())
         } else if b_degree_plus_1 == 1 {
             (self * b.coeffs[0].inverse(), Self::empty())
         } else {
-            let rev_b = b.rev();
-            let rev_b_inv = rev_b.inv_mod_xn(a_degree_plug_1 - b_degree_plus_1 + 1);
-            let rhs: Self = self.rev().coeffs[..=a_degree_plug_1 - b_degree_plus_1]
-                .to_vec()
-                .into();
-            let rev_q: Self = (&rev_b_inv * &rhs).coeffs[..=a_degree_plug_1 - b_degree_plus_1]
-                .to_vec()
-                .into();
-            let mut q = rev_q.rev();
-            let qb = &q * b;
-            let mut r = self - &qb;
+            let q_len = a_degree_plus_1 - b_degree_plus_1 + 1;
+            if min(q_len, b_degree_plus_1) <= LONG_DIVISION_MAX_LEN {
+                return self.div_rem_long_division(b);
+            }
+
+            let computed_root_table;
+            let root_table = match root_table {
+                Some(table) => table,
+                None => {
+                    computed_root_table = fft_root_table(2 * a_degree_plus_1.next_power_of_two());
+                    &computed_root_table[..]
+                }
+            };
+
+            // With `rev(p) = X^deg(p) p(1/X)`, we have `rev(a) = rev(q) rev(b) mod X^q_len`.
+            let rev_b_inv = b.rev().inv_mod_xn_with_table(q_len, root_table);
+            let rev_a = Self::new(self.rev().coeffs[..q_len].to_vec());
+            let mut rev_q = rev_b_inv.mul_with_table(&rev_a, root_table);
+            rev_q.coeffs.truncate(q_len);
+            rev_q.coeffs.resize(q_len, F::ZERO);
+            let mut q = Self::new(rev_q.coeffs.into_iter().rev().collect());
+
+            let qb = q.mul_with_table(&b.trimmed(), root_table);
+            let mut r = Self::new(self.coeffs[..b_degree_plus_1 - 1].to_vec());
+            for (c, &qb_c) in r.coeffs.iter_mut().zip(&qb.coeffs) {
+                *c -= qb_c;
+            }
             q.trim();
             r.trim();
             (q, r)
@@ -90,45 +122,33 @@ impl<F: Field> PolynomialCoeffs<F> {
     /// Computes the inverse of `self` modulo `x^n`.
     pub fn inv_mod_xn(&self, n: usize) -> Self {
         assert!(n > 0, "`n` needs to be nonzero");
-        assert!(self.coeffs[0].is_nonzero(), "Inverse doesn't exist.");
+        let root_table = fft_root_table(2 * n.next_power_of_two());
+        self.inv_mod_xn_with_table(n, &root_table)
+    }
 
-        // If polynomial is constant, return the inverse of the constant.
-        if self.degree_plus_one() == 1 {
-            return Self::new(vec![self.coeffs[0].inverse()]);
-        }
+    /// Same as [`Self::inv_mod_xn`], with a root table at least twice as large as `n` for the FFTs.
+    ///
+    /// This uses Newton iteration: if `g = 1/f mod X^k`, then `g (2 - f g) = 1/f mod X^(2k)`.
+    pub(crate) fn inv_mod_xn_with_table(&self, n: usize, root_table: &[Vec<F>]) -> Self {
+        assert!(n > 0, "`n` needs to be nonzero");
+        assert!(self.coeffs[0].is_nonzero(), "Inverse doesn't exist.");
 
-        let h = if self.len() < n {
-            self.padded(n)
-        } else {
-            self.clone()
-        };
-
-        let mut a = Self::empty();
-        a.coeffs.push(h.coeffs[0].inverse());
-        for i in 0..log2_ceil(n) {
-            let l = 1 << i;
-            let h0 = h.coeffs[..l].to_vec().into();
-            let mut h1: Self = h.coeffs[l..].to_vec().into();
-            let mut c = &a * &h0;
-            if l == c.len() {
-                c = Self::zero(1);
-            } else {
-                c.coeffs.drain(0..l);
-            }
-            h1.trim();
-            let mut tmp = &a * &h1;
-            tmp = &tmp + &c;
-            tmp.coeffs.iter_mut().for_each(|x| *x = -(*x));
-            tmp.trim();
-            let mut b = &a * &tmp;
-            b.trim();
-            if b.len() > l {
-                b.coeffs.drain(l..);
-            }
-            a.coeffs.extend_from_slice(&b.coeffs);
+        let mut inv = Self::new(vec![self.coeffs[0].inverse()]);
+        let mut k = 1;
+        while k < n {
+            let next_k = min(2 * k, n);
+            // `e = f g mod X^next_k` is `1 + X^k h` for some `h`, and the next inverse is
+            // `g - X^k (g h mod X^(next_k - k))`.
+            let f = Self::new(self.coeffs[..min(next_k, self.len())].to_vec());
+            let mut e = f.mul_with_table(&inv, root_table);
+            e.coeffs.resize(next_k, F::ZERO);
+            let h = Self::new(e.coeffs.split_off(k));
+            let mut gh = inv.mul_with_table(&h, root_table);
+            gh.coeffs.resize(next_k - k, F::ZERO);
+            inv.coeffs.extend(gh.coeffs.into_iter().map(|c| -c));
+            k = next_k;
         }
-        a.coeffs.drain(n..);
-        a
+        inv
     }
 }
 
@@ -1,20 +1,25 @@
 pub(crate) mod division;
+pub mod multipoint;
 
 use alloc::vec;
 use alloc::vec::Vec;
-use core::cmp::max;
+use core::cmp::{max, min};
 use core::iter::Sum;
 use core::ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign};
 
 use anyhow::{ensure, Result};
 use itertools::Itertools;
-use plonky2_util::log2_strict;
+use plonky2_util::{log2_ceil, log2_strict};
 use serde::{Deserialize, Serialize};
 
 use crate::extension::{Extendable, FieldExtension};
-use crate::fft::{fft, fft_with_options, ifft, FftRootTable};
+use crate::fft::{fft, fft_in_place_with_table, fft_with_options, ifft, FftRootTable};
 use crate::types::Field;
 
+/// Products with an operand of at most this many coefficients use schoolbook multiplication, which
+/// beats the FFT at such sizes.
+const SCHOOLBOOK_MUL_MAX_LEN: usize = 32;
+
 /// A polynomial in point-value form.
 ///
 /// The points are implicitly `g^i`, where `g` generates the subgroup whose size equals the number
@@ -261,6 +266,65 @@ impl<F: Field> PolynomialCoeffs<F> {
         Self::new(self.trimmed().coeffs.into_iter().rev().collect())
     }
 
+    /// The formal derivative of the polynomial.
+    pub fn derivative(&self) -> Self {
+        let coeffs = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, &c)| F::from_canonical_usize(i) * c)
+            .collect();
+        Self::new(coeffs)
+    }
+
+    /// Multiplies `self` by `rhs`. Short operands use schoolbook multiplication; otherwise the
+    /// product is computed with FFTs reading their twiddles from a prefix of `root_table`, which
+    /// must be at least as large as the product.
+    pub(crate) fn mul_with_table(&self, rhs: &Self, root_table: &[Vec<F>]) -> Self {
+        let (a_len, b_len) = (self.len(), rhs.len());
+        if a_len == 0 || b_len == 0 {
+            return Self::empty();
+        }
+        let product_len = a_len + b_len - 1;
+
+        if min(a_len, b_len) <= SCHOOLBOOK_MUL_MAX_LEN {
+            let mut coeffs = vec![F::ZERO; product_len];
+            for (i, &a) in self.coeffs.iter().enumerate() {
+                for (c, &b) in coeffs[i..].iter_mut().zip(&rhs.coeffs) {
+                    *c += a * b;
+                }
+            }
+            return Self::new(coeffs);
+        }
+
+        let lg_len = log2_ceil(product_len);
+        assert!(
+            root_table.len() >= lg_len,
+            "Root table too small for a product of length {product_len}"
+        );
+        let root_table = &root_table[..lg_len];
+        let zero_factor = lg_len - log2_ceil(max(a_len, b_len));
+        let mut a_evals = self.padded(1 << lg_len).coeffs;
+        let mut b_evals = rhs.padded(1 << lg_len).coeffs;
+        fft_in_place_with_table(&mut a_evals, zero_factor, root_table, false, false);
+        fft_in_place_with_table(&mut b_evals, zero_factor, root_table, false, false);
+        for (a, b) in a_evals.iter_mut().zip(b_evals) {
+            *a *= b;
+        }
+
+        // The inverse FFT is a forward FFT, followed by reversing all values but the first and
+        // dividing by the length.
+        fft_in_place_with_table(&mut a_evals, 0, root_table, false, false);
+        a_evals[1..].reverse();
+        a_evals.truncate(product_len);
+        let len_inv = F::inverse_2exp(lg_len);
+        for c in a_evals.iter_mut() {
+            *c *= len_inv;
+        }
+        Self::new(a_evals)
+    }
+
     pub fn fft(self) -> PolynomialValues<F> {
         fft(self)
     }
@@ -446,6 +510,7 @@ mod tests {
     use rand::Rng;
 
     use super::*;
+    use crate::fft::fft_root_table;
     use crate::goldilocks_field::GoldilocksField;
     use crate::types::Sample;
 
@@ -533,6 +598,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mul_with_table() {
+        type F = GoldilocksField;
+        let root_table = fft_root_table(1 << 12);
+        for (a_len, b_len) in [(1, 1), (5, 300), (33, 33), (1000, 2000)] {
+            let a = PolynomialCoeffs::new(F::rand_vec(a_len));
+            let b = PolynomialCoeffs::new(F::rand_vec(b_len));
+            let product = a.mul_with_table(&b, &root_table);
+            assert_eq!(product.len(), a_len + b_len - 1);
+            assert_eq!(product, &a * &b);
+        }
+    }
+
     #[test]
     fn test_inv_mod_xn() {
         type F = GoldilocksField;
@@ -0,0 +1,198 @@
+//! Multipoint evaluation and interpolation over arbitrary point sets, using subproduct trees.
+//!
+//! See e.g. von zur Gathen and Gerhard, *Modern Computer Algebra*, chapter 10.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::min;
+
+use plonky2_maybe_rayon::*;
+
+use crate::fft::{fft_root_table, FftRootTable};
+use crate::polynomial::PolynomialCoeffs;
+use crate::types::Field;
+
+/// Below this layer of the tree, [`SubproductTree::evaluate`] evaluates the remainders directly.
+const EVAL_BASE_LAYER: usize = 4;
+
+/// The subproduct tree of a list of points `x_0, ..., x_{n-1}`.
+///
+/// Layer `k` holds the polynomials `M_{k,i} = prod_j (X - x_j)` for `j` in
+/// `[i * 2^k, (i + 1) * 2^k)`. Each node is thus the product of two nodes of the layer below, or
+/// a copy of a lone one, and the single node of the last layer is the vanishing polynomial of all
+/// the points.
+///
+/// Building the tree and each evaluation or interpolation take `O(n log^2 n)` operations. The
+/// field must have a subgroup of order `2 * n.next_power_of_two()`.
+#[derive(Clone, Debug)]
+pub struct SubproductTree<F: Field> {
+    points: Vec<F>,
+    layers: Vec<Vec<PolynomialCoeffs<F>>>,
+    /// Root table large enough for every product and division in the tree.
+    root_table: FftRootTable<F>,
+}
+
+impl<F: Field> SubproductTree<F> {
+    pub fn new(points: Vec<F>) -> Self {
+        assert!(!points.is_empty(), "No points given");
+        let root_table = fft_root_table(2 * points.len().next_power_of_two());
+
+        let mut layers = vec![points
+            .iter()
+            .map(|&x| PolynomialCoeffs::new(vec![-x, F::ONE]))
+            .collect::<Vec<_>>()];
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers
+                .last()
+                .unwrap()
+                .par_chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => left.mul_with_table(right, &root_table),
+                    [lone] => lone.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(layer);
+        }
+
+        Self {
+            points,
+            layers,
+            root_table,
+        }
+    }
+
+    pub fn points(&self) -> &[F] {
+        &self.points
+    }
+
+    /// The vanishing polynomial `prod_i (X - x_i)` of the points.
+    pub fn vanishing_poly(&self) -> &PolynomialCoeffs<F> {
+        &self.layers.last().unwrap()[0]
+    }
+
+    /// Evaluates `poly` at each of the points, by reducing it modulo the nodes of the tree.
+    pub fn evaluate(&self, poly: &PolynomialCoeffs<F>) -> Vec<F> {
+        let top = self.layers.len() - 1;
+        let base = min(EVAL_BASE_LAYER, top);
+
+        // `poly` may be larger than the root table, so this first division uses its own.
+        let mut remainders = vec![poly.div_rem(self.vanishing_poly()).1];
+        for layer in self.layers[base..top].iter().rev() {
+            remainders = layer
+                .par_iter()
+                .enumerate()
+                .map(|(i, node)| {
+                    remainders[i / 2]
+                        .div_rem_with_table(node, Some(&self.root_table[..]))
+                        .1
+                })
+                .collect();
+        }
+
+        self.points
+            .par_iter()
+            .enumerate()
+            .map(|(i, &x)| remainders[i >> base].eval(x))
+            .collect()
+    }
+
+    /// Returns the unique polynomial of degree less than `n` taking the given values at the points.
+    /// The points must be distinct.
+    pub fn interpolate(&self, values: &[F]) -> PolynomialCoeffs<F> {
+        assert_eq!(
+            values.len(),
+            self.points.len(),
+            "Number of values does not match the number of points"
+        );
+
+        // The Lagrange basis polynomial of `x_i` is `M(X) / ((X - x_i) M'(x_i))`.
+        let denominators = self.evaluate(&self.vanishing_poly().derivative());
+        assert!(
+            denominators.iter().all(|d| d.is_nonzero()),
+            "Interpolation points must be distinct"
+        );
+        let weights = F::batch_multiplicative_inverse(&denominators);
+
+        // Going up the tree, each node `M_{k,i}` holds `sum_j w_j y_j M_{k,i} / (X - x_j)` over its
+        // points.
+        let mut combinations = values
+            .iter()
+            .zip(weights)
+            .map(|(&y, w)| PolynomialCoeffs::new(vec![y * w]))
+            .collect::<Vec<_>>();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            combinations = combinations
+                .par_chunks(2)
+                .enumerate()
+                .map(|(i, pair)| match pair {
+                    [left, right] => {
+                        let mut combination =
+                            left.mul_with_table(&layer[2 * i + 1], &self.root_table);
+                        combination += right.mul_with_table(&layer[2 * i], &self.root_table);
+                        combination
+                    }
+                    [lone] => lone.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+
+        let mut interpolant = combinations.pop().unwrap();
+        interpolant.trim();
+        interpolant
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::goldilocks_field::GoldilocksField;
+    use crate::interpolation::interpolant;
+    use crate::polynomial::multipoint::SubproductTree;
+    use crate::polynomial::PolynomialCoeffs;
+    use crate::types::{Field, Sample};
+
+    type F = GoldilocksField;
+
+    #[test]
+    fn evaluate_matches_naive() {
+        for (num_points, degree) in [(1, 0), (5, 12), (37, 10), (300, 1000), (1000, 300)] {
+            let points = F::rand_vec(num_points);
+            let poly = PolynomialCoeffs::new(F::rand_vec(degree + 1));
+            let tree = SubproductTree::new(points.clone());
+            let expected = points.iter().map(|&x| poly.eval(x)).collect::<Vec<_>>();
+            assert_eq!(tree.evaluate(&poly), expected);
+        }
+    }
+
+    #[test]
+    fn interpolate_matches_naive() {
+        for num_points in [1, 2, 7, 100] {
+            let points = F::rand_vec(num_points);
+            let values = F::rand_vec(num_points);
+            let tree = SubproductTree::new(points.clone());
+            let expected = interpolant(
+                &points
+                    .iter()
+                    .copied()
+                    .zip(values.iter().copied())
+                    .collect::<Vec<_>>(),
+            );
+            assert_eq!(tree.interpolate(&values), expected);
+        }
+    }
+
+    #[test]
+    fn interpolate_evaluate_roundtrip() {
+        let num_points = 1 << 10;
+        let points = F::rand_vec(num_points);
+        let poly = PolynomialCoeffs::new(F::rand_vec(num_points));
+        let tree = SubproductTree::new(points);
+        let values = tree.evaluate(&poly);
+        assert_eq!(tree.interpolate(&values), poly);
+        assert!(tree
+            .points()
+            .iter()
+            .all(|&x| tree.vanishing_poly().eval(x) == F::ZERO));
+    }
+}
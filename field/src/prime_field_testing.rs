@@ -1,6 +1,9 @@
 use alloc::vec::Vec;
 
-use crate::types::PrimeField64;
+use num::bigint::BigUint;
+use num::One;
+
+use crate::types::{PrimeField, PrimeField64, Sample};
 
 /// Generates a series of non-negative integers less than `modulus` which cover a range of
 /// interesting test values.
@@ -66,6 +69,150 @@ where
     }
 }
 
+/// Generates a series of elements of `F`, as integers less than its order, which cover a range of
+/// interesting test values. Unlike `test_inputs`, this supports orders of any size.
+pub fn test_inputs_biguint<F: PrimeField>() -> Vec<BigUint> {
+    const CHUNK_SIZE: u64 = 5;
+    const NUM_RANDOM: usize = 10;
+
+    let order = F::order();
+    let mut inputs: Vec<BigUint> = (0..CHUNK_SIZE)
+        .flat_map(|i| [BigUint::from(i), &order - 1u64 - i])
+        .collect();
+    for bits in [32, 64, 128, 192, F::BITS - 1] {
+        let power = BigUint::one() << bits;
+        inputs.extend([&power - 1u64, power.clone(), power + 1u64]);
+    }
+    inputs.extend((0..NUM_RANDOM).map(|_| F::rand().to_canonical_biguint()));
+    inputs.retain(|x| x < &order);
+    inputs
+}
+
+/// Apply the unary function `op` to the inputs from `test_inputs_biguint`, and panic if the
+/// results differ from those of `expected_op`, which operates on `BigUint`s.
+pub fn run_unaryop_biguint_test_cases<F, UnaryOp, ExpectedOp>(op: UnaryOp, expected_op: ExpectedOp)
+where
+    F: PrimeField,
+    UnaryOp: Fn(F) -> F,
+    ExpectedOp: Fn(&BigUint) -> BigUint,
+{
+    for x in test_inputs_biguint::<F>() {
+        let actual = op(F::from_noncanonical_biguint(x.clone())).to_canonical_biguint();
+        let expected = expected_op(&x);
+        assert_eq!(
+            actual, expected,
+            "Expected {}, got {} for input {}",
+            expected, actual, x
+        );
+    }
+}
+
+/// Apply the binary functions `op` and `expected_op` to each pair of inputs from
+/// `test_inputs_biguint`.
+pub fn run_binaryop_biguint_test_cases<F, BinaryOp, ExpectedOp>(
+    op: BinaryOp,
+    expected_op: ExpectedOp,
+) where
+    F: PrimeField,
+    BinaryOp: Fn(F, F) -> F,
+    ExpectedOp: Fn(&BigUint, &BigUint) -> BigUint,
+{
+    let inputs = test_inputs_biguint::<F>();
+
+    for lhs in &inputs {
+        for rhs in &inputs {
+            let lhs_f = F::from_noncanonical_biguint(lhs.clone());
+            let rhs_f = F::from_noncanonical_biguint(rhs.clone());
+            let actual = op(lhs_f, rhs_f).to_canonical_biguint();
+            let expected = expected_op(lhs, rhs);
+            assert_eq!(
+                actual, expected,
+                "Expected {}, got {} for inputs ({}, {})",
+                expected, actual, lhs, rhs
+            );
+        }
+    }
+}
+
+/// Cross-checks the arithmetic of a large prime field against `BigUint` arithmetic modulo its
+/// order.
+#[macro_export]
+macro_rules! test_prime_field_arithmetic_biguint {
+    ($field:ty) => {
+        mod prime_field_arithmetic_biguint {
+            use core::ops::{Add, Mul, Neg, Sub};
+
+            use num::bigint::BigUint;
+            use $crate::ops::Square;
+            use $crate::types::{Field, PrimeField};
+
+            #[test]
+            fn arithmetic_addition() {
+                let modulus = <$field>::order();
+                $crate::prime_field_testing::run_binaryop_biguint_test_cases(
+                    <$field>::add,
+                    |x, y| (x + y) % &modulus,
+                )
+            }
+
+            #[test]
+            fn arithmetic_subtraction() {
+                let modulus = <$field>::order();
+                $crate::prime_field_testing::run_binaryop_biguint_test_cases(
+                    <$field>::sub,
+                    |x, y| (x + &modulus - y) % &modulus,
+                )
+            }
+
+            #[test]
+            fn arithmetic_negation() {
+                let modulus = <$field>::order();
+                $crate::prime_field_testing::run_unaryop_biguint_test_cases(<$field>::neg, |x| {
+                    (&modulus - x) % &modulus
+                })
+            }
+
+            #[test]
+            fn arithmetic_multiplication() {
+                let modulus = <$field>::order();
+                $crate::prime_field_testing::run_binaryop_biguint_test_cases(
+                    <$field>::mul,
+                    |x, y| (x * y) % &modulus,
+                )
+            }
+
+            #[test]
+            fn arithmetic_square() {
+                let modulus = <$field>::order();
+                $crate::prime_field_testing::run_unaryop_biguint_test_cases(
+                    |x: $field| x.square(),
+                    |x| (x * x) % &modulus,
+                )
+            }
+
+            #[test]
+            fn inversion() {
+                let modulus = <$field>::order();
+                assert_eq!(<$field>::ZERO.try_inverse(), None);
+                $crate::prime_field_testing::run_unaryop_biguint_test_cases(
+                    |x: $field| x.try_inverse().unwrap_or(<$field>::ZERO),
+                    |x| x.modpow(&(&modulus - 2u64), &modulus),
+                )
+            }
+
+            #[test]
+            fn constants() {
+                type F = $field;
+                let modulus = F::order();
+                assert_eq!(F::ONE.to_canonical_biguint(), BigUint::from(1u64));
+                assert_eq!(F::TWO.to_canonical_biguint(), BigUint::from(2u64));
+                assert_eq!(F::NEG_ONE.to_canonical_biguint(), &modulus - 1u64);
+                assert_eq!(F::BITS as u64, modulus.bits());
+            }
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! test_prime_field_arithmetic {
     ($field:ty) => {
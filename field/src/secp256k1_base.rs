@@ -9,6 +9,7 @@ use num::bigint::BigUint;
 use num::{Integer, One};
 use serde::{Deserialize, Serialize};
 
+use crate::montgomery::{MontgomeryField, MontgomeryParameters};
 use crate::types::{Field, PrimeField, Sample};
 
 /// The base field of the secp256k1 elliptic curve.
@@ -263,9 +264,70 @@ impl DivAssign for Secp256K1Base {
     }
 }
 
+/// Parameters of [`Secp256K1BaseMontgomery`].
+#[derive(Copy, Clone, Debug)]
+pub struct Secp256K1BaseParameters;
+
+impl MontgomeryParameters<4> for Secp256K1BaseParameters {
+    const MODULUS: [u64; 4] = [
+        0xFFFFFFFEFFFFFC2F,
+        0xFFFFFFFFFFFFFFFF,
+        0xFFFFFFFFFFFFFFFF,
+        0xFFFFFFFFFFFFFFFF,
+    ];
+
+    const TWO_ADICITY: usize = 1;
+
+    // Sage: `g = GF(p).multiplicative_generator()`
+    const MULTIPLICATIVE_GROUP_GENERATOR: [u64; 4] = [5, 0, 0, 0];
+
+    // Sage: `g_2 = g^((p - 1) / 2)`, i.e. -1
+    const POWER_OF_TWO_GENERATOR: [u64; 4] = [
+        0xFFFFFFFEFFFFFC2E,
+        0xFFFFFFFFFFFFFFFF,
+        0xFFFFFFFFFFFFFFFF,
+        0xFFFFFFFFFFFFFFFF,
+    ];
+}
+
+/// The base field of the secp256k1 elliptic curve, with elements in Montgomery form. Its
+/// arithmetic is much faster than that of [`Secp256K1Base`].
+pub type Secp256K1BaseMontgomery = MontgomeryField<Secp256K1BaseParameters, 4>;
+
 #[cfg(test)]
 mod tests {
     use crate::test_field_arithmetic;
 
     test_field_arithmetic!(crate::secp256k1_base::Secp256K1Base);
+
+    mod montgomery {
+        use crate::secp256k1_base::{Secp256K1Base, Secp256K1BaseMontgomery};
+        use crate::types::{Field, PrimeField, Sample};
+        use crate::{test_field_arithmetic, test_prime_field_arithmetic_biguint};
+
+        test_field_arithmetic!(crate::secp256k1_base::Secp256K1BaseMontgomery);
+        test_prime_field_arithmetic_biguint!(crate::secp256k1_base::Secp256K1BaseMontgomery);
+
+        #[test]
+        fn matches_biguint_field() {
+            type F = Secp256K1BaseMontgomery;
+            let convert = |x: Secp256K1Base| F::from_noncanonical_biguint(x.to_canonical_biguint());
+
+            assert_eq!(
+                convert(Secp256K1Base::MULTIPLICATIVE_GROUP_GENERATOR),
+                F::MULTIPLICATIVE_GROUP_GENERATOR
+            );
+            assert_eq!(
+                convert(Secp256K1Base::POWER_OF_TWO_GENERATOR),
+                F::POWER_OF_TWO_GENERATOR
+            );
+            for _ in 0..100 {
+                let (x, y) = (Secp256K1Base::rand(), Secp256K1Base::rand());
+                assert_eq!(convert(x + y), convert(x) + convert(y));
+                assert_eq!(convert(x - y), convert(x) - convert(y));
+                assert_eq!(convert(x * y), convert(x) * convert(y));
+                assert_eq!(convert(x.inverse()), convert(x).inverse());
+            }
+        }
+    }
 }
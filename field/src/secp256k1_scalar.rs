@@ -9,6 +9,7 @@ use num::bigint::BigUint;
 use num::{Integer, One};
 use serde::{Deserialize, Serialize};
 
+use crate::montgomery::{MontgomeryField, MontgomeryParameters};
 use crate::types::{Field, PrimeField, Sample};
 
 /// The base field of the secp256k1 elliptic curve.
@@ -271,9 +272,71 @@ impl DivAssign for Secp256K1Scalar {
     }
 }
 
+/// Parameters of [`Secp256K1ScalarMontgomery`].
+#[derive(Copy, Clone, Debug)]
+pub struct Secp256K1ScalarParameters;
+
+impl MontgomeryParameters<4> for Secp256K1ScalarParameters {
+    const MODULUS: [u64; 4] = [
+        0xBFD25E8CD0364141,
+        0xBAAEDCE6AF48A03B,
+        0xFFFFFFFFFFFFFFFE,
+        0xFFFFFFFFFFFFFFFF,
+    ];
+
+    const TWO_ADICITY: usize = 6;
+
+    // Sage: `g = GF(p).multiplicative_generator()`
+    const MULTIPLICATIVE_GROUP_GENERATOR: [u64; 4] = [7, 0, 0, 0];
+
+    // Sage: `g_2 = power_mod(g, (p - 1) // 2^6), p)`
+    const POWER_OF_TWO_GENERATOR: [u64; 4] = [
+        0x992F4B5402B052F2,
+        0x98BDEAB680756045,
+        0xDF9879A3FBC483A8,
+        0x0C1DC060E7A91986,
+    ];
+}
+
+/// The scalar field of the secp256k1 elliptic curve, with elements in Montgomery form. Its
+/// arithmetic is much faster than that of [`Secp256K1Scalar`].
+pub type Secp256K1ScalarMontgomery = MontgomeryField<Secp256K1ScalarParameters, 4>;
+
 #[cfg(test)]
 mod tests {
     use crate::test_field_arithmetic;
 
     test_field_arithmetic!(crate::secp256k1_scalar::Secp256K1Scalar);
+
+    mod montgomery {
+        use crate::secp256k1_scalar::{Secp256K1Scalar, Secp256K1ScalarMontgomery};
+        use crate::types::{Field, PrimeField, Sample};
+        use crate::{test_field_arithmetic, test_prime_field_arithmetic_biguint};
+
+        test_field_arithmetic!(crate::secp256k1_scalar::Secp256K1ScalarMontgomery);
+        test_prime_field_arithmetic_biguint!(crate::secp256k1_scalar::Secp256K1ScalarMontgomery);
+
+        #[test]
+        fn matches_biguint_field() {
+            type F = Secp256K1ScalarMontgomery;
+            let convert =
+                |x: Secp256K1Scalar| F::from_noncanonical_biguint(x.to_canonical_biguint());
+
+            assert_eq!(
+                convert(Secp256K1Scalar::MULTIPLICATIVE_GROUP_GENERATOR),
+                F::MULTIPLICATIVE_GROUP_GENERATOR
+            );
+            assert_eq!(
+                convert(Secp256K1Scalar::POWER_OF_TWO_GENERATOR),
+                F::POWER_OF_TWO_GENERATOR
+            );
+            for _ in 0..100 {
+                let (x, y) = (Secp256K1Scalar::rand(), Secp256K1Scalar::rand());
+                assert_eq!(convert(x + y), convert(x) + convert(y));
+                assert_eq!(convert(x - y), convert(x) - convert(y));
+                assert_eq!(convert(x * y), convert(x) * convert(y));
+                assert_eq!(convert(x.inverse()), convert(x).inverse());
+            }
+        }
+    }
 }
@@ -9,7 +9,7 @@ use tynm::type_name;
 pub(crate) fn bench_ffts<F: Field>(c: &mut Criterion) {
     let mut group = c.benchmark_group(&format!("fft<{}>", type_name::<F>()));
 
-    for size_log in [13, 14, 15, 16] {
+    for size_log in [13, 14, 15, 16, 18, 20] {
         let size = 1 << size_log;
         group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
             let coeffs = PolynomialCoeffs::new(F::rand_vec(size));
@@ -0,0 +1,181 @@
+use core::fmt::Debug;
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::field::ops::Square;
+use crate::field::types::{Field, PrimeField, Sample};
+
+/// A short Weierstrass curve `y^2 = x^3 + A x + B` whose group of points has prime order.
+pub trait Curve: 'static + Sync + Sized + Copy + Debug {
+    type BaseField: PrimeField;
+    type ScalarField: PrimeField + Sample;
+
+    const A: Self::BaseField;
+    const B: Self::BaseField;
+
+    const GENERATOR_AFFINE: AffinePoint<Self>;
+}
+
+/// A point of the curve `C` in affine coordinates, or the point at infinity if `zero` is set.
+#[derive(Copy, Clone, Debug)]
+pub struct AffinePoint<C: Curve> {
+    pub x: C::BaseField,
+    pub y: C::BaseField,
+    pub zero: bool,
+}
+
+impl<C: Curve> AffinePoint<C> {
+    pub const ZERO: Self = Self {
+        x: C::BaseField::ZERO,
+        y: C::BaseField::ZERO,
+        zero: true,
+    };
+
+    pub const fn nonzero(x: C::BaseField, y: C::BaseField) -> Self {
+        Self { x, y, zero: false }
+    }
+
+    /// Returns the point with the given `x` coordinate and `y` coordinate of the given parity, if
+    /// there is one.
+    pub fn lift_x(x: C::BaseField, y_is_odd: bool) -> Option<Self> {
+        let y = (x.cube() + C::A * x + C::B).sqrt()?;
+        let y = if y.to_canonical_biguint().bit(0) == y_is_odd {
+            y
+        } else {
+            -y
+        };
+        Some(Self::nonzero(x, y))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let Self { x, y, zero } = *self;
+        zero || y.square() == x.cube() + C::A * x + C::B
+    }
+
+    pub fn double(&self) -> Self {
+        let Self { x, y, zero } = *self;
+        if zero || y.is_zero() {
+            return Self::ZERO;
+        }
+
+        let lambda = (x.square().triple() + C::A) / y.double();
+        let x3 = lambda.square() - x.double();
+        let y3 = lambda * (x - x3) - y;
+        Self::nonzero(x3, y3)
+    }
+}
+
+impl<C: Curve> PartialEq for AffinePoint<C> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.zero, other.zero) {
+            (true, true) => true,
+            (false, false) => self.x == other.x && self.y == other.y,
+            _ => false,
+        }
+    }
+}
+
+impl<C: Curve> Eq for AffinePoint<C> {}
+
+impl<C: Curve> Neg for AffinePoint<C> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+            zero: self.zero,
+        }
+    }
+}
+
+impl<C: Curve> Add for AffinePoint<C> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.zero {
+            return rhs;
+        }
+        if rhs.zero {
+            return self;
+        }
+        if self.x == rhs.x {
+            return if self.y == rhs.y {
+                self.double()
+            } else {
+                Self::ZERO
+            };
+        }
+
+        let lambda = (rhs.y - self.y) / (rhs.x - self.x);
+        let x3 = lambda.square() - self.x - rhs.x;
+        let y3 = lambda * (self.x - x3) - self.y;
+        Self::nonzero(x3, y3)
+    }
+}
+
+impl<C: Curve> Sub for AffinePoint<C> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+/// A scalar by which points of `C` can be multiplied.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CurveScalar<C: Curve>(pub C::ScalarField);
+
+impl<C: Curve> Mul<AffinePoint<C>> for CurveScalar<C> {
+    type Output = AffinePoint<C>;
+
+    /// Double-and-add, from the most significant bit of the canonical representative.
+    fn mul(self, rhs: AffinePoint<C>) -> AffinePoint<C> {
+        let k = self.0.to_canonical_biguint();
+        (0..k.bits()).rev().fold(AffinePoint::ZERO, |acc, i| {
+            let acc = acc.double();
+            if k.bit(i) {
+                acc + rhs
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, PrimeField, Sample};
+
+    type C = Secp256K1;
+
+    #[test]
+    fn test_group_laws() {
+        let g = C::GENERATOR_AFFINE;
+        assert!(g.is_valid());
+
+        let (a, b) = (Secp256K1Scalar::rand(), Secp256K1Scalar::rand());
+        let a_g = CurveScalar::<C>(a) * g;
+        let b_g = CurveScalar::<C>(b) * g;
+        assert!(a_g.is_valid());
+        assert_eq!(a_g + b_g, CurveScalar::<C>(a + b) * g);
+        assert_eq!(a_g - b_g, CurveScalar::<C>(a - b) * g);
+        assert_eq!(a_g + a_g, a_g.double());
+        assert_eq!(a_g - a_g, AffinePoint::ZERO);
+        assert_eq!(CurveScalar::<C>(Secp256K1Scalar::NEG_ONE) * g, -g);
+        assert_eq!(
+            CurveScalar::<C>(Secp256K1Scalar::ZERO) * g,
+            AffinePoint::ZERO
+        );
+    }
+
+    #[test]
+    fn test_lift_x() {
+        let p = CurveScalar::<C>(Secp256K1Scalar::rand()) * C::GENERATOR_AFFINE;
+        let p_is_odd = p.y.to_canonical_biguint().bit(0);
+        assert_eq!(AffinePoint::<C>::lift_x(p.x, p_is_odd), Some(p));
+        assert_eq!(AffinePoint::<C>::lift_x(p.x, !p_is_odd), Some(-p));
+    }
+}
@@ -0,0 +1,168 @@
+use keccak_hash::keccak;
+use serde::{Deserialize, Serialize};
+
+use crate::curve::curve_types::{AffinePoint, Curve, CurveScalar};
+use crate::curve::secp256k1::Secp256K1;
+use crate::field::types::{Field, PrimeField, Sample};
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ECDSASignature<C: Curve> {
+    pub r: C::ScalarField,
+    pub s: C::ScalarField,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct ECDSASecretKey<C: Curve>(pub C::ScalarField);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ECDSAPublicKey<C: Curve>(pub AffinePoint<C>);
+
+impl<C: Curve> ECDSASecretKey<C> {
+    pub fn to_public(&self) -> ECDSAPublicKey<C> {
+        ECDSAPublicKey(CurveScalar::<C>(self.0) * C::GENERATOR_AFFINE)
+    }
+}
+
+/// Converts an `x` coordinate to a scalar, reducing it modulo the order of the curve.
+fn base_to_scalar<C: Curve>(x: C::BaseField) -> C::ScalarField {
+    C::ScalarField::from_noncanonical_biguint(x.to_canonical_biguint() % C::ScalarField::order())
+}
+
+/// Signs the message hash `msg` with a random nonce. Also returns the parity of the `y`
+/// coordinate of the nonce point, which [`recover_public_key`] needs.
+pub fn sign_message<C: Curve>(
+    msg: C::ScalarField,
+    sk: ECDSASecretKey<C>,
+) -> (ECDSASignature<C>, bool) {
+    loop {
+        let k = C::ScalarField::rand();
+        let point = CurveScalar::<C>(k) * C::GENERATOR_AFFINE;
+        let r = base_to_scalar::<C>(point.x);
+        if point.zero || r.is_zero() {
+            continue;
+        }
+        let s = k.inverse() * (msg + r * sk.0);
+        if s.is_zero() {
+            continue;
+        }
+        let y_is_odd = point.y.to_canonical_biguint().bit(0);
+        return (ECDSASignature { r, s }, y_is_odd);
+    }
+}
+
+pub fn verify_message<C: Curve>(
+    msg: C::ScalarField,
+    sig: ECDSASignature<C>,
+    pk: ECDSAPublicKey<C>,
+) -> bool {
+    let ECDSASignature { r, s } = sig;
+    if r.is_zero() || s.is_zero() || !pk.0.is_valid() || pk.0.zero {
+        return false;
+    }
+
+    let s_inv = s.inverse();
+    let u1 = msg * s_inv;
+    let u2 = r * s_inv;
+    let point = CurveScalar::<C>(u1) * C::GENERATOR_AFFINE + CurveScalar::<C>(u2) * pk.0;
+    !point.zero && base_to_scalar::<C>(point.x) == r
+}
+
+/// Recovers the public key of a signature, given the parity of the `y` coordinate of the nonce
+/// point. The `x` coordinate of the nonce point is assumed to be `r`, rather than `r + n`.
+pub fn recover_public_key<C: Curve>(
+    msg: C::ScalarField,
+    sig: ECDSASignature<C>,
+    y_is_odd: bool,
+) -> Option<ECDSAPublicKey<C>> {
+    let ECDSASignature { r, s } = sig;
+    if r.is_zero() || s.is_zero() {
+        return None;
+    }
+
+    let x = C::BaseField::from_noncanonical_biguint(r.to_canonical_biguint());
+    let point = AffinePoint::<C>::lift_x(x, y_is_odd)?;
+    let r_inv = r.inverse();
+    let pk =
+        CurveScalar::<C>(s * r_inv) * point + CurveScalar::<C>(-msg * r_inv) * C::GENERATOR_AFFINE;
+    (!pk.zero).then_some(ECDSAPublicKey(pk))
+}
+
+/// The Ethereum address of a public key: the last 20 bytes of the Keccak-256 hash of its
+/// big-endian coordinates.
+pub fn eth_address(pk: &ECDSAPublicKey<Secp256K1>) -> [u8; 20] {
+    let mut encoding = [0u8; 64];
+    for (chunk, coordinate) in encoding.chunks_mut(32).zip([pk.0.x, pk.0.y]) {
+        let bytes = coordinate.to_canonical_biguint().to_bytes_be();
+        chunk[32 - bytes.len()..].copy_from_slice(&bytes);
+    }
+    keccak(encoding).0[12..].try_into().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigUint;
+
+    use crate::curve::ecdsa::{
+        eth_address, recover_public_key, sign_message, verify_message, ECDSASecretKey,
+        ECDSASignature,
+    };
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+
+    type C = Secp256K1;
+
+    fn scalar_from_hex(hex: &[u8]) -> Secp256K1Scalar {
+        Secp256K1Scalar::from_noncanonical_biguint(BigUint::parse_bytes(hex, 16).unwrap())
+    }
+
+    #[test]
+    fn test_sign_verify_recover() {
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<C>(Secp256K1Scalar::rand());
+        let pk = sk.to_public();
+
+        let (sig, y_is_odd) = sign_message(msg, sk);
+        assert!(verify_message(msg, sig, pk));
+        assert!(!verify_message(msg + Secp256K1Scalar::ONE, sig, pk));
+        assert_eq!(recover_public_key(msg, sig, y_is_odd), Some(pk));
+        assert_ne!(recover_public_key(msg, sig, !y_is_odd), Some(pk));
+    }
+
+    #[test]
+    fn test_eth_address() {
+        let sk = ECDSASecretKey::<C>(Secp256K1Scalar::ONE);
+        assert_eq!(
+            eth_address(&sk.to_public()),
+            hex_bytes("7e5f4552091a69125d5dfcb7b8c2659029395bdf")
+        );
+    }
+
+    /// The example transaction of EIP-155, signed with the key `0x4646...46`.
+    #[test]
+    fn test_recover_eip155_vector() {
+        let msg =
+            scalar_from_hex(b"daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53");
+        let sig = ECDSASignature::<C> {
+            r: scalar_from_hex(b"28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276"),
+            s: scalar_from_hex(b"67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"),
+        };
+        // `v = 37 = 35 + 2 * chain_id`, so the nonce point has an even `y` coordinate.
+        let pk = recover_public_key(msg, sig, false).unwrap();
+
+        let sk = ECDSASecretKey::<C>(scalar_from_hex(&b"46".repeat(32)));
+        assert_eq!(pk, sk.to_public());
+        assert!(verify_message(msg, sig, pk));
+        assert_eq!(
+            eth_address(&pk),
+            hex_bytes("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f")
+        );
+    }
+
+    fn hex_bytes(hex: &str) -> [u8; 20] {
+        let bytes = BigUint::parse_bytes(hex.as_bytes(), 16)
+            .unwrap()
+            .to_bytes_be();
+        bytes.try_into().unwrap()
+    }
+}
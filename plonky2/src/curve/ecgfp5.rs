@@ -0,0 +1,263 @@
+//! The ecGFp5 curve, defined over the degree 5 extension of the Goldilocks field so that its
+//! arithmetic is cheap in circuits over the Goldilocks field.
+//!
+//! The curve is `y^2 = x^3 + A x^2 + B x` over `GF(p^5) = GF(p)[z] / (z^5 - 3)`, with `A = 2` and
+//! `B = 263 z`. Its group of points has order `2 n`, where `n` is the 319-bit prime order of
+//! [`EcGFp5Scalar`], and only the subgroup of order `n` is used. A nonzero point is in this
+//! subgroup exactly when its `x` coordinate is a square.
+
+use core::ops::{Add, Mul, Neg, Sub};
+
+use num::BigUint;
+
+use crate::field::ecgfp5_scalar::EcGFp5Scalar;
+use crate::field::extension::quintic::QuinticExtension;
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::ops::Square;
+use crate::field::types::{Field, PrimeField};
+
+/// The base field of the ecGFp5 curve.
+pub type EcGFp5Base = QuinticExtension<GoldilocksField>;
+
+pub const ECGFP5_A: EcGFp5Base = QuinticExtension([
+    GoldilocksField(2),
+    GoldilocksField(0),
+    GoldilocksField(0),
+    GoldilocksField(0),
+    GoldilocksField(0),
+]);
+
+pub const ECGFP5_B: EcGFp5Base = QuinticExtension([
+    GoldilocksField(0),
+    GoldilocksField(263),
+    GoldilocksField(0),
+    GoldilocksField(0),
+    GoldilocksField(0),
+]);
+
+/// The point with `x = 1` and an even constant coefficient of `y`, which is in the subgroup of
+/// order `n`.
+const ECGFP5_GENERATOR_X: EcGFp5Base = QuinticExtension([
+    GoldilocksField(1),
+    GoldilocksField(0),
+    GoldilocksField(0),
+    GoldilocksField(0),
+    GoldilocksField(0),
+]);
+
+const ECGFP5_GENERATOR_Y: EcGFp5Base = QuinticExtension([
+    GoldilocksField(0xD90AE8EEC0D2329E),
+    GoldilocksField(0x023FE34FC5DE7FAB),
+    GoldilocksField(0x3F0793E3DAAAE42B),
+    GoldilocksField(0xBA17291775449F4F),
+    GoldilocksField(0xF33E4FA9D6EE7E31),
+]);
+
+/// A point of the ecGFp5 curve in affine coordinates, or the point at infinity if `zero` is set.
+#[derive(Copy, Clone, Debug)]
+pub struct EcGFp5Point {
+    pub x: EcGFp5Base,
+    pub y: EcGFp5Base,
+    pub zero: bool,
+}
+
+impl EcGFp5Point {
+    pub const ZERO: Self = Self {
+        x: EcGFp5Base::ZERO,
+        y: EcGFp5Base::ZERO,
+        zero: true,
+    };
+
+    /// A generator of the subgroup of order `n`.
+    pub const GENERATOR: Self = Self::nonzero(ECGFP5_GENERATOR_X, ECGFP5_GENERATOR_Y);
+
+    pub const fn nonzero(x: EcGFp5Base, y: EcGFp5Base) -> Self {
+        Self { x, y, zero: false }
+    }
+
+    /// Returns whether the point is on the curve and in the subgroup of order `n`.
+    pub fn is_valid(&self) -> bool {
+        let Self { x, y, zero } = *self;
+        zero || (y.square() == ecgfp5_rhs(x) && x.is_nonzero() && quintic_is_square(x))
+    }
+
+    pub fn double(&self) -> Self {
+        let Self { x, y, zero } = *self;
+        if zero || y.is_zero() {
+            return Self::ZERO;
+        }
+
+        let lambda = (x.square().triple() + ECGFP5_A.double() * x + ECGFP5_B) / y.double();
+        let x3 = lambda.square() - ECGFP5_A - x.double();
+        let y3 = lambda * (x - x3) - y;
+        Self::nonzero(x3, y3)
+    }
+}
+
+impl PartialEq for EcGFp5Point {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.zero, other.zero) {
+            (true, true) => true,
+            (false, false) => self.x == other.x && self.y == other.y,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for EcGFp5Point {}
+
+impl Neg for EcGFp5Point {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: self.x,
+            y: -self.y,
+            zero: self.zero,
+        }
+    }
+}
+
+impl Add for EcGFp5Point {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        if self.zero {
+            return rhs;
+        }
+        if rhs.zero {
+            return self;
+        }
+        if self.x == rhs.x {
+            return if self.y == rhs.y {
+                self.double()
+            } else {
+                Self::ZERO
+            };
+        }
+
+        let lambda = (rhs.y - self.y) / (rhs.x - self.x);
+        let x3 = lambda.square() - ECGFP5_A - self.x - rhs.x;
+        let y3 = lambda * (self.x - x3) - self.y;
+        Self::nonzero(x3, y3)
+    }
+}
+
+impl Sub for EcGFp5Point {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul<EcGFp5Scalar> for EcGFp5Point {
+    type Output = Self;
+
+    /// Double-and-add, from the most significant bit of the canonical representative.
+    fn mul(self, rhs: EcGFp5Scalar) -> Self {
+        let k = rhs.to_canonical_biguint();
+        (0..k.bits()).rev().fold(Self::ZERO, |acc, i| {
+            let acc = acc.double();
+            if k.bit(i) {
+                acc + self
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+/// Returns `x^3 + A x^2 + B x`.
+pub(crate) fn ecgfp5_rhs(x: EcGFp5Base) -> EcGFp5Base {
+    ((x + ECGFP5_A) * x + ECGFP5_B) * x
+}
+
+/// Euler's criterion in `GF(p^5)`.
+pub(crate) fn quintic_is_square(x: EcGFp5Base) -> bool {
+    let power = (EcGFp5Base::order() - 1u32) / 2u32;
+    x.is_zero() || x.exp_biguint(&power).is_one()
+}
+
+/// Returns a square root of `x`, if there is one, with the Tonelli-Shanks algorithm.
+pub(crate) fn quintic_sqrt(x: EcGFp5Base) -> Option<EcGFp5Base> {
+    if x.is_zero() {
+        return Some(x);
+    }
+    if !quintic_is_square(x) {
+        return None;
+    }
+
+    let t = (EcGFp5Base::order() - 1u32) >> EcGFp5Base::TWO_ADICITY;
+    let mut z = EcGFp5Base::POWER_OF_TWO_GENERATOR;
+    let mut w = x.exp_biguint(&((t - BigUint::from(1u32)) / 2u32));
+    let mut root = w * x;
+    let mut b = root * w;
+    let mut v = EcGFp5Base::TWO_ADICITY;
+    while !b.is_one() {
+        let mut k = 0;
+        let mut b2k = b;
+        while !b2k.is_one() {
+            b2k = b2k.square();
+            k += 1;
+        }
+        w = z;
+        for _ in 0..v - k - 1 {
+            w = w.square();
+        }
+        z = w.square();
+        b *= z;
+        root *= w;
+        v = k;
+    }
+    Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::ecgfp5::{quintic_sqrt, EcGFp5Base, EcGFp5Point};
+    use crate::field::ecgfp5_scalar::EcGFp5Scalar;
+    use crate::field::ops::Square;
+    use crate::field::types::{Field, Sample};
+
+    #[test]
+    fn test_generator() {
+        let g = EcGFp5Point::GENERATOR;
+        assert!(g.is_valid());
+
+        // The order of the generator is the order of the scalar field.
+        let neg_g = g * EcGFp5Scalar::NEG_ONE;
+        assert_eq!(neg_g + g, EcGFp5Point::ZERO);
+        assert_ne!(neg_g, g);
+    }
+
+    #[test]
+    fn test_group_laws() {
+        let g = EcGFp5Point::GENERATOR;
+        let (a, b) = (EcGFp5Scalar::rand(), EcGFp5Scalar::rand());
+        let a_g = g * a;
+        let b_g = g * b;
+        assert!(a_g.is_valid());
+        assert_eq!(a_g + b_g, g * (a + b));
+        assert_eq!(a_g - b_g, g * (a - b));
+        assert_eq!(a_g + a_g, a_g.double());
+        assert_eq!(a_g - a_g, EcGFp5Point::ZERO);
+        assert_eq!(g * EcGFp5Scalar::ZERO, EcGFp5Point::ZERO);
+    }
+
+    #[test]
+    fn test_subgroup_membership() {
+        // The 2-torsion point is on the curve but not in the subgroup of order `n`.
+        let t = EcGFp5Point::nonzero(EcGFp5Base::ZERO, EcGFp5Base::ZERO);
+        assert!(!t.is_valid());
+        assert_eq!(t.double(), EcGFp5Point::ZERO);
+        assert!(!(EcGFp5Point::GENERATOR + t).is_valid());
+    }
+
+    #[test]
+    fn test_quintic_sqrt() {
+        let x = EcGFp5Base::rand();
+        let root = quintic_sqrt(x.square()).unwrap();
+        assert!(root == x || root == -x);
+    }
+}
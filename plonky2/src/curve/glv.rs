@@ -0,0 +1,122 @@
+//! The GLV endomorphism `(x, y) -> (beta x, y)` of secp256k1, which multiplies points by `lambda`.
+//! Splitting a scalar `k` as `k1 + lambda k2` with `k1` and `k2` of about 128 bits halves the
+//! number of doublings of a scalar multiplication.
+
+use num::{BigInt, Signed};
+
+use crate::curve::curve_types::{AffinePoint, CurveScalar};
+use crate::curve::secp256k1::Secp256K1;
+use crate::field::secp256k1_base::Secp256K1Base;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::field::types::{Field, PrimeField};
+
+/// A cube root of unity in the base field.
+pub const GLV_BETA: Secp256K1Base = Secp256K1Base([
+    0xC1396C28719501EE,
+    0x9CF0497512F58995,
+    0x6E64479EAC3434E9,
+    0x7AE96A2B657C0710,
+]);
+
+/// The cube root of unity in the scalar field by which the endomorphism multiplies points.
+pub const GLV_LAMBDA: Secp256K1Scalar = Secp256K1Scalar([
+    0xDF02967C1B23BD72,
+    0x122E22EA20816678,
+    0xA5261C028812645A,
+    0x5363AD4CC05C30E0,
+]);
+
+// A short basis `(a1, b1), (a2, b2)` of the lattice of `(x, y)` with `x + lambda y = 0 mod n`,
+// where `b1 = -GLV_MINUS_B1`, `a2 = a1 - b1` and `b2 = a1`.
+const GLV_A1: u128 = 0x3086D221A7D46BCDE86C90E49284EB15;
+const GLV_MINUS_B1: u128 = 0xE4437ED6010E88286F547FA90ABFE4C3;
+
+/// The number of bits of the absolute values returned by [`decompose_secp256k1_scalar`].
+pub const GLV_HALF_BITS: usize = 128;
+
+/// Returns `(k1, k2, k1_neg, k2_neg)` such that `k = ±k1 + lambda (±k2)`, where `k1` and `k2` are
+/// less than `2^128` and the flags tell which terms are negated.
+pub fn decompose_secp256k1_scalar(
+    k: Secp256K1Scalar,
+) -> (Secp256K1Scalar, Secp256K1Scalar, bool, bool) {
+    let n = BigInt::from(Secp256K1Scalar::order());
+    let k = BigInt::from(k.to_canonical_biguint());
+    let a1 = BigInt::from(GLV_A1);
+    let minus_b1 = BigInt::from(GLV_MINUS_B1);
+    let a2 = &a1 + &minus_b1;
+    let b2 = a1.clone();
+
+    // Round `k (b2, -b1) / n` to the closest lattice point and subtract it.
+    let half_n: BigInt = &n >> 1usize;
+    let c1 = (&b2 * &k + &half_n) / &n;
+    let c2 = (&minus_b1 * &k + &half_n) / &n;
+    let k1 = &k - &c1 * &a1 - &c2 * &a2;
+    let k2 = &c1 * &minus_b1 - &c2 * &b2;
+    debug_assert!(k1.bits() <= GLV_HALF_BITS as u64 && k2.bits() <= GLV_HALF_BITS as u64);
+
+    (
+        Secp256K1Scalar::from_noncanonical_biguint(k1.magnitude().clone()),
+        Secp256K1Scalar::from_noncanonical_biguint(k2.magnitude().clone()),
+        k1.is_negative(),
+        k2.is_negative(),
+    )
+}
+
+/// Applies the endomorphism, which multiplies `p` by [`GLV_LAMBDA`].
+pub fn glv_endomorphism(p: AffinePoint<Secp256K1>) -> AffinePoint<Secp256K1> {
+    AffinePoint {
+        x: GLV_BETA * p.x,
+        ..p
+    }
+}
+
+/// Computes `k p` as `k1 p + k2 phi(p)`.
+pub fn glv_mul(p: AffinePoint<Secp256K1>, k: Secp256K1Scalar) -> AffinePoint<Secp256K1> {
+    let (k1, k2, k1_neg, k2_neg) = decompose_secp256k1_scalar(k);
+    let p1 = if k1_neg { -p } else { p };
+    let p2 = glv_endomorphism(if k2_neg { -p } else { p });
+    CurveScalar::<Secp256K1>(k1) * p1 + CurveScalar::<Secp256K1>(k2) * p2
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::curve_types::{Curve, CurveScalar};
+    use crate::curve::glv::{
+        decompose_secp256k1_scalar, glv_endomorphism, glv_mul, GLV_HALF_BITS, GLV_LAMBDA,
+    };
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, PrimeField, Sample};
+
+    #[test]
+    fn test_glv_decomposition() {
+        for k in [
+            Secp256K1Scalar::ZERO,
+            Secp256K1Scalar::ONE,
+            Secp256K1Scalar::NEG_ONE,
+            GLV_LAMBDA,
+            Secp256K1Scalar::rand(),
+        ] {
+            let (k1, k2, k1_neg, k2_neg) = decompose_secp256k1_scalar(k);
+            assert!(k1.to_canonical_biguint().bits() <= GLV_HALF_BITS as u64);
+            assert!(k2.to_canonical_biguint().bits() <= GLV_HALF_BITS as u64);
+
+            let k1 = if k1_neg { -k1 } else { k1 };
+            let k2 = if k2_neg { -k2 } else { k2 };
+            assert_eq!(k1 + GLV_LAMBDA * k2, k);
+        }
+    }
+
+    #[test]
+    fn test_glv_mul() {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        assert_eq!(
+            glv_endomorphism(g),
+            CurveScalar::<Secp256K1>(GLV_LAMBDA) * g
+        );
+
+        let p = CurveScalar::<Secp256K1>(Secp256K1Scalar::rand()) * g;
+        let k = Secp256K1Scalar::rand();
+        assert_eq!(glv_mul(p, k), CurveScalar::<Secp256K1>(k) * p);
+    }
+}
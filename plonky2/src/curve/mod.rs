@@ -0,0 +1,10 @@
+//! Native elliptic curve arithmetic, ECDSA and Schnorr signatures, mirrored by the gadgets in
+//! [`gadgets::curve`](crate::gadgets::curve), [`gadgets::ecdsa`](crate::gadgets::ecdsa),
+//! [`gadgets::ecgfp5`](crate::gadgets::ecgfp5) and [`gadgets::schnorr`](crate::gadgets::schnorr).
+
+pub mod curve_types;
+pub mod ecdsa;
+pub mod ecgfp5;
+pub mod glv;
+pub mod schnorr;
+pub mod secp256k1;
@@ -0,0 +1,116 @@
+//! Schnorr signatures over the ecGFp5 curve, with Poseidon challenges so that they can be verified
+//! cheaply in circuits.
+//!
+//! A signature of a message `m` under the public key `pk = sk G` is a pair `(s, e)` such that
+//! `e = H(R, pk, m)` for `R = s G + e pk`, where `H` is the Poseidon hash of the coordinates of the
+//! points and the message. The four elements of the hash are the little-endian 64-bit limbs of
+//! `e`, which is thus less than `2^256` and canonical as a scalar.
+
+use alloc::vec::Vec;
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::curve::ecgfp5::EcGFp5Point;
+use crate::field::ecgfp5_scalar::EcGFp5Scalar;
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::{Field, PrimeField64, Sample};
+use crate::hash::poseidon::PoseidonHash;
+use crate::plonk::config::Hasher;
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct SchnorrSignature {
+    pub s: EcGFp5Scalar,
+    pub e: EcGFp5Scalar,
+}
+
+#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct SchnorrSecretKey(pub EcGFp5Scalar);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SchnorrPublicKey(pub EcGFp5Point);
+
+impl SchnorrSecretKey {
+    pub fn to_public(&self) -> SchnorrPublicKey {
+        SchnorrPublicKey(EcGFp5Point::GENERATOR * self.0)
+    }
+}
+
+/// Returns the challenge `H(r, pk, msg)`.
+pub(crate) fn schnorr_challenge(
+    r: EcGFp5Point,
+    pk: SchnorrPublicKey,
+    msg: &[GoldilocksField],
+) -> EcGFp5Scalar {
+    let inputs = [r.x.0, r.y.0, pk.0.x.0, pk.0.y.0]
+        .concat()
+        .into_iter()
+        .chain(msg.iter().copied())
+        .collect::<Vec<_>>();
+    let hash = PoseidonHash::hash_no_pad(&inputs);
+    let e = hash
+        .elements
+        .iter()
+        .rev()
+        .fold(BigUint::default(), |acc, h| {
+            (acc << 64) + h.to_canonical_u64()
+        });
+    EcGFp5Scalar::from_noncanonical_biguint(e)
+}
+
+/// Signs the message `msg` with a random nonce.
+pub fn sign_message(msg: &[GoldilocksField], sk: SchnorrSecretKey) -> SchnorrSignature {
+    let pk = sk.to_public();
+    loop {
+        let k = EcGFp5Scalar::rand();
+        if k.is_zero() {
+            continue;
+        }
+        let r = EcGFp5Point::GENERATOR * k;
+        let e = schnorr_challenge(r, pk, msg);
+        let s = k - e * sk.0;
+        return SchnorrSignature { s, e };
+    }
+}
+
+pub fn verify_message(
+    msg: &[GoldilocksField],
+    sig: SchnorrSignature,
+    pk: SchnorrPublicKey,
+) -> bool {
+    if !pk.0.is_valid() || pk.0.zero {
+        return false;
+    }
+
+    let r = EcGFp5Point::GENERATOR * sig.s + pk.0 * sig.e;
+    !r.zero && schnorr_challenge(r, pk, msg) == sig.e
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::curve::schnorr::{sign_message, verify_message, SchnorrSecretKey};
+    use crate::field::ecgfp5_scalar::EcGFp5Scalar;
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::field::types::{Field, Sample};
+
+    #[test]
+    fn test_schnorr_native() {
+        let msg = GoldilocksField::rand_vec(7);
+        let sk = SchnorrSecretKey(EcGFp5Scalar::rand());
+        let pk = sk.to_public();
+
+        let sig = sign_message(&msg, sk);
+        assert!(verify_message(&msg, sig, pk));
+
+        let mut other_msg = msg.clone();
+        other_msg[0] += GoldilocksField::ONE;
+        assert!(!verify_message(&other_msg, sig, pk));
+
+        let other_pk = SchnorrSecretKey(EcGFp5Scalar::rand()).to_public();
+        assert!(!verify_message(&msg, sig, other_pk));
+
+        let mut forged = sig;
+        forged.s += EcGFp5Scalar::ONE;
+        assert!(!verify_message(&msg, forged, pk));
+    }
+}
@@ -7,6 +7,7 @@ use plonky2_maybe_rayon::*;
 
 use crate::field::extension::Extendable;
 use crate::field::fft::FftRootTable;
+use crate::field::lde::{CosetLde, LdeOutput};
 use crate::field::packed::PackedField;
 use crate::field::polynomial::{PolynomialCoeffs, PolynomialValues};
 use crate::fri::proof::FriProof;
@@ -62,20 +63,43 @@ impl<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>
         timing: &mut TimingTree,
         fft_root_table: Option<&FftRootTable<F>>,
     ) -> Self {
-        let coeffs = timed!(
+        let degree = values[0].len();
+        let salt_size = if blinding { SALT_SIZE } else { 0 };
+        let LdeOutput { coeffs, mut leaves } = timed!(
+            timing,
+            "IFFT + FFT",
+            CosetLde::new(
+                log2_strict(degree),
+                rate_bits,
+                F::coset_shift(),
+                fft_root_table
+            )
+            .lde_columns(&values, salt_size)
+        );
+        drop(values);
+
+        if blinding {
+            timed!(
+                timing,
+                "blinding",
+                leaves
+                    .par_iter_mut()
+                    .for_each(|leaf| leaf.extend(F::rand_vec(SALT_SIZE)))
+            );
+        }
+        let merkle_tree = timed!(
             timing,
-            "IFFT",
-            values.into_par_iter().map(|v| v.ifft()).collect::<Vec<_>>()
+            "build Merkle tree",
+            MerkleTree::new(leaves, cap_height)
         );
 
-        Self::from_coeffs(
-            coeffs,
+        Self {
+            polynomials: coeffs,
+            merkle_tree,
+            degree_log: log2_strict(degree),
             rate_bits,
             blinding,
-            cap_height,
-            timing,
-            fft_root_table,
-        )
+        }
     }
 
     /// Creates a list polynomial commitment for the polynomials `polynomials`.
@@ -345,6 +345,12 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         BoolTarget::new_unsafe(self.add(res_minus_b2, b2.target))
     }
 
+    /// computes the arithmetic extension of logical "xor": `b1 + b2 - 2 * b1 * b2`
+    pub fn xor(&mut self, b1: BoolTarget, b2: BoolTarget) -> BoolTarget {
+        let sum = self.add(b1.target, b2.target);
+        BoolTarget::new_unsafe(self.arithmetic(-F::TWO, F::ONE, b1.target, b2.target, sum))
+    }
+
     pub fn _if(&mut self, b: BoolTarget, x: Target, y: Target) -> Target {
         let not_b = self.not(b);
         let maybe_x = self.mul(b.target, x);
@@ -0,0 +1,715 @@
+//! Arbitrary-precision unsigned integers, with any number of 16-bit limbs.
+//!
+//! As with [`NonNativeTarget`](crate::gadgets::nonnative::NonNativeTarget), results are hinted and
+//! then checked with integer identities between limb vectors, column by column.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use num::{BigUint, Integer, One, Zero};
+
+use crate::field::extension::Extendable;
+use crate::gadgets::nonnative::{get_biguint, set_biguint, u16_limbs_of, NONNATIVE_LIMB_BITS};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::PartitionWitness;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+use crate::util::{ceil_div_usize, log2_ceil};
+
+/// An unsigned integer, as little-endian 16-bit limbs.
+///
+/// The limbs of the targets returned by the `CircuitBuilder` methods below are range checked. The
+/// most significant limbs may be zero, so the number of limbs only bounds the value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BigUintTarget {
+    pub(crate) limbs: Vec<Target>,
+}
+
+impl BigUintTarget {
+    pub fn num_limbs(&self) -> usize {
+        self.limbs.len()
+    }
+
+    pub fn limbs(&self) -> &[Target] {
+        &self.limbs
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds an integer with `num_limbs` range checked limbs.
+    pub fn add_virtual_biguint_target(&mut self, num_limbs: usize) -> BigUintTarget {
+        let limbs = self.add_virtual_targets(num_limbs);
+        self.biguint_from_limbs(limbs)
+    }
+
+    /// Interprets the little-endian 16-bit `limbs` as an integer, checking that each limb fits in
+    /// 16 bits.
+    pub fn biguint_from_limbs(&mut self, limbs: Vec<Target>) -> BigUintTarget {
+        assert!(!limbs.is_empty(), "An integer needs at least one limb");
+        for &limb in &limbs {
+            self.range_check_u16(limb);
+        }
+        BigUintTarget { limbs }
+    }
+
+    /// Returns a constant with as few limbs as possible.
+    pub fn constant_biguint(&mut self, value: &BigUint) -> BigUintTarget {
+        let num_limbs = ceil_div_usize(value.bits() as usize, NONNATIVE_LIMB_BITS).max(1);
+        let limbs = u16_limbs_of(value, num_limbs);
+        BigUintTarget {
+            limbs: self.constants(&limbs),
+        }
+    }
+
+    pub fn zero_biguint(&mut self) -> BigUintTarget {
+        self.constant_biguint(&BigUint::zero())
+    }
+
+    /// Connects two integers, whose numbers of limbs may differ.
+    pub fn connect_biguint(&mut self, lhs: &BigUintTarget, rhs: &BigUintTarget) {
+        let num_limbs = lhs.num_limbs().max(rhs.num_limbs());
+        let lhs = self.pad_biguint_limbs(lhs, num_limbs);
+        let rhs = self.pad_biguint_limbs(rhs, num_limbs);
+        for (l, r) in lhs.into_iter().zip(rhs) {
+            self.connect(l, r);
+        }
+    }
+
+    pub fn is_equal_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BoolTarget {
+        let num_limbs = a.num_limbs().max(b.num_limbs());
+        let a = self.pad_biguint_limbs(a, num_limbs);
+        let b = self.pad_biguint_limbs(b, num_limbs);
+        let mut result = self._true();
+        for (a_limb, b_limb) in a.into_iter().zip(b) {
+            let limb_equal = self.is_equal(a_limb, b_limb);
+            result = self.and(result, limb_equal);
+        }
+        result
+    }
+
+    pub fn is_less_than_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BoolTarget {
+        let num_limbs = a.num_limbs().max(b.num_limbs());
+        let a = self.pad_biguint_limbs(a, num_limbs);
+        let b = self.pad_biguint_limbs(b, num_limbs);
+        let geq = self.limbs_geq(&a, &b);
+        self.not(geq)
+    }
+
+    /// Returns `a + b`, with one more limb than the longest operand.
+    pub fn add_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let sum = self.add_virtual_biguint_target(a.num_limbs().max(b.num_limbs()) + 1);
+        self.add_simple_generator(BigUintAdditionGenerator {
+            a: a.limbs.clone(),
+            b: b.limbs.clone(),
+            sum: sum.limbs.clone(),
+        });
+
+        // a + b - sum = 0
+        let mut columns = BigUintColumns::default();
+        columns.add_linear(self, &a.limbs, false);
+        columns.add_linear(self, &b.limbs, false);
+        columns.add_linear(self, &sum.limbs, true);
+        columns.assert_zero(self);
+
+        sum
+    }
+
+    /// Returns `a - b`, with as many limbs as `a`. Results in an unsatisfiable instance if
+    /// `a < b`.
+    pub fn sub_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let diff = self.add_virtual_biguint_target(a.num_limbs());
+        self.add_simple_generator(BigUintSubtractionGenerator {
+            a: a.limbs.clone(),
+            b: b.limbs.clone(),
+            diff: diff.limbs.clone(),
+        });
+
+        // a - b - diff = 0
+        let mut columns = BigUintColumns::default();
+        columns.add_linear(self, &a.limbs, false);
+        columns.add_linear(self, &b.limbs, true);
+        columns.add_linear(self, &diff.limbs, true);
+        columns.assert_zero(self);
+
+        diff
+    }
+
+    /// Returns `a * b`, with as many limbs as the operands together.
+    pub fn mul_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        let product = self.add_virtual_biguint_target(a.num_limbs() + b.num_limbs());
+        self.add_simple_generator(BigUintMultiplicationGenerator {
+            a: a.limbs.clone(),
+            b: b.limbs.clone(),
+            product: product.limbs.clone(),
+        });
+
+        // a * b - product = 0
+        let mut columns = BigUintColumns::default();
+        columns.add_product(self, &a.limbs, &b.limbs, false);
+        columns.add_linear(self, &product.limbs, true);
+        columns.assert_zero(self);
+
+        product
+    }
+
+    /// Returns the quotient and remainder of the Euclidean division of `a` by `b`, with as many
+    /// limbs as `a` and `b` respectively. Results in an unsatisfiable instance if `b = 0`.
+    pub fn div_rem_biguint(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+    ) -> (BigUintTarget, BigUintTarget) {
+        self.div_rem_product_biguint(&[&a.limbs], b, a.num_limbs())
+    }
+
+    pub fn div_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        self.div_rem_biguint(a, b).0
+    }
+
+    pub fn rem_biguint(&mut self, a: &BigUintTarget, b: &BigUintTarget) -> BigUintTarget {
+        self.div_rem_biguint(a, b).1
+    }
+
+    /// Returns `a * b mod m`, with as many limbs as `m`. Results in an unsatisfiable instance if
+    /// `m = 0`.
+    pub fn mul_mod_biguint(
+        &mut self,
+        a: &BigUintTarget,
+        b: &BigUintTarget,
+        m: &BigUintTarget,
+    ) -> BigUintTarget {
+        let quotient_len = a.num_limbs() + b.num_limbs();
+        self.div_rem_product_biguint(&[&a.limbs, &b.limbs], m, quotient_len)
+            .1
+    }
+
+    /// Returns `base^exponent mod modulus`, with as many limbs as `modulus`, or zero if the modulus
+    /// is zero, as in the `MODEXP` precompile of EIP-198. This costs two modular multiplications
+    /// per limb bit of `exponent`.
+    pub fn modexp_biguint(
+        &mut self,
+        base: &BigUintTarget,
+        exponent: &BigUintTarget,
+        modulus: &BigUintTarget,
+    ) -> BigUintTarget {
+        // Everything is zero modulo one, so a zero modulus is replaced by one.
+        let limb_sum = self.add_many(&modulus.limbs);
+        let zero = self.zero();
+        let modulus_is_zero = self.is_equal(limb_sum, zero);
+        let mut modulus_limbs = modulus.limbs.clone();
+        modulus_limbs[0] = self.add(modulus_limbs[0], modulus_is_zero.target);
+        let modulus = BigUintTarget {
+            limbs: modulus_limbs,
+        };
+        let modulus_len = modulus.num_limbs();
+
+        // Both operands of each product are reduced, so the quotients fit in as many limbs as the
+        // modulus.
+        let base = self.rem_biguint(base, &modulus);
+        let one = self.constant_biguint(&BigUint::one());
+        let mut result = self.rem_biguint(&one, &modulus);
+        for bit in self.split_biguint_to_bits(exponent).into_iter().rev() {
+            result = self
+                .div_rem_product_biguint(&[&result.limbs, &result.limbs], &modulus, modulus_len)
+                .1;
+            let product = self
+                .div_rem_product_biguint(&[&result.limbs, &base.limbs], &modulus, modulus_len)
+                .1;
+            result = self.select_biguint(bit, &product, &result);
+        }
+        result
+    }
+
+    /// Selects `x` or `y` based on `b`, i.e., this returns `if b { x } else { y }`.
+    pub fn select_biguint(
+        &mut self,
+        b: BoolTarget,
+        x: &BigUintTarget,
+        y: &BigUintTarget,
+    ) -> BigUintTarget {
+        let num_limbs = x.num_limbs().max(y.num_limbs());
+        let x = self.pad_biguint_limbs(x, num_limbs);
+        let y = self.pad_biguint_limbs(y, num_limbs);
+        let limbs = x
+            .into_iter()
+            .zip(y)
+            .map(|(x_limb, y_limb)| self.select(b, x_limb, y_limb))
+            .collect();
+        BigUintTarget { limbs }
+    }
+
+    /// Returns the integer with the given little-endian bits.
+    pub fn biguint_from_le_bits(&mut self, bits: &[BoolTarget]) -> BigUintTarget {
+        assert!(!bits.is_empty(), "An integer needs at least one limb");
+        let limbs = bits
+            .chunks(NONNATIVE_LIMB_BITS)
+            .map(|chunk| self.le_sum(chunk.iter()))
+            .collect();
+        BigUintTarget { limbs }
+    }
+
+    /// Returns the little-endian bits of `x`, 16 per limb.
+    pub fn split_biguint_to_bits(&mut self, x: &BigUintTarget) -> Vec<BoolTarget> {
+        x.limbs
+            .iter()
+            .flat_map(|&limb| self.split_le(limb, NONNATIVE_LIMB_BITS))
+            .collect()
+    }
+
+    /// Returns the integer with the given little-endian bytes, which are range checked.
+    pub fn biguint_from_le_bytes(&mut self, bytes: &[Target]) -> BigUintTarget {
+        assert!(!bytes.is_empty(), "An integer needs at least one limb");
+        let byte_base = F::from_canonical_u16(1 << 8);
+        let limbs = bytes
+            .chunks(2)
+            .map(|chunk| {
+                for &byte in chunk {
+                    self.range_check(byte, 8);
+                }
+                match *chunk {
+                    [low, high] => self.mul_const_add(byte_base, high, low),
+                    [low] => low,
+                    _ => unreachable!(),
+                }
+            })
+            .collect();
+        BigUintTarget { limbs }
+    }
+
+    /// Returns the little-endian bytes of `x`, two per limb.
+    pub fn split_biguint_to_bytes(&mut self, x: &BigUintTarget) -> Vec<Target> {
+        let bits = self.split_biguint_to_bits(x);
+        bits.chunks(8)
+            .map(|byte_bits| self.le_sum(byte_bits.iter()))
+            .collect()
+    }
+
+    /// Hints `q` and `r` such that the product of the integers with limbs `factors`, of which there
+    /// are one or two, is `q * divisor + r` with `r < divisor`, checks this identity and returns
+    /// `(q, r)`. The quotient has `quotient_len` limbs, which must be enough for completeness.
+    fn div_rem_product_biguint(
+        &mut self,
+        factors: &[&[Target]],
+        divisor: &BigUintTarget,
+        quotient_len: usize,
+    ) -> (BigUintTarget, BigUintTarget) {
+        let quotient = self.add_virtual_biguint_target(quotient_len);
+        let remainder = self.add_virtual_biguint_target(divisor.num_limbs());
+        self.add_simple_generator(BigUintDivRemGenerator {
+            factors: factors.iter().map(|f| f.to_vec()).collect(),
+            divisor: divisor.limbs.clone(),
+            quotient: quotient.limbs.clone(),
+            remainder: remainder.limbs.clone(),
+        });
+
+        // prod(factors) - quotient * divisor - remainder = 0
+        let mut columns = BigUintColumns::default();
+        match factors {
+            [x] => columns.add_linear(self, x, false),
+            [a, b] => columns.add_product(self, a, b, false),
+            _ => panic!("Expected one or two factors"),
+        }
+        columns.add_product(self, &quotient.limbs, &divisor.limbs, true);
+        columns.add_linear(self, &remainder.limbs, true);
+        columns.assert_zero(self);
+
+        // The remainder is less than the divisor, which is thus nonzero.
+        let geq_divisor = self.limbs_geq(&remainder.limbs, &divisor.limbs);
+        self.assert_zero(geq_divisor.target);
+
+        (quotient, remainder)
+    }
+
+    /// Returns the limbs of `x`, padded with zeros to `num_limbs`.
+    fn pad_biguint_limbs(&mut self, x: &BigUintTarget, num_limbs: usize) -> Vec<Target> {
+        let zero = self.zero();
+        let mut limbs = x.limbs.clone();
+        limbs.resize(num_limbs.max(limbs.len()), zero);
+        limbs
+    }
+}
+
+/// Accumulates signed sums of limb vectors and of their products, column by column, keeping track
+/// of a bound on the columns.
+#[derive(Default)]
+struct BigUintColumns {
+    columns: Vec<Target>,
+    /// Whether any term is a product of limbs, and is thus less than `2^32` rather than `2^16`.
+    has_products: bool,
+    /// A bound on the number of terms in any column.
+    num_terms: usize,
+}
+
+impl BigUintColumns {
+    fn resize<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        len: usize,
+    ) {
+        if self.columns.len() < len {
+            let zero = builder.zero();
+            self.columns.resize(len, zero);
+        }
+    }
+
+    fn add_linear<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        x: &[Target],
+        negate: bool,
+    ) {
+        self.resize(builder, x.len());
+        for (column, &limb) in self.columns.iter_mut().zip(x) {
+            *column = if negate {
+                builder.sub(*column, limb)
+            } else {
+                builder.add(*column, limb)
+            };
+        }
+        self.num_terms += 1;
+    }
+
+    fn add_product<F: RichField + Extendable<D>, const D: usize>(
+        &mut self,
+        builder: &mut CircuitBuilder<F, D>,
+        a: &[Target],
+        b: &[Target],
+        negate: bool,
+    ) {
+        let sign = if negate { F::NEG_ONE } else { F::ONE };
+        self.resize(builder, a.len() + b.len() - 1);
+        for (i, &a_limb) in a.iter().enumerate() {
+            for (j, &b_limb) in b.iter().enumerate() {
+                self.columns[i + j] =
+                    builder.arithmetic(sign, F::ONE, a_limb, b_limb, self.columns[i + j]);
+            }
+        }
+        self.has_products = true;
+        self.num_terms += a.len().min(b.len());
+    }
+
+    /// Checks that the signed sum is zero.
+    fn assert_zero<F: RichField + Extendable<D>, const D: usize>(
+        self,
+        builder: &mut CircuitBuilder<F, D>,
+    ) {
+        let term_bits = if self.has_products {
+            2 * NONNATIVE_LIMB_BITS
+        } else {
+            NONNATIVE_LIMB_BITS
+        };
+        builder.assert_limb_columns_zero(&self.columns, term_bits + log2_ceil(self.num_terms));
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BigUintAdditionGenerator {
+    a: Vec<Target>,
+    b: Vec<Target>,
+    sum: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for BigUintAdditionGenerator
+{
+    fn id(&self) -> String {
+        "BigUintAdditionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.iter().chain(&self.b).copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = get_biguint(witness, &self.a);
+        let b = get_biguint(witness, &self.b);
+        set_biguint(out_buffer, &self.sum, &(a + b));
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a)?;
+        dst.write_target_vec(&self.b)?;
+        dst.write_target_vec(&self.sum)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_vec()?;
+        let b = src.read_target_vec()?;
+        let sum = src.read_target_vec()?;
+        Ok(Self { a, b, sum })
+    }
+}
+
+/// Computes `a - b`, or zero if `a < b`, which then fails the check.
+#[derive(Debug, Default)]
+pub struct BigUintSubtractionGenerator {
+    a: Vec<Target>,
+    b: Vec<Target>,
+    diff: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for BigUintSubtractionGenerator
+{
+    fn id(&self) -> String {
+        "BigUintSubtractionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.iter().chain(&self.b).copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = get_biguint(witness, &self.a);
+        let b = get_biguint(witness, &self.b);
+        let diff = if a >= b { a - b } else { BigUint::zero() };
+        set_biguint(out_buffer, &self.diff, &diff);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a)?;
+        dst.write_target_vec(&self.b)?;
+        dst.write_target_vec(&self.diff)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_vec()?;
+        let b = src.read_target_vec()?;
+        let diff = src.read_target_vec()?;
+        Ok(Self { a, b, diff })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BigUintMultiplicationGenerator {
+    a: Vec<Target>,
+    b: Vec<Target>,
+    product: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for BigUintMultiplicationGenerator
+{
+    fn id(&self) -> String {
+        "BigUintMultiplicationGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.iter().chain(&self.b).copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = get_biguint(witness, &self.a);
+        let b = get_biguint(witness, &self.b);
+        set_biguint(out_buffer, &self.product, &(a * b));
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a)?;
+        dst.write_target_vec(&self.b)?;
+        dst.write_target_vec(&self.product)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_vec()?;
+        let b = src.read_target_vec()?;
+        let product = src.read_target_vec()?;
+        Ok(Self { a, b, product })
+    }
+}
+
+/// Computes the quotient and remainder of the product of `factors` by `divisor`. A zero divisor
+/// leaves both zero, which then fails the check.
+#[derive(Debug, Default)]
+pub struct BigUintDivRemGenerator {
+    factors: Vec<Vec<Target>>,
+    divisor: Vec<Target>,
+    quotient: Vec<Target>,
+    remainder: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for BigUintDivRemGenerator
+{
+    fn id(&self) -> String {
+        "BigUintDivRemGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut deps = self.factors.concat();
+        deps.extend_from_slice(&self.divisor);
+        deps
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let dividend = self
+            .factors
+            .iter()
+            .map(|factor| get_biguint(witness, factor))
+            .product::<BigUint>();
+        let divisor = get_biguint(witness, &self.divisor);
+        let (quotient, remainder) = if divisor.is_zero() {
+            (BigUint::zero(), BigUint::zero())
+        } else {
+            dividend.div_rem(&divisor)
+        };
+
+        set_biguint(out_buffer, &self.quotient, &quotient);
+        set_biguint(out_buffer, &self.remainder, &remainder);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.factors.len())?;
+        for factor in &self.factors {
+            dst.write_target_vec(factor)?;
+        }
+        dst.write_target_vec(&self.divisor)?;
+        dst.write_target_vec(&self.quotient)?;
+        dst.write_target_vec(&self.remainder)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_factors = src.read_usize()?;
+        let factors = (0..num_factors)
+            .map(|_| src.read_target_vec())
+            .collect::<IoResult<Vec<_>>>()?;
+        let divisor = src.read_target_vec()?;
+        let quotient = src.read_target_vec()?;
+        let remainder = src.read_target_vec()?;
+        Ok(Self {
+            factors,
+            divisor,
+            quotient,
+            remainder,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use num::bigint::RandBigInt;
+    use num::{BigUint, Integer, One, Zero};
+    use rand::rngs::OsRng;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_biguint_arithmetic() -> Result<()> {
+        let x = OsRng.gen_biguint(300);
+        let y = OsRng.gen_biguint(130);
+        let (q, r) = x.div_rem(&y);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let xt = builder.add_virtual_biguint_target(20);
+        let yt = builder.add_virtual_biguint_target(9);
+        pw.set_biguint_target(&xt, &x);
+        pw.set_biguint_target(&yt, &y);
+
+        let (qt, rt) = builder.div_rem_biguint(&xt, &yt);
+        let results = [
+            (builder.add_biguint(&xt, &yt), &x + &y),
+            (builder.sub_biguint(&xt, &yt), &x - &y),
+            (builder.mul_biguint(&xt, &yt), &x * &y),
+            (qt, q),
+            (rt, r),
+            (builder.mul_mod_biguint(&xt, &xt, &yt), &x * &x % &y),
+        ];
+        for (result, expected) in results {
+            let expected = builder.constant_biguint(&expected);
+            builder.connect_biguint(&result, &expected);
+        }
+
+        let lt = builder.is_less_than_biguint(&yt, &xt);
+        builder.assert_one(lt.target);
+        let gt = builder.is_less_than_biguint(&xt, &yt);
+        builder.assert_zero(gt.target);
+        let eq = builder.is_equal_biguint(&xt, &xt);
+        builder.assert_one(eq.target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_biguint_conversions() -> Result<()> {
+        let x = OsRng.gen_biguint(200);
+        let bytes = x.to_bytes_le();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let xt = builder.add_virtual_biguint_target(13);
+        pw.set_biguint_target(&xt, &x);
+
+        let bits = builder.split_biguint_to_bits(&xt);
+        let from_bits = builder.biguint_from_le_bits(&bits);
+        builder.connect_biguint(&xt, &from_bits);
+
+        let byte_targets = builder.split_biguint_to_bytes(&xt);
+        for (i, &byte) in byte_targets.iter().enumerate() {
+            let expected =
+                builder.constant(F::from_canonical_u8(bytes.get(i).copied().unwrap_or(0)));
+            builder.connect(byte, expected);
+        }
+        let from_bytes = builder.biguint_from_le_bytes(&byte_targets[..bytes.len()]);
+        builder.connect_biguint(&xt, &from_bytes);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_modexp() -> Result<()> {
+        let base = OsRng.gen_biguint(300);
+        let exponent = OsRng.gen_biguint(40);
+        let modulus = OsRng.gen_biguint(256);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let base_t = builder.add_virtual_biguint_target(19);
+        let exponent_t = builder.add_virtual_biguint_target(3);
+        let modulus_t = builder.add_virtual_biguint_target(16);
+        let zero_t = builder.add_virtual_biguint_target(16);
+        pw.set_biguint_target(&base_t, &base);
+        pw.set_biguint_target(&exponent_t, &exponent);
+        pw.set_biguint_target(&modulus_t, &modulus);
+        pw.set_biguint_target(&zero_t, &BigUint::zero());
+
+        let result = builder.modexp_biguint(&base_t, &exponent_t, &modulus_t);
+        let expected = builder.constant_biguint(&base.modpow(&exponent, &modulus));
+        builder.connect_biguint(&result, &expected);
+
+        // A zero modulus gives zero, and a zero exponent gives one.
+        let result = builder.modexp_biguint(&base_t, &exponent_t, &zero_t);
+        let zero = builder.zero_biguint();
+        builder.connect_biguint(&result, &zero);
+        let result = builder.modexp_biguint(&base_t, &zero_t, &modulus_t);
+        let one = builder.constant_biguint(&BigUint::one());
+        builder.connect_biguint(&result, &one);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -0,0 +1,406 @@
+//! The BLAKE2s and BLAKE2b hashes over byte targets, without a key, with bitwise operations done
+//! by byte lookups.
+
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// The parameters distinguishing BLAKE2s from BLAKE2b.
+struct Blake2Params {
+    word_bytes: usize,
+    num_rounds: usize,
+    rotations: [usize; 4],
+    iv: [u64; 8],
+}
+
+impl Blake2Params {
+    fn block_bytes(&self) -> usize {
+        16 * self.word_bytes
+    }
+
+    fn max_digest_bytes(&self) -> usize {
+        8 * self.word_bytes
+    }
+}
+
+const BLAKE2S: Blake2Params = Blake2Params {
+    word_bytes: 4,
+    num_rounds: 10,
+    rotations: [16, 12, 8, 7],
+    iv: [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ],
+};
+
+const BLAKE2B: Blake2Params = Blake2Params {
+    word_bytes: 8,
+    num_rounds: 12,
+    rotations: [32, 24, 16, 63],
+    iv: [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ],
+};
+
+/// The message word permutations, used cyclically by the rounds.
+const BLAKE2_SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns the BLAKE2s hash of a fixed-length message, given as bytes, with a digest of
+    /// `digest_len` bytes, at most 32.
+    pub fn blake2s(&mut self, input: &[Target], digest_len: usize) -> Vec<Target> {
+        let length = self.constant(F::from_canonical_usize(input.len()));
+        self.blake2_variable_length(&BLAKE2S, input, length, digest_len)
+    }
+
+    /// Returns the BLAKE2s hash of the first `length` bytes of `input`, with a digest of
+    /// `digest_len` bytes, at most 32. The bytes of `input` past the length are ignored, and
+    /// `length` must be at most `input.len()`.
+    pub fn blake2s_variable_length(
+        &mut self,
+        input: &[Target],
+        length: Target,
+        digest_len: usize,
+    ) -> Vec<Target> {
+        self.blake2_variable_length(&BLAKE2S, input, length, digest_len)
+    }
+
+    /// Returns the BLAKE2b hash of a fixed-length message, given as bytes, with a digest of
+    /// `digest_len` bytes, at most 64.
+    pub fn blake2b(&mut self, input: &[Target], digest_len: usize) -> Vec<Target> {
+        let length = self.constant(F::from_canonical_usize(input.len()));
+        self.blake2_variable_length(&BLAKE2B, input, length, digest_len)
+    }
+
+    /// Returns the BLAKE2b hash of the first `length` bytes of `input`, with a digest of
+    /// `digest_len` bytes, at most 64. The bytes of `input` past the length are ignored, and
+    /// `length` must be at most `input.len()`.
+    pub fn blake2b_variable_length(
+        &mut self,
+        input: &[Target],
+        length: Target,
+        digest_len: usize,
+    ) -> Vec<Target> {
+        self.blake2_variable_length(&BLAKE2B, input, length, digest_len)
+    }
+
+    /// As with SHA-256, the cost is that of hashing a message of the maximal length, and all the
+    /// input bytes are range checked.
+    fn blake2_variable_length(
+        &mut self,
+        params: &Blake2Params,
+        input: &[Target],
+        length: Target,
+        digest_len: usize,
+    ) -> Vec<Target> {
+        assert!(
+            (1..=params.max_digest_bytes()).contains(&digest_len),
+            "Unsupported digest length of {} bytes",
+            digest_len
+        );
+        for &byte in input {
+            self.range_check_u8(byte);
+        }
+        let max_length = input.len();
+        let (is_length, is_before_length) = self.variable_length_flags(length, max_length);
+        let block_bytes = params.block_bytes();
+        let num_blocks = max_length.div_ceil(block_bytes).max(1);
+
+        // The block `k` is the last one if `block_bytes k < length <= block_bytes (k + 1)`, or if
+        // `k = 0` and the message is empty.
+        let is_last_block = (0..num_blocks)
+            .map(|k| {
+                let first = if k == 0 { 0 } else { block_bytes * k + 1 };
+                let last = (block_bytes * (k + 1)).min(max_length);
+                let sum = self.add_many(is_length[first..=last].iter().map(|b| b.target));
+                BoolTarget::new_unsafe(sum)
+            })
+            .collect::<Vec<_>>();
+
+        // The counter of the last block is the length, which is far below `2^32`.
+        let zero = self.zero();
+        let mut length_word = self.split_le_u8(length, 4);
+        length_word.resize(params.word_bytes, zero);
+
+        let mut h = params
+            .iv
+            .iter()
+            .map(|&iv| self.constant_word(iv, params.word_bytes))
+            .collect::<Vec<_>>();
+        // The parameter block, with no key.
+        h[0] = self.constant_word(
+            params.iv[0] ^ 0x01010000 ^ digest_len as u64,
+            params.word_bytes,
+        );
+
+        let mut final_states = Vec::with_capacity(num_blocks);
+        for (k, &is_last) in is_last_block.iter().enumerate() {
+            let block = (block_bytes * k..block_bytes * (k + 1))
+                .map(|i| self.masked_message_byte(input, &is_before_length, i))
+                .collect::<Vec<_>>();
+            let counter = (block_bytes * (k + 1)) as u64;
+            h = self.blake2_compress(params, &h, &block, counter, &length_word, is_last);
+            final_states.push(h.concat());
+        }
+
+        let mut digest = self.select_word_one_hot(&is_last_block, &final_states);
+        digest.truncate(digest_len);
+        digest
+    }
+
+    /// The BLAKE2 compression function. The counter is `counter`, or the length if `is_last` is
+    /// set, in which case the block is also flagged as the final one.
+    fn blake2_compress(
+        &mut self,
+        params: &Blake2Params,
+        h: &[Vec<Target>],
+        block: &[Target],
+        counter: u64,
+        length_word: &[Target],
+        is_last: BoolTarget,
+    ) -> Vec<Vec<Target>> {
+        let word_bytes = params.word_bytes;
+        let m = block
+            .chunks(word_bytes)
+            .map(|word| word.to_vec())
+            .collect::<Vec<_>>();
+
+        let mut v = h.to_vec();
+        for &iv in &params.iv {
+            v.push(self.constant_word(iv, word_bytes));
+        }
+
+        // v[12] ^= counter
+        let counter_word = self.constant_word(counter, word_bytes);
+        let counter_word = counter_word
+            .into_iter()
+            .zip(length_word)
+            .map(|(c, &l)| {
+                let diff = self.sub(l, c);
+                self.mul_add(is_last.target, diff, c)
+            })
+            .collect::<Vec<_>>();
+        v[12] = self.xor_word(&v[12], &counter_word);
+
+        // v[14] = !v[14] in the final block. It is a constant, so this is `c + is_last (255 - 2 c)`.
+        let v14 = params.iv[6];
+        v[14] = (0..word_bytes)
+            .map(|i| {
+                let c = (v14 >> (8 * i)) & 0xff;
+                let c_target = self.constant(F::from_canonical_u64(c));
+                let flip = F::from_canonical_u64(0xff) - F::from_canonical_u64(2 * c);
+                self.mul_const_add(flip, is_last.target, c_target)
+            })
+            .collect();
+
+        for round in 0..params.num_rounds {
+            let s = &BLAKE2_SIGMA[round % 10];
+            self.blake2_mix(params, &mut v, [0, 4, 8, 12], &m[s[0]], &m[s[1]]);
+            self.blake2_mix(params, &mut v, [1, 5, 9, 13], &m[s[2]], &m[s[3]]);
+            self.blake2_mix(params, &mut v, [2, 6, 10, 14], &m[s[4]], &m[s[5]]);
+            self.blake2_mix(params, &mut v, [3, 7, 11, 15], &m[s[6]], &m[s[7]]);
+            self.blake2_mix(params, &mut v, [0, 5, 10, 15], &m[s[8]], &m[s[9]]);
+            self.blake2_mix(params, &mut v, [1, 6, 11, 12], &m[s[10]], &m[s[11]]);
+            self.blake2_mix(params, &mut v, [2, 7, 8, 13], &m[s[12]], &m[s[13]]);
+            self.blake2_mix(params, &mut v, [3, 4, 9, 14], &m[s[14]], &m[s[15]]);
+        }
+
+        (0..8)
+            .map(|i| {
+                let h_v = self.xor_word(&h[i], &v[i]);
+                self.xor_word(&h_v, &v[i + 8])
+            })
+            .collect()
+    }
+
+    /// The mixing function `G`, on the words of `v` at the given indices.
+    fn blake2_mix(
+        &mut self,
+        params: &Blake2Params,
+        v: &mut [Vec<Target>],
+        [a, b, c, d]: [usize; 4],
+        x: &[Target],
+        y: &[Target],
+    ) {
+        let [r1, r2, r3, r4] = params.rotations;
+        for (m, r_d, r_b) in [(x, r1, r2), (y, r3, r4)] {
+            v[a] = self.add_words_wrapping(&[&v[a][..], &v[b][..], m]);
+            let d_xor_a = self.xor_word(&v[d], &v[a]);
+            v[d] = self.rotate_right_word(&d_xor_a, r_d);
+            v[c] = self.add_words_wrapping(&[&v[c], &v[d]]);
+            let b_xor_c = self.xor_word(&v[b], &v[c]);
+            v[b] = self.rotate_right_word(&b_xor_c, r_b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Proves the BLAKE2s or BLAKE2b hashes of the first `length` bytes of `0, 1, 2, ...`, for each
+    /// of the given lengths, with a maximal length of `max_length`.
+    fn test_blake2_lengths(
+        max_length: usize,
+        blake2b: bool,
+        cases: &[(usize, &str)],
+    ) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let input = builder.add_virtual_targets(max_length);
+        for (i, &t) in input.iter().enumerate() {
+            pw.set_target(t, F::from_canonical_usize(i));
+        }
+        for &(length, expected) in cases {
+            let length = builder.constant(F::from_canonical_usize(length));
+            let expected = bytes_from_hex(expected);
+            let digest = if blake2b {
+                builder.blake2b_variable_length(&input, length, expected.len())
+            } else {
+                builder.blake2s_variable_length(&input, length, expected.len())
+            };
+            for (byte, expected) in digest.into_iter().zip(expected) {
+                let expected = builder.constant(F::from_canonical_u8(expected));
+                builder.connect(byte, expected);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    fn bytes_from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_blake2s() -> Result<()> {
+        test_blake2_lengths(
+            3,
+            false,
+            &[
+                (
+                    0,
+                    "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9",
+                ),
+                (
+                    3,
+                    "e8f91c6ef232a041452ab0e149070cdd7dd1769e75b3a5921be37876c45c9900",
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_blake2s_two_blocks() -> Result<()> {
+        test_blake2_lengths(
+            70,
+            false,
+            &[
+                (
+                    0,
+                    "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9",
+                ),
+                (
+                    55,
+                    "f4495470f226c8c214be08fdfad4bc4a2a9dbea9136a210df0d4b64929e6fc14",
+                ),
+                (
+                    56,
+                    "e290dd270b467f34ab1c002d340fa016257ff19e5833fdbbf2cb401c3b2817de",
+                ),
+                (
+                    64,
+                    "56f34e8b96557e90c1f24b52d0c89d51086acf1b00f634cf1dde9233b8eaaa3e",
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    #[ignore] // Too slow to run on CI.
+    fn test_blake2s_variable_length() -> Result<()> {
+        test_blake2_lengths(
+            200,
+            false,
+            &[
+                (
+                    64,
+                    "56f34e8b96557e90c1f24b52d0c89d51086acf1b00f634cf1dde9233b8eaaa3e",
+                ),
+                (
+                    65,
+                    "1b53ee94aaf34e4b159d48de352c7f0661d0a40edff95a0b1639b4090e974472",
+                ),
+                (
+                    200,
+                    "6d244e1a06ce4ef578dd0f63aff0936706735119ca9c8d22d86c801414ab9741",
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_blake2b() -> Result<()> {
+        test_blake2_lengths(
+            3,
+            true,
+            &[
+                (0, "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"),
+                (3, "40a374727302d9a4769c17b5f409ff32f58aa24ff122d7603e4fda1509e919d4107a52c57570a6d94e50967aea573b11f86f473f537565c66f7039830a85d186"),
+            ],
+        )
+    }
+
+    #[test]
+    #[ignore] // Too slow to run on CI.
+    fn test_blake2b_variable_length() -> Result<()> {
+        test_blake2_lengths(
+            200,
+            true,
+            &[
+                (128, "2319e3789c47e2daa5fe807f61bec2a1a6537fa03f19ff32e87eecbfd64b7e0e8ccff439ac333b040f19b0c4ddd11a61e24ac1fe0f10a039806c5dcc0da3d115"),
+                (129, "f7f3c46ba2564ff4c4c162da1f5b605f9f1c4aa6a20652a9f9a337c1a2f5b9c9"),
+                (200, "fb3c1f0f56a56f8e316fdf5d853c8c872c39635d083634c3904fc3ac07d1b578e85ff0e480e92d44ade33b62e893ee32343e79ddf6ef292e89b582d312502314"),
+            ],
+        )
+    }
+}
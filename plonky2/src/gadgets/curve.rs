@@ -0,0 +1,349 @@
+//! Arithmetic on points of short Weierstrass curves over foreign fields.
+//!
+//! Points are in affine coordinates, and the addition formulas are incomplete: they do not handle
+//! the point at infinity, and adding two points with the same `x` coordinate results in an
+//! unsatisfiable instance. Scalar multiplications start from a fixed point with unknown discrete
+//! logarithm, so that an honest prover only hits these cases with negligible probability.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use keccak_hash::keccak;
+use num::BigUint;
+
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::field::extension::Extendable;
+use crate::field::types::Field;
+use crate::gadgets::nonnative::{NonNativeTarget, NONNATIVE_LIMB_BITS};
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// The number of scalar bits handled by each step of the windowed scalar multiplications.
+pub const WINDOW_BITS: usize = 4;
+
+/// A point of the curve `C`, other than the point at infinity.
+#[derive(Clone, Debug)]
+pub struct AffinePointTarget<C: Curve> {
+    pub x: NonNativeTarget<C::BaseField>,
+    pub y: NonNativeTarget<C::BaseField>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn constant_affine_point<C: Curve>(
+        &mut self,
+        point: AffinePoint<C>,
+    ) -> AffinePointTarget<C> {
+        assert!(
+            !point.zero,
+            "The point at infinity has no affine coordinates"
+        );
+        AffinePointTarget {
+            x: self.constant_nonnative(point.x),
+            y: self.constant_nonnative(point.y),
+        }
+    }
+
+    /// Adds a point whose coordinates are canonical and which is checked to be on the curve.
+    pub fn add_virtual_affine_point_target<C: Curve>(&mut self) -> AffinePointTarget<C> {
+        let x = self.add_virtual_nonnative_target();
+        let y = self.add_virtual_nonnative_target();
+        let point = AffinePointTarget { x, y };
+        self.curve_assert_valid(&point);
+        point
+    }
+
+    pub fn connect_affine_point<C: Curve>(
+        &mut self,
+        lhs: &AffinePointTarget<C>,
+        rhs: &AffinePointTarget<C>,
+    ) {
+        self.connect_nonnative(&lhs.x, &rhs.x);
+        self.connect_nonnative(&lhs.y, &rhs.y);
+    }
+
+    /// Checks that `y^2 = x^3 + A x + B`.
+    pub fn curve_assert_valid<C: Curve>(&mut self, p: &AffinePointTarget<C>) {
+        let y_squared = self.mul_nonnative(&p.y, &p.y);
+        let x_squared = self.mul_nonnative(&p.x, &p.x);
+        let x_cubed = self.mul_nonnative(&x_squared, &p.x);
+        let mut rhs = x_cubed;
+        if C::A.is_nonzero() {
+            let a = self.constant_nonnative(C::A);
+            let a_x = self.mul_nonnative(&a, &p.x);
+            rhs = self.add_nonnative(&rhs, &a_x);
+        }
+        let b = self.constant_nonnative(C::B);
+        let rhs = self.add_nonnative(&rhs, &b);
+        self.connect_nonnative(&y_squared, &rhs);
+    }
+
+    pub fn curve_neg<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: self.neg_nonnative(&p.y),
+        }
+    }
+
+    /// Returns `if b { p } else { q }`.
+    pub fn curve_select<C: Curve>(
+        &mut self,
+        b: BoolTarget,
+        p: &AffinePointTarget<C>,
+        q: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        AffinePointTarget {
+            x: self.select_nonnative(b, &p.x, &q.x),
+            y: self.select_nonnative(b, &p.y, &q.y),
+        }
+    }
+
+    /// Returns `-p` if `b` is set, and `p` otherwise.
+    pub fn curve_conditional_neg<C: Curve>(
+        &mut self,
+        b: BoolTarget,
+        p: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        let neg_y = self.neg_nonnative(&p.y);
+        AffinePointTarget {
+            x: p.x.clone(),
+            y: self.select_nonnative(b, &neg_y, &p.y),
+        }
+    }
+
+    /// Returns `2 p`. As `C` has prime order, no point has a zero `y` coordinate, which the slope
+    /// computation would reject.
+    pub fn curve_double<C: Curve>(&mut self, p: &AffinePointTarget<C>) -> AffinePointTarget<C> {
+        // lambda = (3 x^2 + A) / (2 y)
+        let x_squared = self.mul_nonnative(&p.x, &p.x);
+        let two_x_squared = self.add_nonnative(&x_squared, &x_squared);
+        let mut numerator = self.add_nonnative(&two_x_squared, &x_squared);
+        if C::A.is_nonzero() {
+            let a = self.constant_nonnative(C::A);
+            numerator = self.add_nonnative(&numerator, &a);
+        }
+        let two_y = self.add_nonnative(&p.y, &p.y);
+        let lambda = self.div_nonnative(&numerator, &two_y);
+
+        self.curve_add_with_slope(p, &p.x, &lambda)
+    }
+
+    /// Returns `p + q`, for points with distinct `x` coordinates, i.e. `p != ±q`. Results in an
+    /// unsatisfiable instance otherwise.
+    pub fn curve_add<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        q: &AffinePointTarget<C>,
+    ) -> AffinePointTarget<C> {
+        // lambda = (q.y - p.y) / (q.x - p.x)
+        let dy = self.sub_nonnative(&q.y, &p.y);
+        let dx = self.sub_nonnative(&q.x, &p.x);
+        let lambda = self.div_nonnative(&dy, &dx);
+
+        self.curve_add_with_slope(p, &q.x, &lambda)
+    }
+
+    /// Computes `n p`. Neither `n` nor `n p` may be zero.
+    pub fn curve_scalar_mul_windowed<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        n: &NonNativeTarget<C::ScalarField>,
+    ) -> AffinePointTarget<C> {
+        let table = self.curve_window_table(p);
+        let windows = self.split_limbs_to_windows(n.limbs());
+        self.curve_msm_windowed(&[table], &[windows])
+    }
+
+    /// Returns `p + q`, given the `x` coordinate of `q` and the slope `lambda` of the line through
+    /// `p` and `q`, or of the tangent at `p` if they are equal.
+    fn curve_add_with_slope<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+        q_x: &NonNativeTarget<C::BaseField>,
+        lambda: &NonNativeTarget<C::BaseField>,
+    ) -> AffinePointTarget<C> {
+        // x3 = lambda^2 - p.x - q.x, y3 = lambda (p.x - x3) - p.y
+        let lambda_squared = self.mul_nonnative(lambda, lambda);
+        let x3 = self.sub_nonnative(&lambda_squared, &p.x);
+        let x3 = self.sub_nonnative(&x3, q_x);
+        let dx = self.sub_nonnative(&p.x, &x3);
+        let y3 = self.mul_nonnative(lambda, &dx);
+        let y3 = self.sub_nonnative(&y3, &p.y);
+        AffinePointTarget { x: x3, y: y3 }
+    }
+
+    /// Returns `[p, p, 2 p, ..., (2^WINDOW_BITS - 1) p]`. The first entry stands in for `0 p`,
+    /// which [`CircuitBuilder::curve_msm_windowed`] never adds.
+    pub(crate) fn curve_window_table<C: Curve>(
+        &mut self,
+        p: &AffinePointTarget<C>,
+    ) -> Vec<AffinePointTarget<C>> {
+        let mut table = vec![p.clone(), p.clone(), self.curve_double(p)];
+        for _ in 3..1 << WINDOW_BITS {
+            let next = self.curve_add(table.last().unwrap(), p);
+            table.push(next);
+        }
+        table
+    }
+
+    /// Returns the little-endian `WINDOW_BITS`-bit windows of the integer with the given
+    /// little-endian 16-bit limbs.
+    pub(crate) fn split_limbs_to_windows(&mut self, limbs: &[Target]) -> Vec<Target> {
+        let mut windows = Vec::with_capacity(limbs.len() * NONNATIVE_LIMB_BITS / WINDOW_BITS);
+        for &limb in limbs {
+            let bits = self.split_le(limb, NONNATIVE_LIMB_BITS);
+            for window_bits in bits.chunks(WINDOW_BITS) {
+                windows.push(self.le_sum(window_bits.iter()));
+            }
+        }
+        windows
+    }
+
+    /// Returns `sum_i k_i P_i`, given the window table of each `P_i` and the little-endian
+    /// `WINDOW_BITS`-bit windows of each `k_i`. All scalars must have the same number of windows.
+    ///
+    /// The sum is accumulated from the fixed point `S` of [`msm_offset_point`] with shared
+    /// doublings, and `2^(WINDOW_BITS (m - 1)) S`, where `m` is the number of windows, is
+    /// subtracted at the end. The sum must not be zero.
+    pub(crate) fn curve_msm_windowed<C: Curve>(
+        &mut self,
+        tables: &[Vec<AffinePointTarget<C>>],
+        windows: &[Vec<Target>],
+    ) -> AffinePointTarget<C> {
+        assert_eq!(tables.len(), windows.len(), "Mismatched tables and scalars");
+        let num_windows = windows[0].len();
+        assert!(
+            windows.iter().all(|w| w.len() == num_windows),
+            "Scalars have different numbers of windows"
+        );
+
+        let zero = self.zero();
+        let offset = msm_offset_point::<C>();
+        let mut result = self.constant_affine_point(offset);
+        for i in (0..num_windows).rev() {
+            if i != num_windows - 1 {
+                for _ in 0..WINDOW_BITS {
+                    result = self.curve_double(&result);
+                }
+            }
+            for (table, windows) in tables.iter().zip(windows) {
+                let to_add = self.curve_random_access(windows[i], table);
+                let sum = self.curve_add(&result, &to_add);
+                let is_zero = self.is_equal(windows[i], zero);
+                result = self.curve_select(is_zero, &result, &sum);
+            }
+        }
+
+        let correction = (0..WINDOW_BITS * (num_windows - 1)).fold(offset, |p, _| p.double());
+        let neg_correction = self.constant_affine_point(-correction);
+        self.curve_add(&result, &neg_correction)
+    }
+
+    /// Returns `table[index]`, for a table of `2^WINDOW_BITS` points.
+    fn curve_random_access<C: Curve>(
+        &mut self,
+        index: Target,
+        table: &[AffinePointTarget<C>],
+    ) -> AffinePointTarget<C> {
+        let num_limbs = NonNativeTarget::<C::BaseField>::num_limbs();
+        let x = (0..num_limbs)
+            .map(|i| self.random_access(index, table.iter().map(|p| p.x.limbs[i]).collect()))
+            .collect();
+        let y = (0..num_limbs)
+            .map(|i| self.random_access(index, table.iter().map(|p| p.y.limbs[i]).collect()))
+            .collect();
+        AffinePointTarget {
+            x: NonNativeTarget::from_limbs(x),
+            y: NonNativeTarget::from_limbs(y),
+        }
+    }
+}
+
+/// A point with no known discrete logarithm, found by hashing a fixed string to an `x`
+/// coordinate and incrementing it until it is on the curve.
+fn msm_offset_point<C: Curve>() -> AffinePoint<C> {
+    let hash = keccak(b"plonky2 curve msm offset point");
+    let mut x = C::BaseField::from_noncanonical_biguint(
+        BigUint::from_bytes_le(&hash.0) % C::BaseField::order(),
+    );
+    loop {
+        if let Some(point) = AffinePoint::lift_x(x, false) {
+            return point;
+        }
+        x += C::BaseField::ONE;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::curve_types::{Curve, CurveScalar};
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_curve_add_double_neg() -> Result<()> {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let p = CurveScalar::<Secp256K1>(Secp256K1Scalar::rand()) * g;
+        let q = CurveScalar::<Secp256K1>(Secp256K1Scalar::rand()) * g;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let pt = builder.add_virtual_affine_point_target::<Secp256K1>();
+        let qt = builder.add_virtual_affine_point_target::<Secp256K1>();
+        pw.set_affine_point_target(&pt, p);
+        pw.set_affine_point_target(&qt, q);
+
+        let results = [
+            (builder.curve_add(&pt, &qt), p + q),
+            (builder.curve_double(&pt), p.double()),
+            (builder.curve_neg(&pt), -p),
+        ];
+        for (result, expected) in results {
+            let expected = builder.constant_affine_point(expected);
+            builder.connect_affine_point(&result, &expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[ignore] // Too slow to run on CI.
+    fn test_curve_scalar_mul_windowed() -> Result<()> {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let p = CurveScalar::<Secp256K1>(Secp256K1Scalar::rand()) * g;
+        let n = Secp256K1Scalar::rand();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let pt = builder.add_virtual_affine_point_target::<Secp256K1>();
+        let nt = builder.add_virtual_nonnative_target();
+        pw.set_affine_point_target(&pt, p);
+        pw.set_nonnative_target(&nt, n);
+
+        let result = builder.curve_scalar_mul_windowed(&pt, &nt);
+        let expected = builder.constant_affine_point(CurveScalar::<Secp256K1>(n) * p);
+        builder.connect_affine_point(&result, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
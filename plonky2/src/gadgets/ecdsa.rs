@@ -0,0 +1,279 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::curve::secp256k1::Secp256K1;
+use crate::field::extension::Extendable;
+use crate::field::secp256k1_base::Secp256K1Base;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::field::types::{Field, PrimeField};
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::nonnative::{
+    biguint_from_u16_limbs, u16_limbs_of, NonNativeTarget, NONNATIVE_LIMB_BITS,
+};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+#[derive(Clone, Debug)]
+pub struct ECDSAPublicKeyTarget<C: Curve>(pub AffinePointTarget<C>);
+
+#[derive(Clone, Debug)]
+pub struct ECDSASignatureTarget<C: Curve> {
+    pub r: NonNativeTarget<C::ScalarField>,
+    pub s: NonNativeTarget<C::ScalarField>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Checks that `sig` is a valid signature of the message hash `msg` under `pk`, which must be
+    /// on the curve, as the points of [`CircuitBuilder::add_virtual_affine_point_target`] are.
+    pub fn verify_ecdsa(
+        &mut self,
+        msg: &NonNativeTarget<Secp256K1Scalar>,
+        sig: &ECDSASignatureTarget<Secp256K1>,
+        pk: &ECDSAPublicKeyTarget<Secp256K1>,
+    ) {
+        let ECDSASignatureTarget { r, s } = sig;
+        self.assert_nonzero_nonnative(r);
+
+        // (u1 G + u2 pk).x = r mod n, where u1 = msg / s and u2 = r / s.
+        let u1 = self.div_nonnative(msg, s);
+        let u2 = self.div_nonnative(r, s);
+        let g = self.constant_affine_point(Secp256K1::GENERATOR_AFFINE);
+        let point = self.glv_msm(&[(g, u1), (pk.0.clone(), u2)]);
+        let x = self.reduce_nonnative::<Secp256K1Scalar>(point.x.limbs());
+        self.connect_nonnative(&x, r);
+    }
+
+    /// Recovers the public key of a signature of the message hash `msg`, given the parity of the
+    /// `y` coordinate of the nonce point. As in
+    /// [`recover_public_key`](crate::curve::ecdsa::recover_public_key), the `x` coordinate of the
+    /// nonce point is assumed to be `r`.
+    pub fn recover_public_key(
+        &mut self,
+        msg: &NonNativeTarget<Secp256K1Scalar>,
+        sig: &ECDSASignatureTarget<Secp256K1>,
+        y_is_odd: BoolTarget,
+    ) -> ECDSAPublicKeyTarget<Secp256K1> {
+        let ECDSASignatureTarget { r, s } = sig;
+        self.assert_nonzero_nonnative(s);
+
+        // As r < n < p, its limbs are those of a canonical base field element.
+        let x = NonNativeTarget::<Secp256K1Base>::from_limbs(r.limbs().to_vec());
+        let y = self.add_virtual_nonnative_target::<Secp256K1Base>();
+        self.add_simple_generator(Secp256K1LiftXGenerator {
+            x: x.limbs().to_vec(),
+            y_is_odd,
+            y: y.limbs().to_vec(),
+        });
+        let y_low_bits = self.split_le(y.limbs()[0], NONNATIVE_LIMB_BITS);
+        self.connect(y_low_bits[0].target, y_is_odd.target);
+        let nonce_point = AffinePointTarget { x, y };
+        self.curve_assert_valid(&nonce_point);
+
+        // pk = (s / r) R - (msg / r) G
+        let u1 = self.div_nonnative(s, r);
+        let msg_over_r = self.div_nonnative(msg, r);
+        let u2 = self.neg_nonnative(&msg_over_r);
+        let g = self.constant_affine_point(Secp256K1::GENERATOR_AFFINE);
+        ECDSAPublicKeyTarget(self.glv_msm(&[(nonce_point, u1), (g, u2)]))
+    }
+
+    /// Returns the 20 bytes of the Ethereum address of `pk`, i.e. the last bytes of the
+    /// Keccak-256 hash of its big-endian coordinates.
+    pub fn eth_address(&mut self, pk: &ECDSAPublicKeyTarget<Secp256K1>) -> Vec<Target> {
+        let mut encoding = Vec::with_capacity(2 * Secp256K1Base::BITS);
+        for coordinate in [&pk.0.x, &pk.0.y] {
+            let bits = self.split_nonnative_to_bits(coordinate);
+            // Big-endian bytes, each with its bits in little-endian order.
+            for byte in bits.chunks(8).rev() {
+                encoding.extend_from_slice(byte);
+            }
+        }
+
+        let hash = self.keccak256(&encoding);
+        hash[12 * 8..]
+            .chunks(8)
+            .map(|byte| self.le_sum(byte.iter()))
+            .collect()
+    }
+
+    /// Returns the 20 bytes of the Ethereum address of the signer of the message hash `msg`, as
+    /// the `ECRECOVER` precompile does, given the parity of the `y` coordinate of the nonce point,
+    /// i.e. `v - 27`.
+    pub fn ecrecover(
+        &mut self,
+        msg: &NonNativeTarget<Secp256K1Scalar>,
+        sig: &ECDSASignatureTarget<Secp256K1>,
+        y_is_odd: BoolTarget,
+    ) -> Vec<Target> {
+        let pk = self.recover_public_key(msg, sig, y_is_odd);
+        self.eth_address(&pk)
+    }
+}
+
+/// Computes the `y` coordinate of the secp256k1 point with a given `x` coordinate and `y` parity.
+#[derive(Debug, Default)]
+pub struct Secp256K1LiftXGenerator {
+    x: Vec<Target>,
+    y_is_odd: BoolTarget,
+    y: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for Secp256K1LiftXGenerator
+{
+    fn id(&self) -> String {
+        "Secp256K1LiftXGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        let mut deps = self.x.clone();
+        deps.push(self.y_is_odd.target);
+        deps
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = Secp256K1Base::from_noncanonical_biguint(biguint_from_u16_limbs(
+            &witness.get_targets(&self.x),
+        ));
+        let y_is_odd = witness.get_bool_target(self.y_is_odd);
+        // If `x` is not on the curve, any `y` fails the curve check.
+        let y = AffinePoint::<Secp256K1>::lift_x(x, y_is_odd).map_or(Secp256K1Base::ZERO, |p| p.y);
+
+        out_buffer.set_target_arr(
+            &self.y,
+            &u16_limbs_of(&y.to_canonical_biguint(), self.y.len()),
+        );
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.x)?;
+        dst.write_target_bool(self.y_is_odd)?;
+        dst.write_target_vec(&self.y)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let x = src.read_target_vec()?;
+        let y_is_odd = src.read_target_bool()?;
+        let y = src.read_target_vec()?;
+        Ok(Self { x, y_is_odd, y })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use num::BigUint;
+
+    use crate::curve::ecdsa::{
+        eth_address, sign_message, ECDSAPublicKey, ECDSASecretKey, ECDSASignature,
+    };
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::gadgets::ecdsa::{ECDSAPublicKeyTarget, ECDSASignatureTarget};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn scalar_from_hex(hex: &[u8]) -> Secp256K1Scalar {
+        Secp256K1Scalar::from_noncanonical_biguint(BigUint::parse_bytes(hex, 16).unwrap())
+    }
+
+    fn test_ecrecover(
+        msg: Secp256K1Scalar,
+        sig: ECDSASignature<Secp256K1>,
+        y_is_odd: bool,
+        expected_address: [u8; 20],
+    ) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let msg_target = builder.add_virtual_nonnative_target();
+        let sig_target = ECDSASignatureTarget {
+            r: builder.add_virtual_nonnative_target(),
+            s: builder.add_virtual_nonnative_target(),
+        };
+        let y_is_odd_target = builder.add_virtual_bool_target_safe();
+        pw.set_nonnative_target(&msg_target, msg);
+        pw.set_nonnative_target(&sig_target.r, sig.r);
+        pw.set_nonnative_target(&sig_target.s, sig.s);
+        pw.set_bool_target(y_is_odd_target, y_is_odd);
+
+        let address = builder.ecrecover(&msg_target, &sig_target, y_is_odd_target);
+        for (byte, expected) in address.into_iter().zip(expected_address) {
+            let expected = builder.constant(F::from_canonical_u8(expected));
+            builder.connect(byte, expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[ignore] // Too slow to run on CI.
+    fn test_verify_ecdsa() -> Result<()> {
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<Secp256K1>(Secp256K1Scalar::rand());
+        let ECDSAPublicKey(pk) = sk.to_public();
+        let (sig, _) = sign_message(msg, sk);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let msg_target = builder.constant_nonnative(msg);
+        let pk_target = ECDSAPublicKeyTarget(builder.add_virtual_affine_point_target());
+        let sig_target = ECDSASignatureTarget {
+            r: builder.add_virtual_nonnative_target(),
+            s: builder.add_virtual_nonnative_target(),
+        };
+        pw.set_affine_point_target(&pk_target.0, pk);
+        pw.set_nonnative_target(&sig_target.r, sig.r);
+        pw.set_nonnative_target(&sig_target.s, sig.s);
+
+        builder.verify_ecdsa(&msg_target, &sig_target, &pk_target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[ignore] // Too slow to run on CI.
+    fn test_ecrecover_random() -> Result<()> {
+        let msg = Secp256K1Scalar::rand();
+        let sk = ECDSASecretKey::<Secp256K1>(Secp256K1Scalar::rand());
+        let (sig, y_is_odd) = sign_message(msg, sk);
+        test_ecrecover(msg, sig, y_is_odd, eth_address(&sk.to_public()))
+    }
+
+    /// The example transaction of EIP-155, signed with the key `0x4646...46`.
+    #[test]
+    #[ignore] // Too slow to run on CI.
+    fn test_ecrecover_eip155_vector() -> Result<()> {
+        let msg =
+            scalar_from_hex(b"daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53");
+        let sig = ECDSASignature {
+            r: scalar_from_hex(b"28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276"),
+            s: scalar_from_hex(b"67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"),
+        };
+        let address = BigUint::parse_bytes(b"9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f", 16)
+            .unwrap()
+            .to_bytes_be();
+        test_ecrecover(msg, sig, false, address.try_into().unwrap())
+    }
+}
@@ -0,0 +1,336 @@
+//! Arithmetic on points of the ecGFp5 curve, whose coordinates are native
+//! [`QuinticExtensionTarget`]s, so that it is much cheaper than that of foreign curves.
+//!
+//! As in [`gadgets::curve`](crate::gadgets::curve), points are in affine coordinates and the
+//! addition formulas are incomplete. The points of
+//! [`CircuitBuilder::add_virtual_ecgfp5_point_target`] are in the subgroup of odd order `n`, in
+//! which no point other than zero has a zero `y` coordinate, and `p + q` only fails for `p = ±q`.
+//! Scalar multiplications start from a fixed point with unknown discrete logarithm, so that an
+//! honest prover only hits these cases with negligible probability.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use keccak_hash::keccak;
+
+use crate::curve::ecgfp5::{
+    ecgfp5_rhs, quintic_is_square, quintic_sqrt, EcGFp5Base, EcGFp5Point, ECGFP5_A, ECGFP5_B,
+};
+use crate::field::ecgfp5_scalar::EcGFp5Scalar;
+use crate::field::extension::quintic::QuinticExtension;
+use crate::field::extension::Extendable;
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::Field;
+use crate::gadgets::curve::WINDOW_BITS;
+use crate::gadgets::nonnative::NonNativeTarget;
+use crate::gadgets::quintic::QuinticExtensionTarget;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A point of the ecGFp5 curve, other than the point at infinity.
+#[derive(Copy, Clone, Debug)]
+pub struct EcGFp5PointTarget {
+    pub x: QuinticExtensionTarget,
+    pub y: QuinticExtensionTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn constant_ecgfp5_point(&mut self, point: EcGFp5Point) -> EcGFp5PointTarget {
+        assert!(
+            !point.zero,
+            "The point at infinity has no affine coordinates"
+        );
+        EcGFp5PointTarget {
+            x: self.constant_quintic_extension(point.x),
+            y: self.constant_quintic_extension(point.y),
+        }
+    }
+
+    /// Adds a point which is checked to be on the curve and in the subgroup of order `n`.
+    pub fn add_virtual_ecgfp5_point_target(&mut self) -> EcGFp5PointTarget {
+        let x = self.add_virtual_quintic_extension_target();
+        let y = self.add_virtual_quintic_extension_target();
+        let point = EcGFp5PointTarget { x, y };
+        self.ecgfp5_assert_valid(&point);
+        point
+    }
+
+    pub fn connect_ecgfp5_point(&mut self, lhs: &EcGFp5PointTarget, rhs: &EcGFp5PointTarget) {
+        self.connect_quintic_extension(lhs.x, rhs.x);
+        self.connect_quintic_extension(lhs.y, rhs.y);
+    }
+
+    /// Checks that `y^2 = x^3 + A x^2 + B x` and that `x` is a nonzero square, i.e. that the point
+    /// is in the subgroup of order `n`.
+    pub fn ecgfp5_assert_valid(&mut self, p: &EcGFp5PointTarget) {
+        let y_squared = self.square_quintic_extension(p.y);
+        let a = self.constant_quintic_extension(ECGFP5_A);
+        let b = self.constant_quintic_extension(ECGFP5_B);
+        let rhs = self.add_quintic_extension(p.x, a);
+        let rhs = self.mul_quintic_extension(rhs, p.x);
+        let rhs = self.add_quintic_extension(rhs, b);
+        let rhs = self.mul_quintic_extension(rhs, p.x);
+        self.connect_quintic_extension(y_squared, rhs);
+
+        // This excludes the point `(0, 0)` of order 2.
+        self.inverse_quintic_extension(p.x);
+        self.assert_square_quintic_extension(p.x);
+    }
+
+    pub fn ecgfp5_neg(&mut self, p: &EcGFp5PointTarget) -> EcGFp5PointTarget {
+        EcGFp5PointTarget {
+            x: p.x,
+            y: self.neg_quintic_extension(p.y),
+        }
+    }
+
+    /// Returns `if b { p } else { q }`.
+    pub fn ecgfp5_select(
+        &mut self,
+        b: BoolTarget,
+        p: &EcGFp5PointTarget,
+        q: &EcGFp5PointTarget,
+    ) -> EcGFp5PointTarget {
+        EcGFp5PointTarget {
+            x: self.select_quintic_extension(b, p.x, q.x),
+            y: self.select_quintic_extension(b, p.y, q.y),
+        }
+    }
+
+    /// Returns `2 p`.
+    pub fn ecgfp5_double(&mut self, p: &EcGFp5PointTarget) -> EcGFp5PointTarget {
+        // lambda = (3 x^2 + 2 A x + B) / (2 y)
+        let x_squared = self.square_quintic_extension(p.x);
+        let three_x_squared = self.mul_const_quintic_extension(F::from_canonical_u8(3), x_squared);
+        let two_a = self.constant_quintic_extension(ECGFP5_A.double());
+        let two_a_x = self.mul_quintic_extension(two_a, p.x);
+        let b = self.constant_quintic_extension(ECGFP5_B);
+        let numerator = self.add_quintic_extension(three_x_squared, two_a_x);
+        let numerator = self.add_quintic_extension(numerator, b);
+        let two_y = self.mul_const_quintic_extension(F::TWO, p.y);
+        let lambda = self.div_quintic_extension(numerator, two_y);
+
+        self.ecgfp5_add_with_slope(p, p.x, lambda)
+    }
+
+    /// Returns `p + q`, for points with distinct `x` coordinates, i.e. `p != ±q`. Results in an
+    /// unsatisfiable instance otherwise.
+    pub fn ecgfp5_add(
+        &mut self,
+        p: &EcGFp5PointTarget,
+        q: &EcGFp5PointTarget,
+    ) -> EcGFp5PointTarget {
+        // lambda = (q.y - p.y) / (q.x - p.x)
+        let dy = self.sub_quintic_extension(q.y, p.y);
+        let dx = self.sub_quintic_extension(q.x, p.x);
+        let lambda = self.div_quintic_extension(dy, dx);
+
+        self.ecgfp5_add_with_slope(p, q.x, lambda)
+    }
+
+    /// Computes `n p`. Neither `n` nor `n p` may be zero.
+    pub fn ecgfp5_scalar_mul_windowed(
+        &mut self,
+        p: &EcGFp5PointTarget,
+        n: &NonNativeTarget<EcGFp5Scalar>,
+    ) -> EcGFp5PointTarget {
+        let table = self.ecgfp5_window_table(p);
+        let windows = self.split_limbs_to_windows(n.limbs());
+        self.ecgfp5_msm_windowed(&[table], &[windows])
+    }
+
+    /// Returns `p + q`, given the `x` coordinate of `q` and the slope `lambda` of the line through
+    /// `p` and `q`, or of the tangent at `p` if they are equal.
+    fn ecgfp5_add_with_slope(
+        &mut self,
+        p: &EcGFp5PointTarget,
+        q_x: QuinticExtensionTarget,
+        lambda: QuinticExtensionTarget,
+    ) -> EcGFp5PointTarget {
+        // x3 = lambda^2 - A - p.x - q.x, y3 = lambda (p.x - x3) - p.y
+        let lambda_squared = self.square_quintic_extension(lambda);
+        let a = self.constant_quintic_extension(ECGFP5_A);
+        let x3 = self.sub_quintic_extension(lambda_squared, a);
+        let x3 = self.sub_quintic_extension(x3, p.x);
+        let x3 = self.sub_quintic_extension(x3, q_x);
+        let dx = self.sub_quintic_extension(p.x, x3);
+        let y3 = self.mul_quintic_extension(lambda, dx);
+        let y3 = self.sub_quintic_extension(y3, p.y);
+        EcGFp5PointTarget { x: x3, y: y3 }
+    }
+
+    /// Returns `[p, p, 2 p, ..., (2^WINDOW_BITS - 1) p]`. The first entry stands in for `0 p`,
+    /// which [`CircuitBuilder::ecgfp5_msm_windowed`] never adds.
+    pub(crate) fn ecgfp5_window_table(&mut self, p: &EcGFp5PointTarget) -> Vec<EcGFp5PointTarget> {
+        let mut table = vec![*p, *p, self.ecgfp5_double(p)];
+        for _ in 3..1 << WINDOW_BITS {
+            let next = self.ecgfp5_add(table.last().unwrap(), p);
+            table.push(next);
+        }
+        table
+    }
+
+    /// The window table of a constant point, computed outside the circuit.
+    pub(crate) fn ecgfp5_constant_window_table(
+        &mut self,
+        p: EcGFp5Point,
+    ) -> Vec<EcGFp5PointTarget> {
+        let mut multiple = p;
+        let mut table = vec![self.constant_ecgfp5_point(p)];
+        for _ in 1..1 << WINDOW_BITS {
+            table.push(self.constant_ecgfp5_point(multiple));
+            multiple = multiple + p;
+        }
+        table
+    }
+
+    /// Returns `sum_i k_i P_i`, given the window table of each `P_i` and the little-endian
+    /// `WINDOW_BITS`-bit windows of each `k_i`, as
+    /// [`CircuitBuilder::curve_msm_windowed`] does for curves over foreign fields. Results in an
+    /// unsatisfiable instance if the sum is zero, as the last addition then adds a point to its
+    /// opposite.
+    pub(crate) fn ecgfp5_msm_windowed(
+        &mut self,
+        tables: &[Vec<EcGFp5PointTarget>],
+        windows: &[Vec<Target>],
+    ) -> EcGFp5PointTarget {
+        assert_eq!(tables.len(), windows.len(), "Mismatched tables and scalars");
+        let num_windows = windows[0].len();
+        assert!(
+            windows.iter().all(|w| w.len() == num_windows),
+            "Scalars have different numbers of windows"
+        );
+
+        let zero = self.zero();
+        let offset = ecgfp5_msm_offset_point();
+        let mut result = self.constant_ecgfp5_point(offset);
+        for i in (0..num_windows).rev() {
+            if i != num_windows - 1 {
+                for _ in 0..WINDOW_BITS {
+                    result = self.ecgfp5_double(&result);
+                }
+            }
+            for (table, windows) in tables.iter().zip(windows) {
+                let to_add = self.ecgfp5_random_access(windows[i], table);
+                let sum = self.ecgfp5_add(&result, &to_add);
+                let is_zero = self.is_equal(windows[i], zero);
+                result = self.ecgfp5_select(is_zero, &result, &sum);
+            }
+        }
+
+        let correction = (0..WINDOW_BITS * (num_windows - 1)).fold(offset, |p, _| p.double());
+        let neg_correction = self.constant_ecgfp5_point(-correction);
+        self.ecgfp5_add(&result, &neg_correction)
+    }
+
+    /// Returns `table[index]`, for a table of `2^WINDOW_BITS` points.
+    fn ecgfp5_random_access(
+        &mut self,
+        index: Target,
+        table: &[EcGFp5PointTarget],
+    ) -> EcGFp5PointTarget {
+        let xs = table.iter().map(|p| p.x).collect::<Vec<_>>();
+        let ys = table.iter().map(|p| p.y).collect::<Vec<_>>();
+        EcGFp5PointTarget {
+            x: self.random_access_quintic_extension(index, &xs),
+            y: self.random_access_quintic_extension(index, &ys),
+        }
+    }
+}
+
+/// A point of the subgroup of order `n` with no known discrete logarithm, found by hashing a fixed
+/// string to an `x` coordinate and incrementing it until it is a square and the `x` coordinate of
+/// a point.
+fn ecgfp5_msm_offset_point() -> EcGFp5Point {
+    let hash = keccak(b"plonky2 ecgfp5 msm offset point");
+    let mut x: EcGFp5Base = QuinticExtension(core::array::from_fn(|i| {
+        let bytes = hash.0[4 * i..4 * i + 4].try_into().unwrap();
+        GoldilocksField::from_canonical_u32(u32::from_le_bytes(bytes))
+    }));
+    loop {
+        if quintic_is_square(x) {
+            if let Some(y) = quintic_sqrt(ecgfp5_rhs(x)) {
+                return EcGFp5Point::nonzero(x, y);
+            }
+        }
+        x += EcGFp5Base::ONE;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::ecgfp5::EcGFp5Point;
+    use crate::field::ecgfp5_scalar::EcGFp5Scalar;
+    use crate::field::types::Sample;
+    use crate::gadgets::ecgfp5::ecgfp5_msm_offset_point;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_msm_offset_point() {
+        assert!(ecgfp5_msm_offset_point().is_valid());
+    }
+
+    #[test]
+    fn test_ecgfp5_add_double_neg() -> Result<()> {
+        let g = EcGFp5Point::GENERATOR;
+        let p = g * EcGFp5Scalar::rand();
+        let q = g * EcGFp5Scalar::rand();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let pt = builder.add_virtual_ecgfp5_point_target();
+        let qt = builder.add_virtual_ecgfp5_point_target();
+        pw.set_ecgfp5_point_target(&pt, p);
+        pw.set_ecgfp5_point_target(&qt, q);
+
+        let results = [
+            (builder.ecgfp5_add(&pt, &qt), p + q),
+            (builder.ecgfp5_double(&pt), p.double()),
+            (builder.ecgfp5_neg(&pt), -p),
+        ];
+        for (result, expected) in results {
+            let expected = builder.constant_ecgfp5_point(expected);
+            builder.connect_ecgfp5_point(&result, &expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_ecgfp5_scalar_mul_windowed() -> Result<()> {
+        let p = EcGFp5Point::GENERATOR * EcGFp5Scalar::rand();
+        let n = EcGFp5Scalar::rand();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let pt = builder.add_virtual_ecgfp5_point_target();
+        let nt = builder.add_virtual_nonnative_target();
+        pw.set_ecgfp5_point_target(&pt, p);
+        pw.set_nonnative_target(&nt, n);
+
+        let result = builder.ecgfp5_scalar_mul_windowed(&pt, &nt);
+        let expected = builder.constant_ecgfp5_point(p * n);
+        builder.connect_ecgfp5_point(&result, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
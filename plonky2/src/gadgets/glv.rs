@@ -0,0 +1,255 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::curve::glv::{decompose_secp256k1_scalar, GLV_BETA, GLV_HALF_BITS, GLV_LAMBDA};
+use crate::curve::secp256k1::Secp256K1;
+use crate::field::extension::Extendable;
+use crate::field::secp256k1_scalar::Secp256K1Scalar;
+use crate::field::types::{Field, PrimeField};
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::nonnative::{
+    biguint_from_u16_limbs, u16_limbs_of, NonNativeTarget, NONNATIVE_LIMB_BITS,
+};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// The number of limbs of the halves of a GLV decomposition.
+const GLV_HALF_LIMBS: usize = GLV_HALF_BITS / NONNATIVE_LIMB_BITS;
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns `(k1, k2, k1_neg, k2_neg)` such that `k = ±k1 + lambda (±k2)`, where the flags tell
+    /// which terms are negated, as in [`decompose_secp256k1_scalar`]. Only the low
+    /// `GLV_HALF_BITS / 16` limbs of `k1` and `k2` may be nonzero.
+    pub fn decompose_secp256k1_scalar(
+        &mut self,
+        k: &NonNativeTarget<Secp256K1Scalar>,
+    ) -> (
+        NonNativeTarget<Secp256K1Scalar>,
+        NonNativeTarget<Secp256K1Scalar>,
+        BoolTarget,
+        BoolTarget,
+    ) {
+        let k1_limbs = self.add_virtual_targets(GLV_HALF_LIMBS);
+        let k2_limbs = self.add_virtual_targets(GLV_HALF_LIMBS);
+        let k1_neg = self.add_virtual_bool_target_safe();
+        let k2_neg = self.add_virtual_bool_target_safe();
+        self.add_simple_generator(GLVDecompositionGenerator {
+            k: k.limbs().to_vec(),
+            k1: k1_limbs.clone(),
+            k2: k2_limbs.clone(),
+            k1_neg,
+            k2_neg,
+        });
+        let k1 = self.glv_half(k1_limbs);
+        let k2 = self.glv_half(k2_limbs);
+
+        // k = ±k1 + lambda (±k2)
+        let neg_k1 = self.neg_nonnative(&k1);
+        let signed_k1 = self.select_nonnative(k1_neg, &neg_k1, &k1);
+        let neg_k2 = self.neg_nonnative(&k2);
+        let signed_k2 = self.select_nonnative(k2_neg, &neg_k2, &k2);
+        let lambda = self.constant_nonnative(GLV_LAMBDA);
+        let lambda_k2 = self.mul_nonnative(&lambda, &signed_k2);
+        let sum = self.add_nonnative(&signed_k1, &lambda_k2);
+        self.connect_nonnative(&sum, k);
+
+        (k1, k2, k1_neg, k2_neg)
+    }
+
+    /// Computes `k p` using the GLV endomorphism. Neither `k` nor `k p` may be zero.
+    pub fn glv_mul(
+        &mut self,
+        p: &AffinePointTarget<Secp256K1>,
+        k: &NonNativeTarget<Secp256K1Scalar>,
+    ) -> AffinePointTarget<Secp256K1> {
+        self.glv_msm(&[(p.clone(), k.clone())])
+    }
+
+    /// Computes `sum_i k_i p_i`, splitting each scalar with the GLV endomorphism so that all the
+    /// terms share `GLV_HALF_BITS` doublings. The sum must not be zero.
+    pub fn glv_msm(
+        &mut self,
+        terms: &[(
+            AffinePointTarget<Secp256K1>,
+            NonNativeTarget<Secp256K1Scalar>,
+        )],
+    ) -> AffinePointTarget<Secp256K1> {
+        let beta = self.constant_nonnative(GLV_BETA);
+        let mut tables = Vec::with_capacity(2 * terms.len());
+        let mut windows = Vec::with_capacity(2 * terms.len());
+        for (p, k) in terms {
+            let (k1, k2, k1_neg, k2_neg) = self.decompose_secp256k1_scalar(k);
+
+            // `k p = k1 (±p) + k2 phi(±p)`, where `phi(x, y) = (beta x, y)`.
+            let table = self.curve_window_table(p);
+            let mut table1 = Vec::with_capacity(table.len());
+            let mut table2 = Vec::with_capacity(table.len());
+            for q in table {
+                let neg_y = self.neg_nonnative(&q.y);
+                table1.push(AffinePointTarget {
+                    x: q.x.clone(),
+                    y: self.select_nonnative(k1_neg, &neg_y, &q.y),
+                });
+                table2.push(AffinePointTarget {
+                    x: self.mul_nonnative(&beta, &q.x),
+                    y: self.select_nonnative(k2_neg, &neg_y, &q.y),
+                });
+            }
+
+            tables.push(table1);
+            windows.push(self.split_limbs_to_windows(&k1.limbs()[..GLV_HALF_LIMBS]));
+            tables.push(table2);
+            windows.push(self.split_limbs_to_windows(&k2.limbs()[..GLV_HALF_LIMBS]));
+        }
+        self.curve_msm_windowed(&tables, &windows)
+    }
+
+    /// Range checks the limbs of half of a GLV decomposition, and pads them into a scalar.
+    fn glv_half(&mut self, mut limbs: Vec<Target>) -> NonNativeTarget<Secp256K1Scalar> {
+        for &limb in &limbs {
+            self.range_check_u16(limb);
+        }
+        let zero = self.zero();
+        limbs.resize(NonNativeTarget::<Secp256K1Scalar>::num_limbs(), zero);
+        // Less than `2^128`, so canonical.
+        NonNativeTarget::from_limbs(limbs)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GLVDecompositionGenerator {
+    k: Vec<Target>,
+    k1: Vec<Target>,
+    k2: Vec<Target>,
+    k1_neg: BoolTarget,
+    k2_neg: BoolTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for GLVDecompositionGenerator
+{
+    fn id(&self) -> String {
+        "GLVDecompositionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.k.clone()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let k = Secp256K1Scalar::from_noncanonical_biguint(biguint_from_u16_limbs(
+            &witness.get_targets(&self.k),
+        ));
+        let (k1, k2, k1_neg, k2_neg) = decompose_secp256k1_scalar(k);
+
+        out_buffer.set_target_arr(
+            &self.k1,
+            &u16_limbs_of(&k1.to_canonical_biguint(), self.k1.len()),
+        );
+        out_buffer.set_target_arr(
+            &self.k2,
+            &u16_limbs_of(&k2.to_canonical_biguint(), self.k2.len()),
+        );
+        out_buffer.set_bool_target(self.k1_neg, k1_neg);
+        out_buffer.set_bool_target(self.k2_neg, k2_neg);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.k)?;
+        dst.write_target_vec(&self.k1)?;
+        dst.write_target_vec(&self.k2)?;
+        dst.write_target_bool(self.k1_neg)?;
+        dst.write_target_bool(self.k2_neg)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let k = src.read_target_vec()?;
+        let k1 = src.read_target_vec()?;
+        let k2 = src.read_target_vec()?;
+        let k1_neg = src.read_target_bool()?;
+        let k2_neg = src.read_target_bool()?;
+        Ok(Self {
+            k,
+            k1,
+            k2,
+            k1_neg,
+            k2_neg,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::curve_types::{Curve, CurveScalar};
+    use crate::curve::glv::decompose_secp256k1_scalar;
+    use crate::curve::secp256k1::Secp256K1;
+    use crate::field::secp256k1_scalar::Secp256K1Scalar;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_glv_decomposition() -> Result<()> {
+        let k = Secp256K1Scalar::rand();
+        let (k1, k2, k1_neg, k2_neg) = decompose_secp256k1_scalar(k);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let kt = builder.add_virtual_nonnative_target();
+        pw.set_nonnative_target(&kt, k);
+        let (k1t, k2t, k1_negt, k2_negt) = builder.decompose_secp256k1_scalar(&kt);
+
+        let k1_expected = builder.constant_nonnative(k1);
+        builder.connect_nonnative(&k1t, &k1_expected);
+        let k2_expected = builder.constant_nonnative(k2);
+        builder.connect_nonnative(&k2t, &k2_expected);
+        let k1_neg_expected = builder.constant_bool(k1_neg);
+        builder.connect(k1_negt.target, k1_neg_expected.target);
+        let k2_neg_expected = builder.constant_bool(k2_neg);
+        builder.connect(k2_negt.target, k2_neg_expected.target);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_glv_mul() -> Result<()> {
+        let g = Secp256K1::GENERATOR_AFFINE;
+        let p = CurveScalar::<Secp256K1>(Secp256K1Scalar::rand()) * g;
+        let k = Secp256K1Scalar::rand();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let pt = builder.add_virtual_affine_point_target::<Secp256K1>();
+        let kt = builder.add_virtual_nonnative_target();
+        pw.set_affine_point_target(&pt, p);
+        pw.set_nonnative_target(&kt, k);
+
+        let result = builder.glv_mul(&pt, &kt);
+        let expected = builder.constant_affine_point(CurveScalar::<Secp256K1>(k) * p);
+        builder.connect_affine_point(&result, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
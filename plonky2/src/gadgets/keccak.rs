@@ -0,0 +1,184 @@
+//! The Keccak-256 hash, as used by Ethereum, over boolean targets.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::BoolTarget;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// The number of message bits absorbed by each permutation.
+const KECCAK256_RATE_BITS: usize = 1088;
+
+const KECCAK_ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offset of the lane `(x, y)` in the rho step, at index `[x][y]`.
+const KECCAK_ROTATIONS: [[usize; 5]; 5] = [
+    [0, 36, 3, 41, 18],
+    [1, 44, 10, 45, 2],
+    [62, 6, 43, 15, 61],
+    [28, 55, 25, 21, 56],
+    [27, 20, 39, 8, 14],
+];
+
+/// The 25 lanes of the Keccak state, the lane `(x, y)` being at index `x + 5 y`, with the bits of
+/// each lane in little-endian order.
+type KeccakState = Vec<[BoolTarget; 64]>;
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns the Keccak-256 hash of a fixed-length message. The message is given as the bits of
+    /// its bytes, least significant bit first, and the 32 bytes of the hash are returned in the
+    /// same order.
+    pub fn keccak256(&mut self, input: &[BoolTarget]) -> Vec<BoolTarget> {
+        assert_eq!(input.len() % 8, 0, "Input must be a whole number of bytes");
+
+        // pad10*1, after the Keccak domain bit, which is the first 1.
+        let _false = self._false();
+        let _true = self._true();
+        let mut padded = input.to_vec();
+        padded.push(_true);
+        while padded.len() % KECCAK256_RATE_BITS != KECCAK256_RATE_BITS - 1 {
+            padded.push(_false);
+        }
+        padded.push(_true);
+
+        let mut state: KeccakState = vec![[_false; 64]; 25];
+        for block in padded.chunks(KECCAK256_RATE_BITS) {
+            for (i, &bit) in block.iter().enumerate() {
+                state[i / 64][i % 64] = self.xor(state[i / 64][i % 64], bit);
+            }
+            state = self.keccak_f(state);
+        }
+
+        state[..4].iter().flatten().copied().collect()
+    }
+
+    /// The Keccak-f[1600] permutation.
+    fn keccak_f(&mut self, mut a: KeccakState) -> KeccakState {
+        let _false = self._false();
+        for round_constant in KECCAK_ROUND_CONSTANTS {
+            // Theta: xor each bit with the parities of two neighbouring columns.
+            let mut c = [[_false; 64]; 5];
+            for x in 0..5 {
+                for z in 0..64 {
+                    c[x][z] = a[x][z];
+                    for y in 1..5 {
+                        c[x][z] = self.xor(c[x][z], a[x + 5 * y][z]);
+                    }
+                }
+            }
+            for x in 0..5 {
+                for z in 0..64 {
+                    let d = self.xor(c[(x + 4) % 5][z], c[(x + 1) % 5][(z + 63) % 64]);
+                    for y in 0..5 {
+                        a[x + 5 * y][z] = self.xor(a[x + 5 * y][z], d);
+                    }
+                }
+            }
+
+            // Rho and pi: rotate each lane, and move it to `(y, 2 x + 3 y)`.
+            let mut b = a.clone();
+            for x in 0..5 {
+                for y in 0..5 {
+                    let rotation = KECCAK_ROTATIONS[x][y];
+                    for z in 0..64 {
+                        b[y + 5 * ((2 * x + 3 * y) % 5)][(z + rotation) % 64] = a[x + 5 * y][z];
+                    }
+                }
+            }
+
+            // Chi: a = b ^ (!b[x + 1] & b[x + 2]), along each row.
+            for x in 0..5 {
+                for y in 0..5 {
+                    for z in 0..64 {
+                        let b1 = b[(x + 1) % 5 + 5 * y][z].target;
+                        let b2 = b[(x + 2) % 5 + 5 * y][z].target;
+                        let not_b1_and_b2 =
+                            BoolTarget::new_unsafe(self.arithmetic(-F::ONE, F::ONE, b1, b2, b2));
+                        a[x + 5 * y][z] = self.xor(b[x + 5 * y][z], not_b1_and_b2);
+                    }
+                }
+            }
+
+            // Iota
+            for z in 0..64 {
+                if (round_constant >> z) & 1 == 1 {
+                    a[0][z] = self.not(a[0][z]);
+                }
+            }
+        }
+        a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use keccak_hash::keccak;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    #[test]
+    fn test_keccak256() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        // One more byte than the rate, so that the padding spans a second block.
+        let input = (0..137).map(|_| OsRng.gen::<u8>()).collect::<Vec<_>>();
+        let expected = keccak(&input).0;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let input_bits = (0..8 * input.len())
+            .map(|_| builder.add_virtual_bool_target_safe())
+            .collect::<Vec<_>>();
+        for (i, &bit) in input_bits.iter().enumerate() {
+            pw.set_bool_target(bit, (input[i / 8] >> (i % 8)) & 1 == 1);
+        }
+
+        let hash = builder.keccak256(&input_bits);
+        for (i, bit) in hash.into_iter().enumerate() {
+            let expected_bit = builder.constant_bool((expected[i / 8] >> (i % 8)) & 1 == 1);
+            builder.connect(bit.target, expected_bit.target);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
@@ -1,3 +1,5 @@
+use alloc::sync::Arc;
+
 use crate::field::extension::Extendable;
 use crate::gates::lookup::LookupGate;
 use crate::gates::lookup_table::{LookupTable, LookupTableGate};
@@ -43,6 +45,26 @@ pub const OTHER_TABLE: [u16; 256] = [
 /// This is a smaller lookup table with arbitrary values.
 pub const SMALLER_TABLE: [u16; 8] = [2, 24, 56, 100, 128, 16, 20, 49];
 
+/// Name of the table mapping `256 a + b` to `a ^ b`, for bytes `a` and `b`.
+const U8_XOR_LUT: &str = "u8_xor";
+
+/// Name of the table mapping `256 a + b` to `a & b`, for bytes `a` and `b`.
+const U8_AND_LUT: &str = "u8_and";
+
+/// Names of the tables mapping a byte `a` to `a >> k`, at index `k`.
+const U8_SHR_LUTS: [&str; 8] = [
+    "u8_shr_0", "u8_shr_1", "u8_shr_2", "u8_shr_3", "u8_shr_4", "u8_shr_5", "u8_shr_6", "u8_shr_7",
+];
+
+/// Returns the table of `op` over all pairs of bytes, indexed by `256 a + b`.
+fn u8_pair_table(op: fn(u8, u8) -> u8) -> LookupTable {
+    Arc::new(
+        (0..=u16::MAX)
+            .map(|i| (i, op((i >> 8) as u8, i as u8) as u16))
+            .collect(),
+    )
+}
+
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     /// Adds a lookup table to the list of stored lookup tables `self.luts` based on a table of (input, output) pairs. It returns the index of the LUT within `self.luts`.
     pub fn add_lookup_table_from_pairs(&mut self, table: LookupTable) -> usize {
@@ -72,6 +94,34 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         looking_out
     }
 
+    /// Returns `a ^ b`, with a lookup into a table of all pairs of bytes. Both `a` and `b` must
+    /// already be known to be bytes.
+    pub fn xor_u8(&mut self, a: Target, b: Target) -> Target {
+        let lut_index = self.get_or_add_named_lut(U8_XOR_LUT, || u8_pair_table(|a, b| a ^ b));
+        self.u8_pair_lookup(a, b, lut_index)
+    }
+
+    /// Returns `a & b`, with a lookup into a table of all pairs of bytes. Both `a` and `b` must
+    /// already be known to be bytes.
+    pub fn and_u8(&mut self, a: Target, b: Target) -> Target {
+        let lut_index = self.get_or_add_named_lut(U8_AND_LUT, || u8_pair_table(|a, b| a & b));
+        self.u8_pair_lookup(a, b, lut_index)
+    }
+
+    /// Returns `a >> k`, with a lookup that also checks that `a` is a byte.
+    pub fn shr_u8(&mut self, a: Target, k: usize) -> Target {
+        assert!(k < 8, "Shift of {} bits out of range", k);
+        let lut_index = self.get_or_add_named_lut(U8_SHR_LUTS[k], || {
+            Arc::new((0..=u8::MAX as u16).map(|i| (i, i >> k)).collect())
+        });
+        self.add_lookup_from_index(a, lut_index)
+    }
+
+    fn u8_pair_lookup(&mut self, a: Target, b: Target, lut_index: usize) -> Target {
+        let pair = self.mul_const_add(F::from_canonical_u16(1 << 8), a, b);
+        self.add_lookup_from_index(pair, lut_index)
+    }
+
     /// We call this function at the end of circuit building right before the PI gate to add all `LookupTableGate` and `LookupGate`.
     /// It also updates `self.lookup_rows` accordingly.
     pub fn add_all_lookups(&mut self) {
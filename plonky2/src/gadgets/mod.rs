@@ -1,11 +1,23 @@
 pub mod arithmetic;
 pub mod arithmetic_extension;
+pub mod biguint;
+pub mod blake2;
+pub mod curve;
+pub mod ecdsa;
+pub mod ecgfp5;
+pub mod glv;
 pub mod hash;
 pub mod interpolation;
+pub mod keccak;
 pub mod lookup;
+pub mod nonnative;
 pub mod polynomial;
+pub mod quintic;
 pub mod random_access;
 pub mod range_check;
+pub mod schnorr;
 pub mod select;
+pub mod sha256;
 pub mod split_base;
 pub mod split_join;
+pub mod words;
@@ -0,0 +1,979 @@
+//! Arithmetic on elements of foreign prime fields, such as the secp256k1 or BN254 fields.
+//!
+//! Elements are represented by the 16-bit limbs of their canonical representatives, range checked
+//! with lookups. Each operation hints its result, and possibly a quotient, then checks an integer
+//! identity between limb vectors such as `a * b = q * p + r`. Such identities are checked column
+//! by column with signed, range-checked carries, which cannot overflow the native field.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use num::{BigUint, Integer, Zero};
+
+use crate::field::extension::Extendable;
+use crate::field::types::{Field, PrimeField, PrimeField64};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+use crate::util::{ceil_div_usize, log2_ceil};
+
+/// The number of bits in each limb of a [`NonNativeTarget`].
+pub const NONNATIVE_LIMB_BITS: usize = 16;
+
+/// An element of the prime field `FF`, as the little-endian 16-bit limbs of its canonical
+/// representative.
+///
+/// The limbs of the targets returned by the `CircuitBuilder` methods below are range checked, and
+/// their value is less than the order of `FF`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NonNativeTarget<FF: Field> {
+    pub(crate) limbs: Vec<Target>,
+    _phantom: PhantomData<FF>,
+}
+
+impl<FF: Field> NonNativeTarget<FF> {
+    /// The number of limbs representing an element of `FF`.
+    pub fn num_limbs() -> usize {
+        ceil_div_usize(FF::BITS, NONNATIVE_LIMB_BITS)
+    }
+
+    /// Wraps limbs without checking them.
+    pub(crate) fn from_limbs(limbs: Vec<Target>) -> Self {
+        debug_assert_eq!(limbs.len(), Self::num_limbs());
+        Self {
+            limbs,
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn limbs(&self) -> &[Target] {
+        &self.limbs
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds a foreign field element whose limbs are range checked and whose value is checked to be
+    /// canonical.
+    pub fn add_virtual_nonnative_target<FF: PrimeField>(&mut self) -> NonNativeTarget<FF> {
+        let limbs = self.add_virtual_targets(NonNativeTarget::<FF>::num_limbs());
+        self.nonnative_from_limbs(limbs)
+    }
+
+    /// Interprets the little-endian 16-bit `limbs` as a foreign field element, checking that each
+    /// limb fits in 16 bits and that the value is canonical.
+    pub fn nonnative_from_limbs<FF: PrimeField>(
+        &mut self,
+        limbs: Vec<Target>,
+    ) -> NonNativeTarget<FF> {
+        let x = NonNativeTarget::from_limbs(limbs);
+        self.range_check_nonnative(&x);
+        x
+    }
+
+    /// Checks that the limbs of `x` fit in 16 bits and that `x` is less than the order of `FF`.
+    pub fn range_check_nonnative<FF: PrimeField>(&mut self, x: &NonNativeTarget<FF>) {
+        for &limb in &x.limbs {
+            self.range_check_u16(limb);
+        }
+        let modulus = u16_limbs_of(&FF::order(), x.limbs.len());
+        let modulus = self.constants(&modulus);
+        let geq_modulus = self.limbs_geq(&x.limbs, &modulus);
+        self.assert_zero(geq_modulus.target);
+    }
+
+    pub fn constant_nonnative<FF: PrimeField>(&mut self, x: FF) -> NonNativeTarget<FF> {
+        let limbs = u16_limbs_of(
+            &x.to_canonical_biguint(),
+            NonNativeTarget::<FF>::num_limbs(),
+        );
+        NonNativeTarget::from_limbs(self.constants(&limbs))
+    }
+
+    pub fn zero_nonnative<FF: PrimeField>(&mut self) -> NonNativeTarget<FF> {
+        self.constant_nonnative(FF::ZERO)
+    }
+
+    pub fn connect_nonnative<FF: Field>(
+        &mut self,
+        lhs: &NonNativeTarget<FF>,
+        rhs: &NonNativeTarget<FF>,
+    ) {
+        for (&l, &r) in lhs.limbs.iter().zip(&rhs.limbs) {
+            self.connect(l, r);
+        }
+    }
+
+    pub fn is_equal_nonnative<FF: Field>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> BoolTarget {
+        let mut result = self._true();
+        for (&a_limb, &b_limb) in a.limbs.iter().zip(&b.limbs) {
+            let limb_equal = self.is_equal(a_limb, b_limb);
+            result = self.and(result, limb_equal);
+        }
+        result
+    }
+
+    /// Returns whether `a < b` as integers, comparing canonical representatives.
+    pub fn is_less_than_nonnative<FF: Field>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> BoolTarget {
+        let geq = self.limbs_geq(&a.limbs, &b.limbs);
+        self.not(geq)
+    }
+
+    pub fn add_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let sum = self.add_virtual_nonnative_target::<FF>();
+        let overflow = self.add_virtual_bool_target_safe();
+        self.add_simple_generator(NonNativeAdditionGenerator {
+            a: a.limbs.clone(),
+            b: b.limbs.clone(),
+            modulus: FF::order(),
+            sum: sum.limbs.clone(),
+            overflow,
+        });
+
+        // a + b - sum - overflow * p = 0
+        let modulus = u16_limbs_of::<F>(&FF::order(), sum.limbs.len());
+        let columns = (0..sum.limbs.len())
+            .map(|i| {
+                let column = self.add(a.limbs[i], b.limbs[i]);
+                let column = self.sub(column, sum.limbs[i]);
+                self.mul_const_add(-modulus[i], overflow.target, column)
+            })
+            .collect::<Vec<_>>();
+        self.assert_limb_columns_zero(&columns, NONNATIVE_LIMB_BITS + 2);
+
+        sum
+    }
+
+    pub fn sub_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let diff = self.add_virtual_nonnative_target::<FF>();
+        let overflow = self.add_virtual_bool_target_safe();
+        self.add_simple_generator(NonNativeSubtractionGenerator {
+            a: a.limbs.clone(),
+            b: b.limbs.clone(),
+            modulus: FF::order(),
+            diff: diff.limbs.clone(),
+            overflow,
+        });
+
+        // a - b + overflow * p - diff = 0
+        let modulus = u16_limbs_of::<F>(&FF::order(), diff.limbs.len());
+        let columns = (0..diff.limbs.len())
+            .map(|i| {
+                let column = self.sub(a.limbs[i], b.limbs[i]);
+                let column = self.sub(column, diff.limbs[i]);
+                self.mul_const_add(modulus[i], overflow.target, column)
+            })
+            .collect::<Vec<_>>();
+        self.assert_limb_columns_zero(&columns, NONNATIVE_LIMB_BITS + 2);
+
+        diff
+    }
+
+    pub fn neg_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let zero = self.zero_nonnative();
+        self.sub_nonnative(&zero, x)
+    }
+
+    pub fn mul_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        self.reduce_product(&[&a.limbs[..], &b.limbs[..]])
+    }
+
+    /// Returns the inverse of `x`, which must be nonzero.
+    pub fn inv_nonnative<FF: PrimeField>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let inv = self.add_virtual_nonnative_target::<FF>();
+        self.add_simple_generator(NonNativeInverseGenerator {
+            x: x.limbs.clone(),
+            modulus: FF::order(),
+            inverse: inv.limbs.clone(),
+        });
+
+        let product = self.mul_nonnative(x, &inv);
+        let one = self.constant_nonnative(FF::ONE);
+        self.connect_nonnative(&product, &one);
+
+        inv
+    }
+
+    /// Results in an unsatisfiable instance if `x = 0`.
+    pub fn assert_nonzero_nonnative<FF: Field>(&mut self, x: &NonNativeTarget<FF>) {
+        // `x` is canonical, so it is zero exactly when its limbs, and thus their sum, are.
+        let limb_sum = self.add_many(&x.limbs);
+        self.inverse(limb_sum);
+    }
+
+    /// Returns `a / b`, with a single multiplication. Results in an unsatisfiable instance if
+    /// `b = 0`.
+    pub fn div_nonnative<FF: PrimeField>(
+        &mut self,
+        a: &NonNativeTarget<FF>,
+        b: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let quotient = self.add_virtual_nonnative_target::<FF>();
+        self.add_simple_generator(NonNativeDivisionGenerator {
+            a: a.limbs.clone(),
+            b: b.limbs.clone(),
+            modulus: FF::order(),
+            quotient: quotient.limbs.clone(),
+        });
+
+        self.assert_nonzero_nonnative(b);
+        let product = self.mul_nonnative(&quotient, b);
+        self.connect_nonnative(&product, a);
+
+        quotient
+    }
+
+    /// Selects `x` or `y` based on `b`, i.e., this returns `if b { x } else { y }`.
+    pub fn select_nonnative<FF: Field>(
+        &mut self,
+        b: BoolTarget,
+        x: &NonNativeTarget<FF>,
+        y: &NonNativeTarget<FF>,
+    ) -> NonNativeTarget<FF> {
+        let limbs = x
+            .limbs
+            .iter()
+            .zip(&y.limbs)
+            .map(|(&x_limb, &y_limb)| self.select(b, x_limb, y_limb))
+            .collect();
+        NonNativeTarget::from_limbs(limbs)
+    }
+
+    /// Reduces the integer with the given little-endian 16-bit limbs modulo the order of `FF`. The
+    /// limbs are range checked, and there may be more of them than for elements of `FF`.
+    pub fn reduce_nonnative<FF: PrimeField>(&mut self, limbs: &[Target]) -> NonNativeTarget<FF> {
+        for &limb in limbs {
+            self.range_check_u16(limb);
+        }
+        self.reduce_product(&[limbs])
+    }
+
+    /// Reduces the integer with the given little-endian bits modulo the order of `FF`.
+    pub fn reduce_le_bits_nonnative<FF: PrimeField>(
+        &mut self,
+        bits: &[BoolTarget],
+    ) -> NonNativeTarget<FF> {
+        let limbs = bits
+            .chunks(NONNATIVE_LIMB_BITS)
+            .map(|chunk| self.le_sum(chunk.iter()))
+            .collect::<Vec<_>>();
+        self.reduce_product(&[&limbs[..]])
+    }
+
+    /// Returns the little-endian bits of the canonical representative of `x`.
+    pub fn split_nonnative_to_bits<FF: Field>(
+        &mut self,
+        x: &NonNativeTarget<FF>,
+    ) -> Vec<BoolTarget> {
+        x.limbs
+            .iter()
+            .flat_map(|&limb| self.split_le(limb, NONNATIVE_LIMB_BITS))
+            .collect()
+    }
+
+    /// Hints `q` and `r` such that the product of the integers with limbs `factors`, of which there
+    /// are one or two, is `q * p + r` with `r < p`, checks this identity and returns `r`. The
+    /// factor limbs must already be range checked.
+    fn reduce_product<FF: PrimeField>(&mut self, factors: &[&[Target]]) -> NonNativeTarget<FF> {
+        let num_limbs = NonNativeTarget::<FF>::num_limbs();
+        let product_len: usize = factors.iter().map(|f| f.len()).sum();
+        // The product is less than `2^(16 product_len)` and `p >= 2^(16 (num_limbs - 1))`.
+        let quotient_len = (product_len + 1).saturating_sub(num_limbs).max(1);
+
+        let quotient = self.add_virtual_targets(quotient_len);
+        for &limb in &quotient {
+            self.range_check_u16(limb);
+        }
+        let remainder = self.add_virtual_nonnative_target::<FF>();
+        self.add_simple_generator(NonNativeReductionGenerator {
+            factors: factors.iter().map(|f| f.to_vec()).collect(),
+            modulus: FF::order(),
+            quotient: quotient.clone(),
+            remainder: remainder.limbs.clone(),
+        });
+
+        // prod(factors) - quotient * p - remainder = 0
+        let (mut columns, product_terms) = match factors {
+            [x] => (x.to_vec(), 1),
+            [a, b] => {
+                let zero = self.zero();
+                let mut columns = vec![zero; a.len() + b.len() - 1];
+                for (i, &a_limb) in a.iter().enumerate() {
+                    for (j, &b_limb) in b.iter().enumerate() {
+                        columns[i + j] = self.mul_add(a_limb, b_limb, columns[i + j]);
+                    }
+                }
+                (columns, a.len().min(b.len()))
+            }
+            _ => panic!("Expected one or two factors"),
+        };
+        let num_columns = columns.len().max(quotient_len + num_limbs - 1);
+        let zero = self.zero();
+        columns.resize(num_columns, zero);
+
+        let modulus = u16_limbs_of::<F>(&FF::order(), num_limbs);
+        for (i, &q_limb) in quotient.iter().enumerate() {
+            for (j, &p_limb) in modulus.iter().enumerate() {
+                if p_limb.is_nonzero() {
+                    columns[i + j] = self.mul_const_add(-p_limb, q_limb, columns[i + j]);
+                }
+            }
+        }
+        for (column, &r_limb) in columns.iter_mut().zip(&remainder.limbs) {
+            *column = self.sub(*column, r_limb);
+        }
+
+        // Each term of a column is less than `2^32`.
+        let num_terms = product_terms + quotient_len.min(num_limbs) + 1;
+        self.assert_limb_columns_zero(&columns, 2 * NONNATIVE_LIMB_BITS + log2_ceil(num_terms));
+
+        remainder
+    }
+
+    /// Returns whether `a >= b`, for integers with the same number of 16-bit limbs.
+    pub(crate) fn limbs_geq(&mut self, a: &[Target], b: &[Target]) -> BoolTarget {
+        assert_eq!(a.len(), b.len(), "Limb counts differ");
+        let diff = self.add_virtual_targets(a.len());
+        for &limb in &diff {
+            self.range_check_u16(limb);
+        }
+        let geq = self.add_virtual_bool_target_safe();
+        self.add_simple_generator(LimbComparisonGenerator {
+            a: a.to_vec(),
+            b: b.to_vec(),
+            diff: diff.clone(),
+            geq,
+        });
+
+        // a + diff + 1 - b - 2^(16 n) geq = 0, so that `geq` is set if and only if `a >= b`.
+        let mut columns = (0..a.len())
+            .map(|i| {
+                let column = self.add(a[i], diff[i]);
+                self.sub(column, b[i])
+            })
+            .collect::<Vec<_>>();
+        columns[0] = self.add_const(columns[0], F::ONE);
+        columns.push(self.neg(geq.target));
+        self.assert_limb_columns_zero(&columns, NONNATIVE_LIMB_BITS + 2);
+
+        geq
+    }
+
+    /// Checks that `sum_k columns[k] 2^(16 k) = 0` over the integers, where each column is known to
+    /// be a signed integer of absolute value less than `2^column_bits`.
+    ///
+    /// Column `k` must satisfy `columns[k] + c_{k-1} = 2^16 c_k`, with `c_{-1}` and the last carry
+    /// zero. The carries `c_k` are less than `2^(column_bits - 15)` in absolute value, and are
+    /// hinted as range-checked limbs of `c_k` plus an offset. As `column_bits` is small, none of
+    /// these equations can wrap around the native field.
+    pub(crate) fn assert_limb_columns_zero(&mut self, columns: &[Target], column_bits: usize) {
+        assert!(
+            (15..=46).contains(&column_bits),
+            "Unsupported column size of {} bits",
+            column_bits
+        );
+        let limbs_per_carry = ceil_div_usize(column_bits - 14, NONNATIVE_LIMB_BITS);
+        let carry_offset = F::from_canonical_u64(1 << (NONNATIVE_LIMB_BITS * limbs_per_carry - 1));
+        let base = F::from_canonical_u64(1 << NONNATIVE_LIMB_BITS);
+
+        let (&last_column, columns) = columns.split_last().expect("No columns");
+        let carry_limbs = self.add_virtual_targets(columns.len() * limbs_per_carry);
+        if !columns.is_empty() {
+            self.add_simple_generator(LimbCarryGenerator {
+                columns: columns.to_vec(),
+                carry_limbs: carry_limbs.clone(),
+                limbs_per_carry,
+            });
+        }
+
+        let mut carry = self.zero();
+        for (&column, limbs) in columns.iter().zip(carry_limbs.chunks(limbs_per_carry)) {
+            let total = self.add(column, carry);
+            let mut shifted_carry = self.zero();
+            for &limb in limbs.iter().rev() {
+                self.range_check_u16(limb);
+                shifted_carry = self.mul_const_add(base, shifted_carry, limb);
+            }
+            carry = self.add_const(shifted_carry, -carry_offset);
+            let residue = self.mul_const_add(-base, carry, total);
+            self.assert_zero(residue);
+        }
+        let total = self.add(last_column, carry);
+        self.assert_zero(total);
+    }
+}
+
+/// Returns the `num_limbs` little-endian 16-bit limbs of `x`, as field elements.
+pub(crate) fn u16_limbs_of<F: Field>(x: &BigUint, num_limbs: usize) -> Vec<F> {
+    let mut limbs = x
+        .to_u32_digits()
+        .into_iter()
+        .flat_map(|digit| [digit as u16, (digit >> 16) as u16])
+        .collect::<Vec<_>>();
+    assert!(
+        limbs.iter().skip(num_limbs).all(|&limb| limb == 0),
+        "Integer does not fit in {} limbs",
+        num_limbs
+    );
+    limbs.resize(num_limbs, 0);
+    limbs.into_iter().map(F::from_canonical_u16).collect()
+}
+
+/// Returns the integer with the given little-endian 16-bit limbs.
+pub(crate) fn biguint_from_u16_limbs<F: PrimeField64>(limbs: &[F]) -> BigUint {
+    limbs.iter().rev().fold(BigUint::zero(), |acc, limb| {
+        (acc << NONNATIVE_LIMB_BITS) + limb.to_canonical_u64()
+    })
+}
+
+/// Interprets `x` as a signed integer in `(-p/2, p/2)`.
+fn signed_value<F: PrimeField64>(x: F) -> i128 {
+    let x = x.to_canonical_u64();
+    if x > F::ORDER / 2 {
+        x as i128 - F::ORDER as i128
+    } else {
+        x as i128
+    }
+}
+
+pub(crate) fn get_biguint<F: RichField>(
+    witness: &PartitionWitness<F>,
+    limbs: &[Target],
+) -> BigUint {
+    biguint_from_u16_limbs(&witness.get_targets(limbs))
+}
+
+pub(crate) fn set_biguint<F: Field>(
+    out_buffer: &mut GeneratedValues<F>,
+    limbs: &[Target],
+    value: &BigUint,
+) {
+    out_buffer.set_target_arr(limbs, &u16_limbs_of(value, limbs.len()));
+}
+
+fn write_biguint(dst: &mut Vec<u8>, x: &BigUint) -> IoResult<()> {
+    let digits = x.to_u32_digits();
+    dst.write_usize(digits.len())?;
+    for digit in digits {
+        dst.write_u32(digit)?;
+    }
+    Ok(())
+}
+
+fn read_biguint(src: &mut Buffer) -> IoResult<BigUint> {
+    let len = src.read_usize()?;
+    let digits = (0..len)
+        .map(|_| src.read_u32())
+        .collect::<IoResult<Vec<_>>>()?;
+    Ok(BigUint::new(digits))
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeAdditionGenerator {
+    a: Vec<Target>,
+    b: Vec<Target>,
+    modulus: BigUint,
+    sum: Vec<Target>,
+    overflow: BoolTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for NonNativeAdditionGenerator
+{
+    fn id(&self) -> String {
+        "NonNativeAdditionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.iter().chain(&self.b).copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = get_biguint(witness, &self.a);
+        let b = get_biguint(witness, &self.b);
+        let sum = a + b;
+        let overflow = sum >= self.modulus;
+        let sum = if overflow { sum - &self.modulus } else { sum };
+
+        set_biguint(out_buffer, &self.sum, &sum);
+        out_buffer.set_bool_target(self.overflow, overflow);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a)?;
+        dst.write_target_vec(&self.b)?;
+        write_biguint(dst, &self.modulus)?;
+        dst.write_target_vec(&self.sum)?;
+        dst.write_target_bool(self.overflow)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_vec()?;
+        let b = src.read_target_vec()?;
+        let modulus = read_biguint(src)?;
+        let sum = src.read_target_vec()?;
+        let overflow = src.read_target_bool()?;
+        Ok(Self {
+            a,
+            b,
+            modulus,
+            sum,
+            overflow,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeSubtractionGenerator {
+    a: Vec<Target>,
+    b: Vec<Target>,
+    modulus: BigUint,
+    diff: Vec<Target>,
+    overflow: BoolTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for NonNativeSubtractionGenerator
+{
+    fn id(&self) -> String {
+        "NonNativeSubtractionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.iter().chain(&self.b).copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = get_biguint(witness, &self.a);
+        let b = get_biguint(witness, &self.b);
+        let overflow = a < b;
+        let diff = if overflow {
+            a + &self.modulus - b
+        } else {
+            a - b
+        };
+
+        set_biguint(out_buffer, &self.diff, &diff);
+        out_buffer.set_bool_target(self.overflow, overflow);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a)?;
+        dst.write_target_vec(&self.b)?;
+        write_biguint(dst, &self.modulus)?;
+        dst.write_target_vec(&self.diff)?;
+        dst.write_target_bool(self.overflow)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_vec()?;
+        let b = src.read_target_vec()?;
+        let modulus = read_biguint(src)?;
+        let diff = src.read_target_vec()?;
+        let overflow = src.read_target_bool()?;
+        Ok(Self {
+            a,
+            b,
+            modulus,
+            diff,
+            overflow,
+        })
+    }
+}
+
+/// Computes the quotient and remainder of the product of `factors` by `modulus`.
+#[derive(Debug, Default)]
+pub struct NonNativeReductionGenerator {
+    factors: Vec<Vec<Target>>,
+    modulus: BigUint,
+    quotient: Vec<Target>,
+    remainder: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for NonNativeReductionGenerator
+{
+    fn id(&self) -> String {
+        "NonNativeReductionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.factors.concat()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let product = self
+            .factors
+            .iter()
+            .map(|factor| get_biguint(witness, factor))
+            .product::<BigUint>();
+        let (quotient, remainder) = product.div_rem(&self.modulus);
+
+        set_biguint(out_buffer, &self.quotient, &quotient);
+        set_biguint(out_buffer, &self.remainder, &remainder);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.factors.len())?;
+        for factor in &self.factors {
+            dst.write_target_vec(factor)?;
+        }
+        write_biguint(dst, &self.modulus)?;
+        dst.write_target_vec(&self.quotient)?;
+        dst.write_target_vec(&self.remainder)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let num_factors = src.read_usize()?;
+        let factors = (0..num_factors)
+            .map(|_| src.read_target_vec())
+            .collect::<IoResult<Vec<_>>>()?;
+        let modulus = read_biguint(src)?;
+        let quotient = src.read_target_vec()?;
+        let remainder = src.read_target_vec()?;
+        Ok(Self {
+            factors,
+            modulus,
+            quotient,
+            remainder,
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NonNativeInverseGenerator {
+    x: Vec<Target>,
+    modulus: BigUint,
+    inverse: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for NonNativeInverseGenerator
+{
+    fn id(&self) -> String {
+        "NonNativeInverseGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.x.clone()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = get_biguint(witness, &self.x);
+        assert!(!x.is_zero(), "Inverse of zero");
+        // The modulus is prime, so `x^(p - 2)` is the inverse of `x`.
+        let exponent = &self.modulus - 2u32;
+        let inverse = x.modpow(&exponent, &self.modulus);
+
+        set_biguint(out_buffer, &self.inverse, &inverse);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.x)?;
+        write_biguint(dst, &self.modulus)?;
+        dst.write_target_vec(&self.inverse)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let x = src.read_target_vec()?;
+        let modulus = read_biguint(src)?;
+        let inverse = src.read_target_vec()?;
+        Ok(Self {
+            x,
+            modulus,
+            inverse,
+        })
+    }
+}
+
+/// Computes `a / b` modulo `modulus`.
+#[derive(Debug, Default)]
+pub struct NonNativeDivisionGenerator {
+    a: Vec<Target>,
+    b: Vec<Target>,
+    modulus: BigUint,
+    quotient: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for NonNativeDivisionGenerator
+{
+    fn id(&self) -> String {
+        "NonNativeDivisionGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.iter().chain(&self.b).copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = get_biguint(witness, &self.a);
+        let b = get_biguint(witness, &self.b);
+        let exponent = &self.modulus - 2u32;
+        let quotient = a * b.modpow(&exponent, &self.modulus) % &self.modulus;
+
+        set_biguint(out_buffer, &self.quotient, &quotient);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a)?;
+        dst.write_target_vec(&self.b)?;
+        write_biguint(dst, &self.modulus)?;
+        dst.write_target_vec(&self.quotient)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_vec()?;
+        let b = src.read_target_vec()?;
+        let modulus = read_biguint(src)?;
+        let quotient = src.read_target_vec()?;
+        Ok(Self {
+            a,
+            b,
+            modulus,
+            quotient,
+        })
+    }
+}
+
+/// Computes `geq = (a >= b)` and `diff = b - a - 1 mod 2^(16 n)` for integers with `n` limbs.
+#[derive(Debug, Default)]
+pub struct LimbComparisonGenerator {
+    a: Vec<Target>,
+    b: Vec<Target>,
+    diff: Vec<Target>,
+    geq: BoolTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for LimbComparisonGenerator
+{
+    fn id(&self) -> String {
+        "LimbComparisonGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.a.iter().chain(&self.b).copied().collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let a = get_biguint(witness, &self.a);
+        let b = get_biguint(witness, &self.b);
+        let geq = a >= b;
+        let wrap = BigUint::from(1u32) << (NONNATIVE_LIMB_BITS * self.a.len());
+        let diff = (b + &wrap - a - 1u32) % wrap;
+
+        set_biguint(out_buffer, &self.diff, &diff);
+        out_buffer.set_bool_target(self.geq, geq);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.a)?;
+        dst.write_target_vec(&self.b)?;
+        dst.write_target_vec(&self.diff)?;
+        dst.write_target_bool(self.geq)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let a = src.read_target_vec()?;
+        let b = src.read_target_vec()?;
+        let diff = src.read_target_vec()?;
+        let geq = src.read_target_bool()?;
+        Ok(Self { a, b, diff, geq })
+    }
+}
+
+/// Computes the carries of [`CircuitBuilder::assert_limb_columns_zero`], each as the limbs of the
+/// carry plus `2^(16 limbs_per_carry - 1)`.
+#[derive(Debug, Default)]
+pub struct LimbCarryGenerator {
+    columns: Vec<Target>,
+    carry_limbs: Vec<Target>,
+    limbs_per_carry: usize,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for LimbCarryGenerator {
+    fn id(&self) -> String {
+        "LimbCarryGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.columns.clone()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let offset = 1i128 << (NONNATIVE_LIMB_BITS * self.limbs_per_carry - 1);
+        let mut carry = 0i128;
+        for (&column, limbs) in self
+            .columns
+            .iter()
+            .zip(self.carry_limbs.chunks(self.limbs_per_carry))
+        {
+            let total = signed_value(witness.get_target(column)) + carry;
+            debug_assert_eq!(total % (1 << NONNATIVE_LIMB_BITS), 0, "Inexact carry");
+            carry = total >> NONNATIVE_LIMB_BITS;
+
+            let mut shifted_carry = (carry + offset) as u64;
+            for &limb in limbs {
+                out_buffer.set_target(limb, F::from_canonical_u64(shifted_carry & 0xffff));
+                shifted_carry >>= NONNATIVE_LIMB_BITS;
+            }
+        }
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_vec(&self.columns)?;
+        dst.write_target_vec(&self.carry_limbs)?;
+        dst.write_usize(self.limbs_per_carry)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let columns = src.read_target_vec()?;
+        let carry_limbs = src.read_target_vec()?;
+        let limbs_per_carry = src.read_usize()?;
+        Ok(Self {
+            columns,
+            carry_limbs,
+            limbs_per_carry,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use num::BigUint;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use crate::field::bn254_base::Bn254Base;
+    use crate::field::secp256k1_base::Secp256K1Base;
+    use crate::field::types::{Field, PrimeField, Sample};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_nonnative_arithmetic() -> Result<()> {
+        type FF = Secp256K1Base;
+        let x = FF::rand();
+        let y = FF::rand();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let xt = builder.add_virtual_nonnative_target::<FF>();
+        let yt = builder.add_virtual_nonnative_target::<FF>();
+        pw.set_nonnative_target(&xt, x);
+        pw.set_nonnative_target(&yt, y);
+
+        let results = [
+            (builder.add_nonnative(&xt, &yt), x + y),
+            (builder.sub_nonnative(&xt, &yt), x - y),
+            (builder.neg_nonnative(&xt), -x),
+            (builder.mul_nonnative(&xt, &yt), x * y),
+            (builder.inv_nonnative(&xt), x.inverse()),
+            (builder.div_nonnative(&xt, &yt), x / y),
+        ];
+        for (result, expected) in results {
+            let expected = builder.constant_nonnative(expected);
+            builder.connect_nonnative(&result, &expected);
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_nonnative_comparison_and_reduction() -> Result<()> {
+        type FF = Bn254Base;
+        let (x, y) = {
+            let (a, b) = (FF::rand(), FF::rand());
+            if a.to_canonical_biguint() < b.to_canonical_biguint() {
+                (a, b)
+            } else {
+                (b, a)
+            }
+        };
+        let wide_bits = (0..512).map(|_| OsRng.gen::<bool>()).collect::<Vec<_>>();
+        let wide = wide_bits
+            .iter()
+            .rev()
+            .fold(BigUint::from(0u32), |acc, &bit| (acc << 1) + bit as u32);
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let xt = builder.add_virtual_nonnative_target::<FF>();
+        let yt = builder.add_virtual_nonnative_target::<FF>();
+        pw.set_nonnative_target(&xt, x);
+        pw.set_nonnative_target(&yt, y);
+
+        let lt = builder.is_less_than_nonnative(&xt, &yt);
+        builder.assert_one(lt.target);
+        let gt = builder.is_less_than_nonnative(&yt, &xt);
+        builder.assert_zero(gt.target);
+        let not_lt_self = builder.is_less_than_nonnative(&xt, &xt);
+        builder.assert_zero(not_lt_self.target);
+        let eq = builder.is_equal_nonnative(&xt, &yt);
+        builder.assert_zero(eq.target);
+
+        let bits = builder.split_nonnative_to_bits(&xt);
+        let x_again = builder.reduce_le_bits_nonnative::<FF>(&bits);
+        builder.connect_nonnative(&xt, &x_again);
+
+        let wide_targets = (0..wide_bits.len())
+            .map(|_| builder.add_virtual_bool_target_safe())
+            .collect::<Vec<_>>();
+        for (&t, &bit) in wide_targets.iter().zip(&wide_bits) {
+            pw.set_bool_target(t, bit);
+        }
+        let reduced = builder.reduce_le_bits_nonnative::<FF>(&wide_targets);
+        let expected = builder.constant_nonnative(FF::from_noncanonical_biguint(wide));
+        builder.connect_nonnative(&reduced, &expected);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
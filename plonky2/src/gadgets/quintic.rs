@@ -0,0 +1,316 @@
+//! Arithmetic in the quintic extension `GF(p^5) = GF(p)[z] / (z^5 - 3)` of the Goldilocks field,
+//! over which the ecGFp5 curve of [`gadgets::ecgfp5`](crate::gadgets::ecgfp5) is defined.
+//!
+//! Unlike [`ExtensionTarget`](crate::iop::ext_target::ExtensionTarget)s, whose degree is that of
+//! the extension used by the proof system, these targets always have five coefficients. The
+//! circuit must be over the Goldilocks field.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::curve::ecgfp5::quintic_sqrt;
+use crate::field::extension::quintic::QuinticExtension;
+use crate::field::extension::Extendable;
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::Field;
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+/// The element `W = z^5` of the Goldilocks field.
+const QUINTIC_W: u64 = 3;
+
+/// An element of `GF(p^5)`, as its five coefficients in the basis `1, z, ..., z^4`.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct QuinticExtensionTarget(pub [Target; 5]);
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    pub fn add_virtual_quintic_extension_target(&mut self) -> QuinticExtensionTarget {
+        QuinticExtensionTarget(self.add_virtual_target_arr())
+    }
+
+    pub fn constant_quintic_extension(
+        &mut self,
+        c: QuinticExtension<GoldilocksField>,
+    ) -> QuinticExtensionTarget {
+        QuinticExtensionTarget(c.0.map(|coeff| self.constant(F::from_canonical_u64(coeff.0))))
+    }
+
+    pub fn zero_quintic_extension(&mut self) -> QuinticExtensionTarget {
+        let zero = self.zero();
+        QuinticExtensionTarget([zero; 5])
+    }
+
+    pub fn one_quintic_extension(&mut self) -> QuinticExtensionTarget {
+        let zero = self.zero();
+        let one = self.one();
+        QuinticExtensionTarget([one, zero, zero, zero, zero])
+    }
+
+    pub fn connect_quintic_extension(
+        &mut self,
+        lhs: QuinticExtensionTarget,
+        rhs: QuinticExtensionTarget,
+    ) {
+        for (l, r) in lhs.0.into_iter().zip(rhs.0) {
+            self.connect(l, r);
+        }
+    }
+
+    pub fn add_quintic_extension(
+        &mut self,
+        a: QuinticExtensionTarget,
+        b: QuinticExtensionTarget,
+    ) -> QuinticExtensionTarget {
+        QuinticExtensionTarget(core::array::from_fn(|i| self.add(a.0[i], b.0[i])))
+    }
+
+    pub fn sub_quintic_extension(
+        &mut self,
+        a: QuinticExtensionTarget,
+        b: QuinticExtensionTarget,
+    ) -> QuinticExtensionTarget {
+        QuinticExtensionTarget(core::array::from_fn(|i| self.sub(a.0[i], b.0[i])))
+    }
+
+    pub fn neg_quintic_extension(&mut self, a: QuinticExtensionTarget) -> QuinticExtensionTarget {
+        QuinticExtensionTarget(a.0.map(|a| self.neg(a)))
+    }
+
+    /// Returns `c a`, for a constant `c` of the Goldilocks field.
+    pub fn mul_const_quintic_extension(
+        &mut self,
+        c: F,
+        a: QuinticExtensionTarget,
+    ) -> QuinticExtensionTarget {
+        QuinticExtensionTarget(a.0.map(|a| self.mul_const(c, a)))
+    }
+
+    pub fn mul_quintic_extension(
+        &mut self,
+        a: QuinticExtensionTarget,
+        b: QuinticExtensionTarget,
+    ) -> QuinticExtensionTarget {
+        // The coefficient `k` of the product is `sum_{i + j = k} a_i b_j` plus
+        // `W sum_{i + j = k + 5} a_i b_j`, as `z^5 = W`.
+        let w = F::from_canonical_u64(QUINTIC_W);
+        let mut c = [self.zero(); 5];
+        for i in 0..5 {
+            for j in 0..5 {
+                let (k, coeff) = if i + j < 5 {
+                    (i + j, F::ONE)
+                } else {
+                    (i + j - 5, w)
+                };
+                c[k] = self.arithmetic(coeff, F::ONE, a.0[i], b.0[j], c[k]);
+            }
+        }
+        QuinticExtensionTarget(c)
+    }
+
+    /// Returns `a^2`, with 15 rather than 25 products of coefficients.
+    pub fn square_quintic_extension(
+        &mut self,
+        a: QuinticExtensionTarget,
+    ) -> QuinticExtensionTarget {
+        let w = F::from_canonical_u64(QUINTIC_W);
+        let mut c = [self.zero(); 5];
+        for i in 0..5 {
+            for j in i..5 {
+                let (k, mut coeff) = if i + j < 5 {
+                    (i + j, F::ONE)
+                } else {
+                    (i + j - 5, w)
+                };
+                if i != j {
+                    coeff = coeff.double();
+                }
+                c[k] = self.arithmetic(coeff, F::ONE, a.0[i], a.0[j], c[k]);
+            }
+        }
+        QuinticExtensionTarget(c)
+    }
+
+    /// Computes `a / b`. Results in an unsatisfiable instance if `b = 0`.
+    pub fn div_quintic_extension(
+        &mut self,
+        a: QuinticExtensionTarget,
+        b: QuinticExtensionTarget,
+    ) -> QuinticExtensionTarget {
+        let inv = self.inverse_quintic_extension(b);
+        self.mul_quintic_extension(a, inv)
+    }
+
+    /// Computes `1 / a`. Results in an unsatisfiable instance if `a = 0`.
+    pub fn inverse_quintic_extension(
+        &mut self,
+        a: QuinticExtensionTarget,
+    ) -> QuinticExtensionTarget {
+        let inv = self.add_virtual_quintic_extension_target();
+        self.add_simple_generator(QuinticInverseGenerator { x: a, inverse: inv });
+
+        // Enforce that `a` times its purported inverse equals 1.
+        let a_inv = self.mul_quintic_extension(a, inv);
+        let one = self.one_quintic_extension();
+        self.connect_quintic_extension(a_inv, one);
+
+        inv
+    }
+
+    /// Results in an unsatisfiable instance if `a` is not a square in `GF(p^5)`.
+    pub fn assert_square_quintic_extension(&mut self, a: QuinticExtensionTarget) {
+        let root = self.add_virtual_quintic_extension_target();
+        self.add_simple_generator(QuinticSqrtGenerator { x: a, root });
+        let root_squared = self.square_quintic_extension(root);
+        self.connect_quintic_extension(root_squared, a);
+    }
+
+    /// Selects `x` or `y` based on `b`, i.e., this returns `if b { x } else { y }`.
+    pub fn select_quintic_extension(
+        &mut self,
+        b: BoolTarget,
+        x: QuinticExtensionTarget,
+        y: QuinticExtensionTarget,
+    ) -> QuinticExtensionTarget {
+        QuinticExtensionTarget(core::array::from_fn(|i| self.select(b, x.0[i], y.0[i])))
+    }
+
+    /// Returns `v[access_index]`, for a power-of-two number of elements.
+    pub fn random_access_quintic_extension(
+        &mut self,
+        access_index: Target,
+        v: &[QuinticExtensionTarget],
+    ) -> QuinticExtensionTarget {
+        QuinticExtensionTarget(core::array::from_fn(|i| {
+            self.random_access(access_index, v.iter().map(|x| x.0[i]).collect())
+        }))
+    }
+}
+
+/// Computes the inverse of `x`, or zero if `x = 0`.
+#[derive(Debug, Default)]
+pub struct QuinticInverseGenerator {
+    x: QuinticExtensionTarget,
+    inverse: QuinticExtensionTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D>
+    for QuinticInverseGenerator
+{
+    fn id(&self) -> String {
+        "QuinticInverseGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.x.0.to_vec()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_quintic_extension_target(self.x);
+        // If `x = 0`, any value fails the inverse check.
+        let inverse = x.try_inverse().unwrap_or_default();
+        out_buffer.set_quintic_extension_target(self.inverse, inverse);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_array(&self.x.0)?;
+        dst.write_target_array(&self.inverse.0)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let x = QuinticExtensionTarget(src.read_target_array()?);
+        let inverse = QuinticExtensionTarget(src.read_target_array()?);
+        Ok(Self { x, inverse })
+    }
+}
+
+/// Computes a square root of `x`, or zero if `x` is not a square.
+#[derive(Debug, Default)]
+pub struct QuinticSqrtGenerator {
+    x: QuinticExtensionTarget,
+    root: QuinticExtensionTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for QuinticSqrtGenerator {
+    fn id(&self) -> String {
+        "QuinticSqrtGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        self.x.0.to_vec()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let x = witness.get_quintic_extension_target(self.x);
+        // If `x` is not a square, any value fails the square check.
+        let root = quintic_sqrt(x).unwrap_or_default();
+        out_buffer.set_quintic_extension_target(self.root, root);
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target_array(&self.x.0)?;
+        dst.write_target_array(&self.root.0)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let x = QuinticExtensionTarget(src.read_target_array()?);
+        let root = QuinticExtensionTarget(src.read_target_array()?);
+        Ok(Self { x, root })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::extension::quintic::QuinticExtension;
+    use crate::field::goldilocks_field::GoldilocksField;
+    use crate::field::ops::Square;
+    use crate::field::types::Sample;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type FF = QuinticExtension<GoldilocksField>;
+
+    #[test]
+    fn test_quintic_extension_arithmetic() -> Result<()> {
+        let (a, b) = (FF::rand(), FF::rand());
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let at = builder.add_virtual_quintic_extension_target();
+        let bt = builder.add_virtual_quintic_extension_target();
+        pw.set_quintic_extension_target(at, a);
+        pw.set_quintic_extension_target(bt, b);
+
+        let results = [
+            (builder.add_quintic_extension(at, bt), a + b),
+            (builder.sub_quintic_extension(at, bt), a - b),
+            (builder.mul_quintic_extension(at, bt), a * b),
+            (builder.square_quintic_extension(at), a.square()),
+            (builder.div_quintic_extension(at, bt), a / b),
+        ];
+        for (result, expected) in results {
+            let expected = builder.constant_quintic_extension(expected);
+            builder.connect_quintic_extension(result, expected);
+        }
+        let a_squared = builder.square_quintic_extension(at);
+        builder.assert_square_quintic_extension(a_squared);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+}
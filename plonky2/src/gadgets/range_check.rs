@@ -1,3 +1,4 @@
+use alloc::sync::Arc;
 use alloc::vec;
 use alloc::vec::Vec;
 
@@ -10,6 +11,12 @@ use crate::plonk::circuit_builder::CircuitBuilder;
 use crate::plonk::circuit_data::CommonCircuitData;
 use crate::util::serialization::{Buffer, IoResult, Read, Write};
 
+/// Name of the lookup table of all 16-bit values, used by [`CircuitBuilder::range_check_u16`].
+const U16_RANGE_LUT: &str = "u16_range";
+
+/// Name of the lookup table of all 8-bit values, used by [`CircuitBuilder::range_check_u8`].
+const U8_RANGE_LUT: &str = "u8_range";
+
 impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
     /// Checks that `x < 2^n_log` using a `BaseSumGate`.
     pub fn range_check(&mut self, x: Target, n_log: usize) {
@@ -46,6 +53,72 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         (low, high)
     }
 
+    /// Checks that `x < 2^16` with a lookup into the table of all 16-bit values.
+    pub fn range_check_u16(&mut self, x: Target) {
+        let lut_index = self.get_or_add_named_lut(U16_RANGE_LUT, || {
+            Arc::new((0..=u16::MAX).map(|i| (i, i)).collect())
+        });
+        self.add_lookup_from_index(x, lut_index);
+    }
+
+    /// Returns the `num_limbs` little-endian 16-bit limbs of `x`, each range checked with
+    /// [`Self::range_check_u16`]. `x` is assumed to be less than `2^(16 * num_limbs)`.
+    pub fn split_le_u16(&mut self, x: Target, num_limbs: usize) -> Vec<Target> {
+        assert!(
+            16 * num_limbs < 64,
+            "{} 16-bit limbs may overflow the field",
+            num_limbs
+        );
+        let limbs = self.add_virtual_targets(num_limbs);
+        self.add_simple_generator(U16LimbsGenerator {
+            integer: x,
+            limbs: limbs.clone(),
+        });
+
+        let base = self.constant(F::from_canonical_u32(1 << 16));
+        let mut sum = self.zero();
+        for &limb in limbs.iter().rev() {
+            self.range_check_u16(limb);
+            sum = self.mul_add(sum, base, limb);
+        }
+        self.connect(x, sum);
+
+        limbs
+    }
+
+    /// Checks that `x < 2^8` with a lookup into the table of all 8-bit values.
+    pub fn range_check_u8(&mut self, x: Target) {
+        let lut_index = self.get_or_add_named_lut(U8_RANGE_LUT, || {
+            Arc::new((0..=u8::MAX as u16).map(|i| (i, i)).collect())
+        });
+        self.add_lookup_from_index(x, lut_index);
+    }
+
+    /// Returns the `num_bytes` little-endian bytes of `x`, each range checked with
+    /// [`Self::range_check_u8`]. `x` is assumed to be less than `2^(8 * num_bytes)`.
+    pub fn split_le_u8(&mut self, x: Target, num_bytes: usize) -> Vec<Target> {
+        assert!(
+            8 * num_bytes < 64,
+            "{} bytes may overflow the field",
+            num_bytes
+        );
+        let bytes = self.add_virtual_targets(num_bytes);
+        self.add_simple_generator(U8LimbsGenerator {
+            integer: x,
+            bytes: bytes.clone(),
+        });
+
+        let base = self.constant(F::from_canonical_u32(1 << 8));
+        let mut sum = self.zero();
+        for &byte in bytes.iter().rev() {
+            self.range_check_u8(byte);
+            sum = self.mul_add(sum, base, byte);
+        }
+        self.connect(x, sum);
+
+        bytes
+    }
+
     pub fn assert_bool(&mut self, b: BoolTarget) {
         let z = self.mul_sub(b.target, b.target, b.target);
         let zero = self.zero();
@@ -99,3 +172,75 @@ impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for Low
         })
     }
 }
+
+#[derive(Debug, Default)]
+pub struct U16LimbsGenerator {
+    integer: Target,
+    limbs: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for U16LimbsGenerator {
+    fn id(&self) -> String {
+        "U16LimbsGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.integer]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let mut integer_value = witness.get_target(self.integer).to_canonical_u64();
+        for &limb in &self.limbs {
+            out_buffer.set_target(limb, F::from_canonical_u64(integer_value & 0xffff));
+            integer_value >>= 16;
+        }
+        debug_assert_eq!(integer_value, 0, "Integer does not fit in the limbs");
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.integer)?;
+        dst.write_target_vec(&self.limbs)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let integer = src.read_target()?;
+        let limbs = src.read_target_vec()?;
+        Ok(Self { integer, limbs })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct U8LimbsGenerator {
+    integer: Target,
+    bytes: Vec<Target>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F, D> for U8LimbsGenerator {
+    fn id(&self) -> String {
+        "U8LimbsGenerator".to_string()
+    }
+
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.integer]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let mut integer_value = witness.get_target(self.integer).to_canonical_u64();
+        for &byte in &self.bytes {
+            out_buffer.set_target(byte, F::from_canonical_u64(integer_value & 0xff));
+            integer_value >>= 8;
+        }
+        debug_assert_eq!(integer_value, 0, "Integer does not fit in the bytes");
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_target(self.integer)?;
+        dst.write_target_vec(&self.bytes)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self> {
+        let integer = src.read_target()?;
+        let bytes = src.read_target_vec()?;
+        Ok(Self { integer, bytes })
+    }
+}
@@ -0,0 +1,185 @@
+//! Verification of the Schnorr signatures over the ecGFp5 curve of
+//! [`curve::schnorr`](crate::curve::schnorr).
+
+use alloc::vec::Vec;
+
+use crate::curve::ecgfp5::EcGFp5Point;
+use crate::field::ecgfp5_scalar::EcGFp5Scalar;
+use crate::field::extension::Extendable;
+use crate::gadgets::ecgfp5::EcGFp5PointTarget;
+use crate::gadgets::nonnative::{u16_limbs_of, NonNativeTarget, NONNATIVE_LIMB_BITS};
+use crate::hash::hash_types::RichField;
+use crate::hash::poseidon::PoseidonHash;
+use crate::iop::target::Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+#[derive(Clone, Debug)]
+pub struct SchnorrPublicKeyTarget(pub EcGFp5PointTarget);
+
+#[derive(Clone, Debug)]
+pub struct SchnorrSignatureTarget {
+    pub s: NonNativeTarget<EcGFp5Scalar>,
+    pub e: NonNativeTarget<EcGFp5Scalar>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Checks that `sig` is a valid signature of `msg` under `pk`. As in
+    /// [`verify_message`](crate::curve::schnorr::verify_message), neither `pk` nor `r` may be the
+    /// neutral point.
+    pub fn verify_schnorr(
+        &mut self,
+        msg: &[Target],
+        sig: &SchnorrSignatureTarget,
+        pk: &SchnorrPublicKeyTarget,
+    ) {
+        // A valid point has affine coordinates, so it isn't neutral.
+        self.ecgfp5_assert_valid(&pk.0);
+
+        // r = s G + e pk, and e = H(r, pk, msg). The MSM is unsatisfiable if `r` is neutral.
+        let g_table = self.ecgfp5_constant_window_table(EcGFp5Point::GENERATOR);
+        let pk_table = self.ecgfp5_window_table(&pk.0);
+        let s_windows = self.split_limbs_to_windows(sig.s.limbs());
+        let e_windows = self.split_limbs_to_windows(sig.e.limbs());
+        let r = self.ecgfp5_msm_windowed(&[g_table, pk_table], &[s_windows, e_windows]);
+
+        let e = self.schnorr_challenge(&r, pk, msg);
+        self.connect_nonnative(&e, &sig.e);
+    }
+
+    /// Returns the challenge `H(r, pk, msg)`, whose four elements are the 64-bit limbs of a
+    /// scalar.
+    fn schnorr_challenge(
+        &mut self,
+        r: &EcGFp5PointTarget,
+        pk: &SchnorrPublicKeyTarget,
+        msg: &[Target],
+    ) -> NonNativeTarget<EcGFp5Scalar> {
+        let inputs = [r.x.0, r.y.0, pk.0.x.0, pk.0.y.0]
+            .concat()
+            .into_iter()
+            .chain(msg.iter().copied())
+            .collect::<Vec<_>>();
+        let hash = self.hash_n_to_hash_no_pad::<PoseidonHash>(inputs);
+
+        // The 16-bit limbs of each element must be those of its canonical representative. The 64
+        // bits of an element only determine it modulo the field order, hence the comparison.
+        let order_limbs = u16_limbs_of(&F::order(), 4);
+        let order_limbs = self.constants(&order_limbs);
+        let mut limbs = Vec::with_capacity(NonNativeTarget::<EcGFp5Scalar>::num_limbs());
+        for h in hash.elements {
+            let h_limbs = self
+                .split_le(h, 64)
+                .chunks(NONNATIVE_LIMB_BITS)
+                .map(|bits| self.le_sum(bits.iter()))
+                .collect::<Vec<_>>();
+            let geq_order = self.limbs_geq(&h_limbs, &order_limbs);
+            self.assert_zero(geq_order.target);
+            limbs.extend(h_limbs);
+        }
+        // The challenge is less than `2^256`, so it is canonical.
+        let zero = self.zero();
+        limbs.resize(NonNativeTarget::<EcGFp5Scalar>::num_limbs(), zero);
+        NonNativeTarget::from_limbs(limbs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::curve::ecgfp5::{EcGFp5Base, EcGFp5Point};
+    use crate::curve::schnorr::{
+        sign_message, verify_message, SchnorrPublicKey, SchnorrSecretKey, SchnorrSignature,
+    };
+    use crate::field::ecgfp5_scalar::EcGFp5Scalar;
+    use crate::field::types::{Field, Sample};
+    use crate::gadgets::ecgfp5::EcGFp5PointTarget;
+    use crate::gadgets::schnorr::{SchnorrPublicKeyTarget, SchnorrSignatureTarget};
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Proves that `sig` is a valid signature of `msg` under `pk`. The public key target isn't
+    /// checked when it is added, so that `verify_schnorr` has to reject invalid keys itself.
+    fn test_schnorr_circuit(msg: &[F], sig: SchnorrSignature, pk: EcGFp5Point) -> Result<()> {
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let msg_t = builder.add_virtual_targets(msg.len());
+        pw.set_target_arr(&msg_t, msg);
+        let pk_t = SchnorrPublicKeyTarget(EcGFp5PointTarget {
+            x: builder.add_virtual_quintic_extension_target(),
+            y: builder.add_virtual_quintic_extension_target(),
+        });
+        pw.set_ecgfp5_point_target(&pk_t.0, pk);
+        let sig_t = SchnorrSignatureTarget {
+            s: builder.add_virtual_nonnative_target(),
+            e: builder.add_virtual_nonnative_target(),
+        };
+        pw.set_nonnative_target(&sig_t.s, sig.s);
+        pw.set_nonnative_target(&sig_t.e, sig.e);
+
+        builder.verify_schnorr(&msg_t, &sig_t, &pk_t);
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    fn test_schnorr_circuit_valid() -> Result<()> {
+        let msg = F::rand_vec(5);
+        let sk = SchnorrSecretKey(EcGFp5Scalar::rand());
+        let sig = sign_message(&msg, sk);
+        test_schnorr_circuit(&msg, sig, sk.to_public().0)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_schnorr_circuit_invalid() {
+        let msg = F::rand_vec(5);
+        let sk = SchnorrSecretKey(EcGFp5Scalar::rand());
+        let mut sig = sign_message(&msg, sk);
+        sig.s += EcGFp5Scalar::ONE;
+        test_schnorr_circuit(&msg, sig, sk.to_public().0).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_schnorr_circuit_neutral_r() {
+        // s G + e pk = (s + e sk) G is neutral.
+        let msg = F::rand_vec(5);
+        let sk = SchnorrSecretKey(EcGFp5Scalar::rand());
+        let e = EcGFp5Scalar::rand();
+        let sig = SchnorrSignature { s: -e * sk.0, e };
+        let pk = sk.to_public();
+        assert!(!verify_message(&msg, sig, pk));
+        test_schnorr_circuit(&msg, sig, pk.0).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_schnorr_circuit_neutral_pk() {
+        // The neutral point has no affine coordinates, so a prover can only try to pass it off as
+        // `(0, 0)`.
+        let msg = F::rand_vec(5);
+        let sig = SchnorrSignature {
+            s: EcGFp5Scalar::rand(),
+            e: EcGFp5Scalar::rand(),
+        };
+        assert!(!verify_message(
+            &msg,
+            sig,
+            SchnorrPublicKey(EcGFp5Point::ZERO)
+        ));
+        let pk = EcGFp5Point::nonzero(EcGFp5Base::ZERO, EcGFp5Base::ZERO);
+        test_schnorr_circuit(&msg, sig, pk).unwrap();
+    }
+}
@@ -0,0 +1,280 @@
+//! The SHA-256 hash over byte targets, with bitwise operations done by byte lookups.
+
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+const SHA256_BLOCK_BYTES: usize = 64;
+
+/// The number of bytes of padding after the message: the `0x80` byte and the 64-bit length.
+const SHA256_MIN_PADDING_BYTES: usize = 9;
+
+const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA256_ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns the SHA-256 hash of a fixed-length message, given as bytes, as 32 bytes.
+    pub fn sha256(&mut self, input: &[Target]) -> Vec<Target> {
+        let length = self.constant(F::from_canonical_usize(input.len()));
+        self.sha256_variable_length(input, length)
+    }
+
+    /// Returns the SHA-256 hash of the first `length` bytes of `input`, as 32 bytes. The bytes of
+    /// `input` past the length are ignored, and `length` must be at most `input.len()`.
+    ///
+    /// The cost is that of hashing a message of the maximal length, and all the input bytes are
+    /// range checked.
+    pub fn sha256_variable_length(&mut self, input: &[Target], length: Target) -> Vec<Target> {
+        for &byte in input {
+            self.range_check_u8(byte);
+        }
+        let max_length = input.len();
+        let (is_length, is_before_length) = self.variable_length_flags(length, max_length);
+        let num_blocks = (max_length + SHA256_MIN_PADDING_BYTES).div_ceil(SHA256_BLOCK_BYTES);
+
+        // The block `k` is the last one if the 64-bit length ends it, i.e. if
+        // `64 k - 9 < length <= 64 k + 55`.
+        let is_last_block = (0..num_blocks)
+            .map(|k| {
+                let first = (SHA256_BLOCK_BYTES * k).saturating_sub(SHA256_MIN_PADDING_BYTES - 1);
+                let last =
+                    (SHA256_BLOCK_BYTES * (k + 1) - SHA256_MIN_PADDING_BYTES).min(max_length);
+                let sum = self.add_many(is_length[first..=last].iter().map(|b| b.target));
+                BoolTarget::new_unsafe(sum)
+            })
+            .collect::<Vec<_>>();
+
+        // The length in bits, as a big-endian 64-bit integer. Lengths are far below `2^29`.
+        let bit_length = self.mul_const(F::from_canonical_u8(8), length);
+        let bit_length_bytes = self.split_le_u8(bit_length, 4);
+
+        let padding_byte = F::from_canonical_u8(0x80);
+        let mut state = SHA256_INITIAL_STATE
+            .iter()
+            .map(|&h| self.constant_word(h as u64, 4))
+            .collect::<Vec<_>>();
+        let mut final_states = Vec::with_capacity(num_blocks);
+        for (k, &is_last) in is_last_block.iter().enumerate() {
+            let block = (SHA256_BLOCK_BYTES * k..SHA256_BLOCK_BYTES * (k + 1))
+                .map(|i| {
+                    let mut byte = self.masked_message_byte(input, &is_before_length, i);
+                    if i <= max_length {
+                        byte = self.mul_const_add(padding_byte, is_length[i].target, byte);
+                    }
+                    let position = i % SHA256_BLOCK_BYTES;
+                    if position >= SHA256_BLOCK_BYTES - 4 {
+                        let length_byte = bit_length_bytes[SHA256_BLOCK_BYTES - 1 - position];
+                        byte = self.mul_add(is_last.target, length_byte, byte);
+                    }
+                    byte
+                })
+                .collect::<Vec<_>>();
+
+            state = self.sha256_compress(&state, &block);
+            final_states.push(state.concat());
+        }
+
+        // Words are big-endian in the digest.
+        self.select_word_one_hot(&is_last_block, &final_states)
+            .chunks(4)
+            .flat_map(|word| word.iter().rev().copied())
+            .collect()
+    }
+
+    /// The SHA-256 compression function, over words as little-endian bytes.
+    fn sha256_compress(&mut self, state: &[Vec<Target>], block: &[Target]) -> Vec<Vec<Target>> {
+        let mut w = block
+            .chunks(4)
+            .map(|word| word.iter().rev().copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for t in 16..64 {
+            let s0 = self.sha256_sigma(&w[t - 15], 7, 18, 3);
+            let s1 = self.sha256_sigma(&w[t - 2], 17, 19, 10);
+            let word = self.add_words_wrapping(&[&w[t - 16], &s0, &w[t - 7], &s1]);
+            w.push(word);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h]: [Vec<Target>; 8] =
+            state.to_vec().try_into().unwrap();
+        for (t, &round_constant) in SHA256_ROUND_CONSTANTS.iter().enumerate() {
+            let s1 = self.sha256_big_sigma(&e, 6, 11, 25);
+            // ch = (e & f) ^ (!e & g), whose two terms have no bits in common.
+            let e_and_f = self.and_word(&e, &f);
+            let not_e = self.not_word(&e);
+            let not_e_and_g = self.and_word(&not_e, &g);
+            let ch = self.add_disjoint_words(&e_and_f, &not_e_and_g);
+            let k = self.constant_word(round_constant as u64, 4);
+            let temp1 = self.add_words_wrapping(&[&h, &s1, &ch, &k, &w[t]]);
+
+            let s0 = self.sha256_big_sigma(&a, 2, 13, 22);
+            // maj = (a & b) | (c & (a ^ b)), whose two terms have no bits in common.
+            let a_and_b = self.and_word(&a, &b);
+            let a_xor_b = self.xor_word(&a, &b);
+            let c_and_a_xor_b = self.and_word(&c, &a_xor_b);
+            let maj = self.add_disjoint_words(&a_and_b, &c_and_a_xor_b);
+            let temp2 = self.add_words_wrapping(&[&s0, &maj]);
+
+            h = g;
+            g = f;
+            f = e;
+            e = self.add_words_wrapping(&[&d, &temp1]);
+            d = c;
+            c = b;
+            b = a;
+            a = self.add_words_wrapping(&[&temp1, &temp2]);
+        }
+
+        [a, b, c, d, e, f, g, h]
+            .iter()
+            .zip(state)
+            .map(|(x, s)| self.add_words_wrapping(&[s, x]))
+            .collect()
+    }
+
+    /// The message schedule functions `sigma_0` and `sigma_1`.
+    fn sha256_sigma(&mut self, x: &[Target], r1: usize, r2: usize, s: usize) -> Vec<Target> {
+        let x_r1 = self.rotate_right_word(x, r1);
+        let x_r2 = self.rotate_right_word(x, r2);
+        let x_s = self.shift_right_word(x, s);
+        let x_r1_r2 = self.xor_word(&x_r1, &x_r2);
+        self.xor_word(&x_r1_r2, &x_s)
+    }
+
+    /// The round functions `Sigma_0` and `Sigma_1`.
+    fn sha256_big_sigma(&mut self, x: &[Target], r1: usize, r2: usize, r3: usize) -> Vec<Target> {
+        let x_r1 = self.rotate_right_word(x, r1);
+        let x_r2 = self.rotate_right_word(x, r2);
+        let x_r3 = self.rotate_right_word(x, r3);
+        let x_r1_r2 = self.xor_word(&x_r1, &x_r2);
+        self.xor_word(&x_r1_r2, &x_r3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::field::types::Field;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    /// Proves the hashes of the first `length` bytes of `0, 1, 2, ...`, for each of the given
+    /// lengths, with a maximal length of `max_length`.
+    fn test_sha256_lengths(max_length: usize, cases: &[(usize, &str)]) -> Result<()> {
+        let input = (0..max_length).map(|i| i as u8).collect::<Vec<_>>();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let input_t = builder.add_virtual_targets(max_length);
+        for (&t, &byte) in input_t.iter().zip(&input) {
+            pw.set_target(t, F::from_canonical_u8(byte));
+        }
+        for &(length, expected) in cases {
+            let length_t = builder.constant(F::from_canonical_usize(length));
+            let digest = builder.sha256_variable_length(&input_t, length_t);
+            for (byte, expected) in digest.into_iter().zip(bytes_from_hex(expected)) {
+                let expected = builder.constant(F::from_canonical_u8(expected));
+                builder.connect(byte, expected);
+            }
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    fn bytes_from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_sha256() -> Result<()> {
+        test_sha256_lengths(
+            3,
+            &[
+                (
+                    0,
+                    "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+                ),
+                (
+                    3,
+                    "ae4b3280e56e2faf83f414a6e3dabe9d5fbe18976544c05fed121accb85b53fc",
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_sha256_two_blocks() -> Result<()> {
+        test_sha256_lengths(
+            70,
+            &[
+                (
+                    0,
+                    "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+                ),
+                (
+                    55,
+                    "463eb28e72f82e0a96c0a4cc53690c571281131f672aa229e0d45ae59b598b59",
+                ),
+                (
+                    56,
+                    "da2ae4d6b36748f2a318f23e7ab1dfdf45acdc9d049bd80e59de82a60895f562",
+                ),
+                (
+                    64,
+                    "fdeab9acf3710362bd2658cdc9a29e8f9c757fcf9811603a8c447cd1d9151108",
+                ),
+            ],
+        )
+    }
+
+    #[test]
+    #[ignore] // Too slow to run on CI.
+    fn test_sha256_variable_length() -> Result<()> {
+        test_sha256_lengths(
+            120,
+            &[
+                (
+                    55,
+                    "463eb28e72f82e0a96c0a4cc53690c571281131f672aa229e0d45ae59b598b59",
+                ),
+                (
+                    56,
+                    "da2ae4d6b36748f2a318f23e7ab1dfdf45acdc9d049bd80e59de82a60895f562",
+                ),
+                (
+                    119,
+                    "da18797ed7c3a777f0847f429724a2d8cd5138e6ed2895c3fa1a6d39d18f7ec6",
+                ),
+            ],
+        )
+    }
+}
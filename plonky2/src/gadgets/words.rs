@@ -0,0 +1,176 @@
+//! Words of bytes, with the wrapping additions and bitwise operations used by hash functions.
+//!
+//! A word is a vector of byte targets in little-endian order. All the bytes produced here are
+//! known to be bytes, either as outputs of lookups or as sums of disjoint bit masks, so that the
+//! words can be fed to further byte lookups.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Returns the `num_bytes` little-endian bytes of `value` as constants.
+    pub(crate) fn constant_word(&mut self, value: u64, num_bytes: usize) -> Vec<Target> {
+        (0..num_bytes)
+            .map(|i| self.constant(F::from_canonical_u64((value >> (8 * i)) & 0xff)))
+            .collect()
+    }
+
+    pub(crate) fn xor_word(&mut self, a: &[Target], b: &[Target]) -> Vec<Target> {
+        assert_eq!(a.len(), b.len(), "Word sizes differ");
+        a.iter().zip(b).map(|(&a, &b)| self.xor_u8(a, b)).collect()
+    }
+
+    pub(crate) fn and_word(&mut self, a: &[Target], b: &[Target]) -> Vec<Target> {
+        assert_eq!(a.len(), b.len(), "Word sizes differ");
+        a.iter().zip(b).map(|(&a, &b)| self.and_u8(a, b)).collect()
+    }
+
+    pub(crate) fn not_word(&mut self, a: &[Target]) -> Vec<Target> {
+        let byte_max = self.constant(F::from_canonical_u8(u8::MAX));
+        a.iter().map(|&a| self.sub(byte_max, a)).collect()
+    }
+
+    /// Returns the bytewise sum of `a` and `b`, which is their bitwise or when they have no bits in
+    /// common.
+    pub(crate) fn add_disjoint_words(&mut self, a: &[Target], b: &[Target]) -> Vec<Target> {
+        assert_eq!(a.len(), b.len(), "Word sizes differ");
+        a.iter().zip(b).map(|(&a, &b)| self.add(a, b)).collect()
+    }
+
+    /// Returns `a` rotated right by `n` bits.
+    pub(crate) fn rotate_right_word(&mut self, a: &[Target], n: usize) -> Vec<Target> {
+        let len = a.len();
+        let rotated = (0..len).map(|i| a[(i + n / 8) % len]).collect::<Vec<_>>();
+        self.shift_bytes_right(&rotated, n % 8, true)
+    }
+
+    /// Returns `a` shifted right by `n` bits.
+    pub(crate) fn shift_right_word(&mut self, a: &[Target], n: usize) -> Vec<Target> {
+        let zero = self.zero();
+        let len = a.len();
+        let shifted = (0..len)
+            .map(|i| a.get(i + n / 8).copied().unwrap_or(zero))
+            .collect::<Vec<_>>();
+        self.shift_bytes_right(&shifted, n % 8, false)
+    }
+
+    /// Shifts `a` right by `k < 8` bits, bringing the low bits of the top byte around if `rotate`
+    /// is set.
+    fn shift_bytes_right(&mut self, a: &[Target], k: usize, rotate: bool) -> Vec<Target> {
+        if k == 0 {
+            return a.to_vec();
+        }
+        // Each byte `a_i` splits as `2^k high_i + low_i`, and the result is
+        // `high_i + 2^(8 - k) low_{i+1}`.
+        let high = a.iter().map(|&a| self.shr_u8(a, k)).collect::<Vec<_>>();
+        let low_shift = F::from_canonical_u16(1 << (8 - k));
+        let high_shift = F::from_canonical_u16(1 << 8);
+        (0..a.len())
+            .map(|i| {
+                let next = if i + 1 < a.len() {
+                    i + 1
+                } else if rotate {
+                    0
+                } else {
+                    return high[i];
+                };
+                let sum = self.mul_const_add(low_shift, a[next], high[i]);
+                self.mul_const_add(-high_shift, high[next], sum)
+            })
+            .collect()
+    }
+
+    /// Returns the sum of `words` modulo `2^(8 n)`, where `n` is their number of bytes. Columns of
+    /// four bytes are summed in the native field, and split back into bytes and a carry.
+    pub(crate) fn add_words_wrapping<W: AsRef<[Target]>>(&mut self, words: &[W]) -> Vec<Target> {
+        assert!(words.len() < 1 << 8, "Too many words to add");
+        let len = words[0].as_ref().len();
+        assert!(
+            words.iter().all(|w| w.as_ref().len() == len),
+            "Word sizes differ"
+        );
+
+        let mut result = Vec::with_capacity(len);
+        let mut carry = self.zero();
+        for start in (0..len).step_by(4) {
+            let end = (start + 4).min(len);
+            let mut sum = carry;
+            for word in words {
+                for (j, &byte) in word.as_ref()[start..end].iter().enumerate() {
+                    sum = self.mul_const_add(F::from_canonical_u64(1 << (8 * j)), byte, sum);
+                }
+            }
+            // The sum is less than `2^40`, so one more byte holds the carry.
+            let mut bytes = self.split_le_u8(sum, end - start + 1);
+            carry = bytes.pop().unwrap();
+            result.extend(bytes);
+        }
+        result
+    }
+
+    /// Returns `sum_i flags[i] words[i]`, i.e. the word whose flag is set, given at most one set
+    /// flag.
+    pub(crate) fn select_word_one_hot(
+        &mut self,
+        flags: &[BoolTarget],
+        words: &[Vec<Target>],
+    ) -> Vec<Target> {
+        let zero = self.zero();
+        let mut result = vec![zero; words[0].len()];
+        for (&flag, word) in flags.iter().zip(words) {
+            for (r, &byte) in result.iter_mut().zip(word) {
+                *r = self.mul_add(flag.target, byte, *r);
+            }
+        }
+        result
+    }
+
+    /// Returns flags `(is_length, is_before_length)`, where `is_length[i]` tells whether
+    /// `length = i` and `is_before_length[i]` whether `i < length`, for `i` up to `max_length`.
+    /// Results in an unsatisfiable instance if `length > max_length`.
+    pub(crate) fn variable_length_flags(
+        &mut self,
+        length: Target,
+        max_length: usize,
+    ) -> (Vec<BoolTarget>, Vec<BoolTarget>) {
+        let is_length = (0..=max_length)
+            .map(|i| {
+                let i = self.constant(F::from_canonical_usize(i));
+                self.is_equal(length, i)
+            })
+            .collect::<Vec<_>>();
+        // Exactly one flag is set, so the complements of their prefix sums are booleans.
+        let one = self.one();
+        let mut seen_length = self.zero();
+        let is_before_length = is_length
+            .iter()
+            .map(|&is_length| {
+                seen_length = self.add(seen_length, is_length.target);
+                BoolTarget::new_unsafe(self.sub(one, seen_length))
+            })
+            .collect();
+        self.connect(seen_length, one);
+
+        (is_length, is_before_length)
+    }
+
+    /// Returns the byte `i` of a message of `max_length` bytes that is only valid before its length,
+    /// or zero past the length.
+    pub(crate) fn masked_message_byte(
+        &mut self,
+        input: &[Target],
+        is_before_length: &[BoolTarget],
+        i: usize,
+    ) -> Target {
+        if i < input.len() {
+            self.mul(is_before_length[i].target, input[i])
+        } else {
+            self.zero()
+        }
+    }
+}
@@ -8,4 +8,5 @@ pub mod path_compression;
 pub mod poseidon;
 pub mod poseidon_goldilocks;
 pub mod poseidon2;
-pub mod poseidon2_goldilocks;
\ No newline at end of file
+pub mod poseidon2_goldilocks;
+pub mod sparse_merkle_tree;
\ No newline at end of file
@@ -0,0 +1,582 @@
+//! Sparse Merkle trees, which commit to a map from `depth`-bit indices to leaves, most of which are
+//! empty, and can be updated one leaf at a time.
+//!
+//! The empty leaf has no data, so its digest is the zero hash for the algebraic hashers. Since
+//! leaves of at most `NUM_HASH_OUT_ELTS` elements are their own digests, a leaf of that size which
+//! is all zeros is also empty. Circuit leaves have a fixed length, so they carry a flag telling
+//! whether they are empty instead.
+//!
+//! The proofs are ordinary [`MerkleProof`]s, and the circuit gadgets take a mask of active levels
+//! so that a single circuit can handle trees of any depth up to a maximum.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use anyhow::{ensure, Result};
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::field::extension::Extendable;
+use crate::hash::hash_types::{HashOutTarget, RichField, NUM_HASH_OUT_ELTS};
+use crate::hash::hashing::PlonkyPermutation;
+use crate::hash::merkle_proofs::{verify_merkle_proof, MerkleProof, MerkleProofTarget};
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::{AlgebraicHasher, Hasher};
+
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree<F: RichField, H: Hasher<F>> {
+    depth: usize,
+
+    /// The data of the nonempty leaves, by index.
+    leaves: HashMap<usize, Vec<F>>,
+
+    /// The digests of the nonempty subtrees, by height above the leaves and index within their
+    /// layer.
+    digests: HashMap<(usize, usize), H::Hash>,
+
+    /// The digests of the empty subtrees, by height.
+    empty_digests: Vec<H::Hash>,
+}
+
+/// The change of a leaf of a sparse Merkle tree, with the proof of the leaf before the change. The
+/// siblings are not affected by the change, so the proof also holds for the new leaf in the new
+/// tree.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(bound = "")]
+pub struct SparseMerkleUpdate<F: RichField, H: Hasher<F>> {
+    pub index: usize,
+    pub old_leaf: Vec<F>,
+    pub new_leaf: Vec<F>,
+    pub proof: MerkleProof<F, H>,
+}
+
+impl<F: RichField, H: Hasher<F>> SparseMerkleTree<F, H> {
+    /// Returns an empty tree with `2^depth` leaves.
+    pub fn new(depth: usize) -> Self {
+        assert!(depth < usize::BITS as usize, "Depth {} is too large", depth);
+        let mut empty_digests = vec![H::hash_or_noop(&[])];
+        for height in 0..depth {
+            let empty = empty_digests[height];
+            empty_digests.push(H::two_to_one(empty, empty));
+        }
+        Self {
+            depth,
+            leaves: HashMap::new(),
+            digests: HashMap::new(),
+            empty_digests,
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn root(&self) -> H::Hash {
+        self.digest(self.depth, 0)
+    }
+
+    /// Returns the data of the leaf at `index`, which is empty if the leaf was never set.
+    pub fn get(&self, index: usize) -> &[F] {
+        self.check_index(index);
+        self.leaves
+            .get(&index)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns the proof of the leaf at `index`, whether it is empty or not.
+    pub fn prove(&self, index: usize) -> MerkleProof<F, H> {
+        self.check_index(index);
+        let siblings = (0..self.depth)
+            .map(|height| self.digest(height, (index >> height) ^ 1))
+            .collect();
+        MerkleProof { siblings }
+    }
+
+    /// Sets the leaf at `index` to `leaf`, which removes it if it is empty, and returns the
+    /// corresponding update.
+    pub fn update(&mut self, index: usize, leaf: Vec<F>) -> SparseMerkleUpdate<F, H> {
+        let proof = self.prove(index);
+        let old_leaf = self.get(index).to_vec();
+
+        let mut digest = H::hash_or_noop(&leaf);
+        self.set_digest(0, index, digest);
+        if digest == self.empty_digests[0] {
+            self.leaves.remove(&index);
+        } else {
+            self.leaves.insert(index, leaf.clone());
+        }
+        for (height, &sibling) in proof.siblings.iter().enumerate() {
+            digest = if (index >> height) & 1 == 1 {
+                H::two_to_one(sibling, digest)
+            } else {
+                H::two_to_one(digest, sibling)
+            };
+            self.set_digest(height + 1, index >> (height + 1), digest);
+        }
+
+        SparseMerkleUpdate {
+            index,
+            old_leaf,
+            new_leaf: leaf,
+            proof,
+        }
+    }
+
+    /// Applies the given updates in order, and returns them, each proven against the tree left by
+    /// the previous ones.
+    pub fn batch_update(
+        &mut self,
+        updates: impl IntoIterator<Item = (usize, Vec<F>)>,
+    ) -> Vec<SparseMerkleUpdate<F, H>> {
+        updates
+            .into_iter()
+            .map(|(index, leaf)| self.update(index, leaf))
+            .collect()
+    }
+
+    fn digest(&self, height: usize, index: usize) -> H::Hash {
+        self.digests
+            .get(&(height, index))
+            .copied()
+            .unwrap_or(self.empty_digests[height])
+    }
+
+    fn set_digest(&mut self, height: usize, index: usize, digest: H::Hash) {
+        if digest == self.empty_digests[height] {
+            self.digests.remove(&(height, index));
+        } else {
+            self.digests.insert((height, index), digest);
+        }
+    }
+
+    fn check_index(&self, index: usize) {
+        assert!(
+            index >> self.depth == 0,
+            "Index {} out of range for depth {}",
+            index,
+            self.depth
+        );
+    }
+}
+
+/// Verifies that the leaf at the given index is empty in the sparse Merkle tree with the given root.
+pub fn verify_sparse_merkle_non_membership<F: RichField, H: Hasher<F>>(
+    leaf_index: usize,
+    merkle_root: H::Hash,
+    proof: &MerkleProof<F, H>,
+) -> Result<()> {
+    verify_merkle_proof(Vec::new(), leaf_index, merkle_root, proof)
+}
+
+/// Verifies an update against the root of the tree before it, and returns the root after it.
+pub fn verify_sparse_merkle_update<F: RichField, H: Hasher<F>>(
+    old_root: H::Hash,
+    update: &SparseMerkleUpdate<F, H>,
+) -> Result<H::Hash> {
+    verify_merkle_proof(
+        update.old_leaf.clone(),
+        update.index,
+        old_root,
+        &update.proof,
+    )?;
+
+    let mut digest = H::hash_or_noop(&update.new_leaf);
+    for (height, &sibling) in update.proof.siblings.iter().enumerate() {
+        digest = if (update.index >> height) & 1 == 1 {
+            H::two_to_one(sibling, digest)
+        } else {
+            H::two_to_one(digest, sibling)
+        };
+    }
+    Ok(digest)
+}
+
+/// Verifies a sequence of updates, each against the root left by the previous ones, and returns
+/// the final root.
+pub fn verify_sparse_merkle_batch_update<F: RichField, H: Hasher<F>>(
+    old_root: H::Hash,
+    updates: &[SparseMerkleUpdate<F, H>],
+) -> Result<H::Hash> {
+    ensure!(
+        updates
+            .iter()
+            .all(|update| update.proof.len() == updates[0].proof.len()),
+        "Inconsistent tree depths."
+    );
+    updates.iter().try_fold(old_root, |root, update| {
+        verify_sparse_merkle_update(root, update)
+    })
+}
+
+/// A sparse Merkle tree update in a circuit. The proof has one sibling per level of the largest
+/// supported tree, and the index one bit per level. A leaf flagged as empty has the digest of the
+/// empty leaf, and its data must be all zeros.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseMerkleUpdateTarget {
+    pub leaf_index_bits: Vec<BoolTarget>,
+    pub old_leaf: Vec<Target>,
+    pub old_leaf_empty: BoolTarget,
+    pub new_leaf: Vec<Target>,
+    pub new_leaf_empty: BoolTarget,
+    pub proof: MerkleProofTarget,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
+    /// Adds an update of a leaf of `leaf_len` elements, in a tree of depth at most `max_depth`.
+    pub fn add_virtual_sparse_merkle_update(
+        &mut self,
+        max_depth: usize,
+        leaf_len: usize,
+    ) -> SparseMerkleUpdateTarget {
+        SparseMerkleUpdateTarget {
+            leaf_index_bits: (0..max_depth)
+                .map(|_| self.add_virtual_bool_target_safe())
+                .collect(),
+            old_leaf: self.add_virtual_targets(leaf_len),
+            old_leaf_empty: self.add_virtual_bool_target_safe(),
+            new_leaf: self.add_virtual_targets(leaf_len),
+            new_leaf_empty: self.add_virtual_bool_target_safe(),
+            proof: MerkleProofTarget {
+                siblings: self.add_virtual_hashes(max_depth),
+            },
+        }
+    }
+
+    /// Returns the mask of the levels of a tree of the given depth, among `max_depth` levels,
+    /// i.e. flags telling whether `i < depth`. Results in an unsatisfiable instance if
+    /// `depth > max_depth`.
+    pub fn merkle_depth_mask(&mut self, depth: Target, max_depth: usize) -> Vec<BoolTarget> {
+        let (_, mut mask) = self.variable_length_flags(depth, max_depth);
+        mask.truncate(max_depth);
+        mask
+    }
+
+    /// Returns the root of the tree in which the given leaf is at the given index, according to
+    /// the proof. Only the levels set in `depth_mask` are hashed, and they must be the lowest ones,
+    /// which the index must fit in.
+    pub fn merkle_root_variable_depth<H: AlgebraicHasher<F>>(
+        &mut self,
+        leaf_data: Vec<Target>,
+        leaf_index_bits: &[BoolTarget],
+        depth_mask: &[BoolTarget],
+        proof: &MerkleProofTarget,
+    ) -> HashOutTarget {
+        let leaf_digest = self.hash_or_noop::<H>(leaf_data);
+        self.merkle_root_from_digest_variable_depth::<H>(
+            leaf_digest,
+            leaf_index_bits,
+            depth_mask,
+            proof,
+        )
+    }
+
+    /// Same as [`Self::merkle_root_variable_depth`], given the digest of the leaf.
+    fn merkle_root_from_digest_variable_depth<H: AlgebraicHasher<F>>(
+        &mut self,
+        leaf_digest: HashOutTarget,
+        leaf_index_bits: &[BoolTarget],
+        depth_mask: &[BoolTarget],
+        proof: &MerkleProofTarget,
+    ) -> HashOutTarget {
+        assert_eq!(leaf_index_bits.len(), depth_mask.len());
+        assert_eq!(proof.siblings.len(), depth_mask.len());
+        debug_assert!(H::AlgebraicPermutation::RATE >= NUM_HASH_OUT_ELTS);
+
+        // The mask is a prefix of ones, and the index has no bits outside of it.
+        for pair in depth_mask.windows(2) {
+            let (active, next_active) = (pair[0], pair[1]);
+            let both_active = self.and(active, next_active);
+            self.connect(both_active.target, next_active.target);
+        }
+        for (&bit, &active) in leaf_index_bits.iter().zip(depth_mask) {
+            let active_bit = self.and(bit, active);
+            self.connect(active_bit.target, bit.target);
+        }
+
+        let zero = self.zero();
+        let mut state = leaf_digest;
+        for ((&bit, &sibling), &active) in
+            leaf_index_bits.iter().zip(&proof.siblings).zip(depth_mask)
+        {
+            let mut perm_inputs = H::AlgebraicPermutation::default();
+            perm_inputs.set_from_slice(&state.elements, 0);
+            perm_inputs.set_from_slice(&sibling.elements, NUM_HASH_OUT_ELTS);
+            perm_inputs.set_from_iter(core::iter::repeat(zero), 2 * NUM_HASH_OUT_ELTS);
+            let perm_outs = self.permute_swapped::<H>(perm_inputs, bit);
+            let parent = HashOutTarget {
+                elements: perm_outs.squeeze()[0..NUM_HASH_OUT_ELTS]
+                    .try_into()
+                    .unwrap(),
+            };
+            state = self.select_hash(active, parent, state);
+        }
+        state
+    }
+
+    /// Verifies that the given leaf data is present at the given index in the tree with the given
+    /// root, whose depth is given by `depth_mask` as in [`Self::merkle_root_variable_depth`].
+    pub fn verify_merkle_proof_variable_depth<H: AlgebraicHasher<F>>(
+        &mut self,
+        leaf_data: Vec<Target>,
+        leaf_index_bits: &[BoolTarget],
+        depth_mask: &[BoolTarget],
+        merkle_root: HashOutTarget,
+        proof: &MerkleProofTarget,
+    ) {
+        let root =
+            self.merkle_root_variable_depth::<H>(leaf_data, leaf_index_bits, depth_mask, proof);
+        self.connect_hashes(root, merkle_root);
+    }
+
+    /// Verifies that the leaf at the given index is empty in the sparse Merkle tree with the given
+    /// root.
+    pub fn verify_sparse_merkle_non_membership<H: AlgebraicHasher<F>>(
+        &mut self,
+        leaf_index_bits: &[BoolTarget],
+        depth_mask: &[BoolTarget],
+        merkle_root: HashOutTarget,
+        proof: &MerkleProofTarget,
+    ) {
+        self.verify_merkle_proof_variable_depth::<H>(
+            Vec::new(),
+            leaf_index_bits,
+            depth_mask,
+            merkle_root,
+            proof,
+        );
+    }
+
+    /// Verifies an update against the root of the tree before it, and returns the root after it.
+    pub fn sparse_merkle_update<H: AlgebraicHasher<F>>(
+        &mut self,
+        old_root: HashOutTarget,
+        update: &SparseMerkleUpdateTarget,
+        depth_mask: &[BoolTarget],
+    ) -> HashOutTarget {
+        let old_digest =
+            self.sparse_merkle_leaf_digest::<H>(&update.old_leaf, update.old_leaf_empty);
+        let root = self.merkle_root_from_digest_variable_depth::<H>(
+            old_digest,
+            &update.leaf_index_bits,
+            depth_mask,
+            &update.proof,
+        );
+        self.connect_hashes(root, old_root);
+
+        let new_digest =
+            self.sparse_merkle_leaf_digest::<H>(&update.new_leaf, update.new_leaf_empty);
+        self.merkle_root_from_digest_variable_depth::<H>(
+            new_digest,
+            &update.leaf_index_bits,
+            depth_mask,
+            &update.proof,
+        )
+    }
+
+    /// Returns the digest of the given leaf, or of the empty leaf if `is_empty` is set. The latter
+    /// differs from the digest of a leaf of zeros when there are more than `NUM_HASH_OUT_ELTS` of
+    /// them.
+    fn sparse_merkle_leaf_digest<H: AlgebraicHasher<F>>(
+        &mut self,
+        leaf: &[Target],
+        is_empty: BoolTarget,
+    ) -> HashOutTarget {
+        // An empty leaf has a unique representation, so that its data can't be mistaken for that
+        // of the leaf.
+        for &limb in leaf {
+            let masked = self.mul(is_empty.target, limb);
+            self.assert_zero(masked);
+        }
+        let digest = self.hash_or_noop::<H>(leaf.to_vec());
+        let empty_digest = self.hash_or_noop::<H>(Vec::new());
+        self.select_hash(is_empty, empty_digest, digest)
+    }
+
+    /// Verifies a sequence of updates, each against the root left by the previous ones, and
+    /// returns the final root.
+    pub fn sparse_merkle_batch_update<H: AlgebraicHasher<F>>(
+        &mut self,
+        old_root: HashOutTarget,
+        updates: &[SparseMerkleUpdateTarget],
+        depth_mask: &[BoolTarget],
+    ) -> HashOutTarget {
+        updates.iter().fold(old_root, |root, update| {
+            self.sparse_merkle_update::<H>(root, update, depth_mask)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use rand::rngs::OsRng;
+    use rand::Rng;
+
+    use super::*;
+    use crate::field::types::{Field, Sample};
+    use crate::hash::hash_types::HashOut;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::verifier::verify;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type H = <C as GenericConfig<D>>::Hasher;
+
+    #[test]
+    fn test_sparse_merkle_tree() -> Result<()> {
+        let depth = 20;
+        let mut tree = SparseMerkleTree::<F, H>::new(depth);
+        let empty_root = tree.root();
+
+        let indices = (0..10)
+            .map(|_| OsRng.gen_range(0..1 << depth))
+            .collect::<Vec<usize>>();
+        let updates = tree.batch_update(indices.iter().map(|&i| (i, F::rand_vec(7))));
+        let root = verify_sparse_merkle_batch_update(empty_root, &updates)?;
+        assert_eq!(root, tree.root());
+
+        for &i in &indices {
+            verify_merkle_proof(tree.get(i).to_vec(), i, root, &tree.prove(i))?;
+        }
+        let absent = (0..1 << depth).find(|i| !indices.contains(i)).unwrap();
+        verify_sparse_merkle_non_membership(absent, root, &tree.prove(absent))?;
+        assert!(
+            verify_sparse_merkle_non_membership(indices[0], root, &tree.prove(indices[0])).is_err()
+        );
+
+        // Removing all the leaves gives back the empty tree.
+        tree.batch_update(indices.iter().map(|&i| (i, Vec::new())));
+        assert_eq!(tree.root(), empty_root);
+        assert!(tree.digests.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sparse_merkle_batch_update_circuit() -> Result<()> {
+        test_sparse_merkle_batch_update_circuit_with_leaf_len(NUM_HASH_OUT_ELTS)
+    }
+
+    #[test]
+    fn test_sparse_merkle_batch_update_circuit_long_leaves() -> Result<()> {
+        // Leaves are hashed, so the empty leaf is not a leaf of zeros.
+        test_sparse_merkle_batch_update_circuit_with_leaf_len(NUM_HASH_OUT_ELTS + 3)
+    }
+
+    fn test_sparse_merkle_batch_update_circuit_with_leaf_len(leaf_len: usize) -> Result<()> {
+        let max_depth = 12;
+        let depth = 8;
+
+        let mut tree = SparseMerkleTree::<F, H>::new(depth);
+        tree.batch_update((0..5).map(|i| (3 * i, F::rand_vec(leaf_len))));
+        let old_root = tree.root();
+        // An insertion, a change and a removal.
+        let updates = tree.batch_update([
+            (100, F::rand_vec(leaf_len)),
+            (3, F::rand_vec(leaf_len)),
+            (6, Vec::new()),
+        ]);
+        let new_root = tree.root();
+        let absent = 101;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let depth_t = builder.constant(F::from_canonical_usize(depth));
+        let depth_mask = builder.merkle_depth_mask(depth_t, max_depth);
+        let old_root_t = builder.add_virtual_hash();
+        pw.set_hash_target(old_root_t, old_root);
+
+        let mut updates_t = Vec::new();
+        for update in &updates {
+            let update_t = builder.add_virtual_sparse_merkle_update(max_depth, leaf_len);
+            set_update_target(&mut pw, &update_t, update);
+            updates_t.push(update_t);
+        }
+        let new_root_t =
+            builder.sparse_merkle_batch_update::<H>(old_root_t, &updates_t, &depth_mask);
+        let expected_new_root = builder.constant_hash(new_root);
+        builder.connect_hashes(new_root_t, expected_new_root);
+
+        let absent_t = builder.add_virtual_sparse_merkle_update(max_depth, 0);
+        let absent_update = SparseMerkleUpdate {
+            index: absent,
+            old_leaf: Vec::new(),
+            new_leaf: Vec::new(),
+            proof: tree.prove(absent),
+        };
+        set_update_target(&mut pw, &absent_t, &absent_update);
+        builder.verify_sparse_merkle_non_membership::<H>(
+            &absent_t.leaf_index_bits,
+            &depth_mask,
+            new_root_t,
+            &absent_t.proof,
+        );
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+        verify(proof, &data.verifier_only, &data.common)
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sparse_merkle_update_circuit_nonzero_empty_leaf() {
+        let max_depth = 12;
+        let depth = 8;
+        let leaf_len = NUM_HASH_OUT_ELTS + 3;
+
+        let mut tree = SparseMerkleTree::<F, H>::new(depth);
+        tree.update(3, F::rand_vec(leaf_len));
+        let old_root = tree.root();
+        let mut update = tree.update(3, Vec::new());
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut pw = PartialWitness::new();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let depth_t = builder.constant(F::from_canonical_usize(depth));
+        let depth_mask = builder.merkle_depth_mask(depth_t, max_depth);
+        let old_root_t = builder.constant_hash(old_root);
+        let mut update_t = builder.add_virtual_sparse_merkle_update(max_depth, leaf_len);
+        // The new leaf carries data, but is flagged as empty.
+        update.new_leaf = F::rand_vec(leaf_len);
+        set_update_target(&mut pw, &update_t, &update);
+        update_t.new_leaf_empty = builder._true();
+        let new_root_t = builder.sparse_merkle_update::<H>(old_root_t, &update_t, &depth_mask);
+        let expected_new_root = builder.constant_hash(tree.root());
+        builder.connect_hashes(new_root_t, expected_new_root);
+
+        let data = builder.build::<C>();
+        data.prove(pw).unwrap();
+    }
+
+    /// Sets the update target, padding the leaves with zeros and the proof with zero siblings.
+    /// Empty leaves are flagged as such.
+    fn set_update_target(
+        pw: &mut PartialWitness<F>,
+        target: &SparseMerkleUpdateTarget,
+        update: &SparseMerkleUpdate<F, H>,
+    ) {
+        for (i, &bit) in target.leaf_index_bits.iter().enumerate() {
+            pw.set_bool_target(bit, (update.index >> i) & 1 == 1);
+        }
+        for (targets, is_empty, leaf) in [
+            (&target.old_leaf, target.old_leaf_empty, &update.old_leaf),
+            (&target.new_leaf, target.new_leaf_empty, &update.new_leaf),
+        ] {
+            pw.set_bool_target(is_empty, leaf.is_empty());
+            for (i, &t) in targets.iter().enumerate() {
+                pw.set_target(t, leaf.get(i).copied().unwrap_or(F::ZERO));
+            }
+        }
+        for (i, &sibling) in target.proof.siblings.iter().enumerate() {
+            let value = update.proof.siblings.get(i).copied();
+            pw.set_hash_target(sibling, value.unwrap_or(HashOut::ZERO));
+        }
+    }
+}
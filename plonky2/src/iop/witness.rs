@@ -3,11 +3,21 @@ use alloc::vec::Vec;
 
 use hashbrown::HashMap;
 use itertools::{zip_eq, Itertools};
+use num::BigUint;
 
+use crate::curve::curve_types::{AffinePoint, Curve};
+use crate::curve::ecgfp5::EcGFp5Point;
+use crate::field::extension::quintic::QuinticExtension;
 use crate::field::extension::{Extendable, FieldExtension};
-use crate::field::types::Field;
+use crate::field::goldilocks_field::GoldilocksField;
+use crate::field::types::{Field, PrimeField};
 use crate::fri::structure::{FriOpenings, FriOpeningsTarget};
 use crate::fri::witness_util::set_fri_proof_target;
+use crate::gadgets::biguint::BigUintTarget;
+use crate::gadgets::curve::AffinePointTarget;
+use crate::gadgets::ecgfp5::EcGFp5PointTarget;
+use crate::gadgets::nonnative::{biguint_from_u16_limbs, u16_limbs_of, NonNativeTarget};
+use crate::gadgets::quintic::QuinticExtensionTarget;
 use crate::hash::hash_types::{HashOut, HashOutTarget, MerkleCapTarget, RichField};
 use crate::hash::merkle_tree::MerkleCap;
 use crate::iop::ext_target::ExtensionTarget;
@@ -67,6 +77,47 @@ pub trait WitnessWrite<F: Field> {
         self.set_target(target.target, F::from_bool(value))
     }
 
+    fn set_nonnative_target<FF: PrimeField>(&mut self, target: &NonNativeTarget<FF>, value: FF) {
+        let limbs = u16_limbs_of(&value.to_canonical_biguint(), target.limbs.len());
+        self.set_target_arr(&target.limbs, &limbs);
+    }
+
+    fn set_biguint_target(&mut self, target: &BigUintTarget, value: &BigUint) {
+        self.set_target_arr(&target.limbs, &u16_limbs_of(value, target.limbs.len()));
+    }
+
+    fn set_affine_point_target<C: Curve>(
+        &mut self,
+        target: &AffinePointTarget<C>,
+        value: AffinePoint<C>,
+    ) {
+        assert!(
+            !value.zero,
+            "The point at infinity has no affine coordinates"
+        );
+        self.set_nonnative_target(&target.x, value.x);
+        self.set_nonnative_target(&target.y, value.y);
+    }
+
+    fn set_quintic_extension_target(
+        &mut self,
+        target: QuinticExtensionTarget,
+        value: QuinticExtension<GoldilocksField>,
+    ) {
+        for (t, v) in target.0.into_iter().zip(value.0) {
+            self.set_target(t, F::from_canonical_u64(v.0));
+        }
+    }
+
+    fn set_ecgfp5_point_target(&mut self, target: &EcGFp5PointTarget, value: EcGFp5Point) {
+        assert!(
+            !value.zero,
+            "The point at infinity has no affine coordinates"
+        );
+        self.set_quintic_extension_target(target.x, value.x);
+        self.set_quintic_extension_target(target.y, value.y);
+    }
+
     /// Set the targets in a `ProofWithPublicInputsTarget` to their corresponding values in a
     /// `ProofWithPublicInputs`.
     fn set_proof_with_pis_target<C: GenericConfig<D, F = F>, const D: usize>(
@@ -216,6 +267,34 @@ pub trait Witness<F: Field>: WitnessWrite<F> {
         panic!("not a bool")
     }
 
+    fn get_nonnative_target<FF: PrimeField>(&self, target: &NonNativeTarget<FF>) -> FF
+    where
+        F: RichField,
+    {
+        FF::from_noncanonical_biguint(biguint_from_u16_limbs(&self.get_targets(&target.limbs)))
+    }
+
+    fn get_biguint_target(&self, target: &BigUintTarget) -> BigUint
+    where
+        F: RichField,
+    {
+        biguint_from_u16_limbs(&self.get_targets(&target.limbs))
+    }
+
+    fn get_quintic_extension_target(
+        &self,
+        target: QuinticExtensionTarget,
+    ) -> QuinticExtension<GoldilocksField>
+    where
+        F: RichField,
+    {
+        QuinticExtension(
+            target.0.map(|t| {
+                GoldilocksField::from_canonical_u64(self.get_target(t).to_canonical_u64())
+            }),
+        )
+    }
+
     fn get_hash_target(&self, ht: HashOutTarget) -> HashOut<F> {
         HashOut {
             elements: self.get_targets(&ht.elements).try_into().unwrap(),
@@ -7,6 +7,7 @@ extern crate alloc;
 #[doc(inline)]
 pub use plonky2_field as field;
 
+pub mod curve;
 pub mod fri;
 pub mod gadgets;
 pub mod gates;
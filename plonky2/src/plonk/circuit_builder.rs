@@ -135,6 +135,9 @@ pub struct CircuitBuilder<F: RichField + Extendable<D>, const D: usize> {
     // Lookup tables in the form of `Vec<(input_value, output_value)>`.
     luts: Vec<LookupTable>,
 
+    /// Indices in `luts` of the tables added with `get_or_add_named_lut`, by name.
+    named_luts: HashMap<&'static str, usize>,
+
     /// Optional common data. When it is `Some(goal_data)`, the `build` function panics if the resulting
     /// common data doesn't equal `goal_data`.
     /// This is used in cyclic recursion.
@@ -166,6 +169,7 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
             lookup_rows: Vec::new(),
             lut_to_lookups: Vec::new(),
             luts: Vec::new(),
+            named_luts: HashMap::new(),
             goal_common_data: None,
             verifier_data_public_input: None,
         };
@@ -665,6 +669,21 @@ impl<F: RichField + Extendable<D>, const D: usize> CircuitBuilder<F, D> {
         }
     }
 
+    /// Returns the index of the LUT registered under `name`, building it with `table` and adding it
+    /// on first use. Unlike the `update_luts_*` methods, this does not compare the table against
+    /// every stored LUT, which matters for large tables looked up many times.
+    pub fn get_or_add_named_lut<T>(&mut self, name: &'static str, table: T) -> usize
+    where
+        T: FnOnce() -> LookupTable,
+    {
+        if let Some(&idx) = self.named_luts.get(name) {
+            return idx;
+        }
+        let idx = self.update_luts_from_pairs(table());
+        self.named_luts.insert(name, idx);
+        idx
+    }
+
     /// Find an available slot, of the form `(row, op)` for gate `G` using parameters `params`
     /// and constants `constants`. Parameters are any data used to differentiate which gate should be
     /// used for the given operation.
@@ -94,7 +94,19 @@ pub mod default {
 
     use crate::gadgets::arithmetic::EqualityGenerator;
     use crate::gadgets::arithmetic_extension::QuotientGeneratorExtension;
-    use crate::gadgets::range_check::LowHighGenerator;
+    use crate::gadgets::biguint::{
+        BigUintAdditionGenerator, BigUintDivRemGenerator, BigUintMultiplicationGenerator,
+        BigUintSubtractionGenerator,
+    };
+    use crate::gadgets::ecdsa::Secp256K1LiftXGenerator;
+    use crate::gadgets::glv::GLVDecompositionGenerator;
+    use crate::gadgets::nonnative::{
+        LimbCarryGenerator, LimbComparisonGenerator, NonNativeAdditionGenerator,
+        NonNativeDivisionGenerator, NonNativeInverseGenerator, NonNativeReductionGenerator,
+        NonNativeSubtractionGenerator,
+    };
+    use crate::gadgets::quintic::{QuinticInverseGenerator, QuinticSqrtGenerator};
+    use crate::gadgets::range_check::{LowHighGenerator, U16LimbsGenerator, U8LimbsGenerator};
     use crate::gadgets::split_base::BaseSumGenerator;
     use crate::gadgets::split_join::{SplitGenerator, WireSplitGenerator};
     use crate::gates::arithmetic_base::ArithmeticBaseGenerator;
@@ -134,25 +146,42 @@ pub mod default {
             ArithmeticExtensionGenerator<F, D>,
             BaseSplitGenerator<2>,
             BaseSumGenerator<2>,
+            BigUintAdditionGenerator,
+            BigUintDivRemGenerator,
+            BigUintMultiplicationGenerator,
+            BigUintSubtractionGenerator,
             ConstantGenerator<F>,
             CopyGenerator,
             DummyProofGenerator<F, C, D>,
             EqualityGenerator,
             ExponentiationGenerator<F, D>,
+            GLVDecompositionGenerator,
             InterpolationGenerator<F, D>,
+            LimbCarryGenerator,
+            LimbComparisonGenerator,
             LookupGenerator,
             LookupTableGenerator,
             LowHighGenerator,
             MulExtensionGenerator<F, D>,
+            NonNativeAdditionGenerator,
+            NonNativeDivisionGenerator,
+            NonNativeInverseGenerator,
+            NonNativeReductionGenerator,
+            NonNativeSubtractionGenerator,
             NonzeroTestGenerator,
             PoseidonGenerator<F, D>,
             PoseidonMdsGenerator<D>,
+            QuinticInverseGenerator,
+            QuinticSqrtGenerator,
             QuotientGeneratorExtension<D>,
             RandomAccessGenerator<F, D>,
             RandomValueGenerator,
             ReducingGenerator<D>,
             ReducingExtensionGenerator<D>,
+            Secp256K1LiftXGenerator,
             SplitGenerator,
+            U16LimbsGenerator,
+            U8LimbsGenerator,
             WireSplitGenerator
         }
     }
@@ -120,6 +120,26 @@ impl TimingTree {
     #[cfg(not(feature = "timing"))]
     pub fn pop(&mut self) {}
 
+    /// Add a closed tree, e.g. one built by a concurrent task, as a child of the deepest open scope
+    /// from this tree.
+    #[cfg(feature = "timing")]
+    pub fn add_child(&mut self, child: TimingTree) {
+        assert!(self.is_open());
+        assert!(!child.is_open());
+
+        if let Some(last_child) = self.children.last_mut() {
+            if last_child.is_open() {
+                last_child.add_child(child);
+                return;
+            }
+        }
+
+        self.children.push(child);
+    }
+
+    #[cfg(not(feature = "timing"))]
+    pub fn add_child(&mut self, _child: TimingTree) {}
+
     #[cfg(feature = "timing")]
     fn duration(&self) -> Duration {
         self.exit_time